@@ -25,7 +25,7 @@ use node_runtime::{
 	AuthorityDiscoveryConfig, BabeConfig, BalancesConfig, ContractsConfig, CouncilConfig,
 	DemocracyConfig,GrandpaConfig, ImOnlineConfig, SessionConfig, SessionKeys, StakerStatus,
 	StakingConfig, ElectionsConfig, IndicesConfig, SocietyConfig, SudoConfig, SystemConfig,
-	TechnicalCommitteeConfig, wasm_binary_unwrap,
+	TechnicalCommitteeConfig, SubswapAssetConfig, SubswapConfig, wasm_binary_unwrap,
 };
 use node_runtime::Block;
 use node_runtime::constants::currency::*;
@@ -204,6 +204,24 @@ pub fn authority_keys_from_seed(seed: &str) -> (
 	)
 }
 
+/// The accounts endowed by default on development chains.
+pub fn well_known_accounts() -> Vec<AccountId> {
+	vec![
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		get_account_id_from_seed::<sr25519::Public>("Bob"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie"),
+		get_account_id_from_seed::<sr25519::Public>("Dave"),
+		get_account_id_from_seed::<sr25519::Public>("Eve"),
+		get_account_id_from_seed::<sr25519::Public>("Ferdie"),
+		get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+	]
+}
+
 /// Helper function to create GenesisConfig for testing
 pub fn testnet_genesis(
 	initial_authorities: Vec<(
@@ -218,22 +236,7 @@ pub fn testnet_genesis(
 	endowed_accounts: Option<Vec<AccountId>>,
 	enable_println: bool,
 ) -> GenesisConfig {
-	let endowed_accounts: Vec<AccountId> = endowed_accounts.unwrap_or_else(|| {
-		vec![
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
-			get_account_id_from_seed::<sr25519::Public>("Bob"),
-			get_account_id_from_seed::<sr25519::Public>("Charlie"),
-			get_account_id_from_seed::<sr25519::Public>("Dave"),
-			get_account_id_from_seed::<sr25519::Public>("Eve"),
-			get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-		]
-	});
+	let endowed_accounts: Vec<AccountId> = endowed_accounts.unwrap_or_else(well_known_accounts);
 	let num_endowed_accounts = endowed_accounts.len();
 
 	const ENDOWMENT: Balance = 10_000_000 * DOLLARS;
@@ -321,18 +324,64 @@ pub fn testnet_genesis(
 			max_members: 999,
 		}),
 		pallet_vesting: Some(Default::default()),
+		subswap_asset: Some(Default::default()),
+		subswap: Some(Default::default()),
 	}
 }
 
+/// Assets and pairs for development chains: USDT and DOT held by the well known accounts, and
+/// pairs of both with the native currency seeded by `provider`.
+pub fn subswap_testnet_genesis(
+	provider: AccountId,
+	holders: Vec<AccountId>,
+) -> (SubswapAssetConfig, SubswapConfig) {
+	const NATIVE: u32 = 0;
+	const USDT: u32 = 1;
+	const DOT: u32 = 2;
+	const USDT_UNIT: Balance = 1_000_000;
+	const DOT_UNIT: Balance = 10_000_000_000;
+	const HOLDING: Balance = 1_000_000;
+
+	let assets = SubswapAssetConfig {
+		assets: vec![
+			(USDT, provider.clone(), b"Tether USD".to_vec(), b"USDT".to_vec(), 6),
+			(DOT, provider.clone(), b"Polkadot".to_vec(), b"DOT".to_vec(), 10),
+		],
+		balances: holders.iter()
+			.flat_map(|who| vec![
+				(USDT, who.clone(), HOLDING * USDT_UNIT),
+				(DOT, who.clone(), HOLDING * DOT_UNIT),
+			])
+			.collect(),
+	};
+	// 1 SUB is worth 2 USDT and 0.5 DOT
+	let pairs = SubswapConfig {
+		pairs: vec![
+			(NATIVE, USDT, vec![(provider.clone(), 100_000 * DOLLARS, 200_000 * USDT_UNIT)]),
+			(NATIVE, DOT, vec![(provider, 100_000 * DOLLARS, 50_000 * DOT_UNIT)]),
+		],
+	};
+	(assets, pairs)
+}
+
 fn development_config_genesis() -> GenesisConfig {
-	testnet_genesis(
+	let genesis = testnet_genesis(
 		vec![
 			authority_keys_from_seed("Alice"),
 		],
 		get_account_id_from_seed::<sr25519::Public>("Alice"),
 		None,
 		true,
-	)
+	);
+	let (subswap_asset, subswap) = subswap_testnet_genesis(
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		well_known_accounts(),
+	);
+	GenesisConfig {
+		subswap_asset: Some(subswap_asset),
+		subswap: Some(subswap),
+		..genesis
+	}
 }
 
 /// Development config (single validator Alice)
@@ -351,7 +400,7 @@ pub fn development_config() -> ChainSpec {
 }
 
 fn local_testnet_genesis() -> GenesisConfig {
-	testnet_genesis(
+	let genesis = testnet_genesis(
 		vec![
 			authority_keys_from_seed("Alice"),
 			authority_keys_from_seed("Bob"),
@@ -359,7 +408,16 @@ fn local_testnet_genesis() -> GenesisConfig {
 		get_account_id_from_seed::<sr25519::Public>("Alice"),
 		None,
 		false,
-	)
+	);
+	let (subswap_asset, subswap) = subswap_testnet_genesis(
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		well_known_accounts(),
+	);
+	GenesisConfig {
+		subswap_asset: Some(subswap_asset),
+		subswap: Some(subswap),
+		..genesis
+	}
 }
 
 /// Local testnet config (multivalidator Alice + Bob)
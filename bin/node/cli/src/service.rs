@@ -638,7 +638,7 @@ mod tests {
 				let check_era = frame_system::CheckEra::from(Era::Immortal);
 				let check_nonce = frame_system::CheckNonce::from(index);
 				let check_weight = frame_system::CheckWeight::new();
-				let payment = pallet_transaction_payment::ChargeTransactionPayment::from(0);
+				let payment = subswap_payment::ChargeAssetTxPayment::from(0, None, 0);
 				let extra = (
 					check_spec_version,
 					check_tx_version,
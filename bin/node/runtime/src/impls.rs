@@ -17,10 +17,13 @@
 
 //! Some configurable implementations as associated type for the substrate runtime.
 
-use node_primitives::Balance;
-use sp_runtime::traits::Convert;
-use frame_support::traits::{OnUnbalanced, Currency};
-use crate::{Balances, Authorship, NegativeImbalance};
+use codec::{Encode, Decode};
+use node_primitives::{AccountId, Balance};
+use sp_runtime::{RuntimeDebug, traits::Convert};
+use frame_support::{dispatch::DispatchResult, traits::{OnUnbalanced, Currency}, weights::Weight};
+use pallet_atomic_swap::BalanceSwapAction;
+use subswap_asset::AssetSwapAction;
+use crate::{Balances, Authorship, NegativeImbalance, Runtime};
 
 pub struct Author;
 impl OnUnbalanced<NegativeImbalance> for Author {
@@ -45,6 +48,46 @@ impl Convert<u128, Balance> for CurrencyToVoteHandler {
 	fn convert(x: u128) -> Balance { x * Self::factor() }
 }
 
+/// The action of an atomic swap, moving either the native currency through the balances module or
+/// an asset of the subswap ledger, so that a single atomic swap module trades both.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub enum SwapAction {
+	/// Move the native currency.
+	Balance(BalanceSwapAction<AccountId, Balances>),
+	/// Move an asset of the subswap ledger.
+	Asset(AssetSwapAction<u32, Balance>),
+}
+
+impl pallet_atomic_swap::SwapAction<AccountId, Runtime> for SwapAction {
+	fn reserve(&self, source: &AccountId) -> DispatchResult {
+		match self {
+			SwapAction::Balance(action) => pallet_atomic_swap::SwapAction::<AccountId, Runtime>::reserve(action, source),
+			SwapAction::Asset(action) => pallet_atomic_swap::SwapAction::<AccountId, Runtime>::reserve(action, source),
+		}
+	}
+
+	fn claim(&self, source: &AccountId, target: &AccountId) -> bool {
+		match self {
+			SwapAction::Balance(action) => pallet_atomic_swap::SwapAction::<AccountId, Runtime>::claim(action, source, target),
+			SwapAction::Asset(action) => pallet_atomic_swap::SwapAction::<AccountId, Runtime>::claim(action, source, target),
+		}
+	}
+
+	fn weight(&self) -> Weight {
+		match self {
+			SwapAction::Balance(action) => pallet_atomic_swap::SwapAction::<AccountId, Runtime>::weight(action),
+			SwapAction::Asset(action) => pallet_atomic_swap::SwapAction::<AccountId, Runtime>::weight(action),
+		}
+	}
+
+	fn cancel(&self, source: &AccountId) {
+		match self {
+			SwapAction::Balance(action) => pallet_atomic_swap::SwapAction::<AccountId, Runtime>::cancel(action, source),
+			SwapAction::Asset(action) => pallet_atomic_swap::SwapAction::<AccountId, Runtime>::cancel(action, source),
+		}
+	}
+}
+
 #[cfg(test)]
 mod multiplier_tests {
 	use super::*;
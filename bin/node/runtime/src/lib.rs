@@ -78,15 +78,18 @@ pub use pallet_staking::StakerStatus;
 
 /// Implementations of some helper traits passed into runtime modules as associated types.
 pub mod impls;
-use impls::{CurrencyToVoteHandler, Author};
+use impls::{CurrencyToVoteHandler, Author, SwapAction};
 
 /// Constant values used within the runtime.
 pub mod constants;
 use constants::{time::*, currency::*};
 use sp_runtime::generic::Era;
 
-/// Import Subswap module
+/// Import Subswap modules
 pub use subswap;
+pub use subswap_asset;
+use subswap_asset::MultiAsset;
+use subswap_runtime_api::{Quote, PairInfo, LiquidityValue, AssetBalance};
 
 /// Weights for pallets used in the runtime.
 mod weights;
@@ -111,10 +114,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 259,
-	impl_version: 1,
+	spec_version: 260,
+	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 4,
 };
 
 /// Native version.
@@ -336,9 +339,82 @@ impl pallet_indices::Trait for Runtime {
 	type WeightInfo = weights::pallet_indices::WeightInfo<Runtime>;
 }
 
-impl subswap::Trait for Runtime {
+parameter_types! {
+	pub const SubswapAssetModuleId: ModuleId = ModuleId(*b"py/subas");
+	pub const MetadataDepositBase: Balance = 10 * DOLLARS;
+	pub const MetadataDepositPerByte: Balance = 1 * DOLLARS;
+	pub const AssetStringLimit: u32 = 50;
+	pub const NativeSymbol: &'static [u8] = b"SUB";
+	pub const NativeDecimals: u8 = 14;
+}
+
+impl subswap_asset::Trait for Runtime {
 	type Event = Event;
 	type AssetId = u32;
+	type ModuleId = SubswapAssetModuleId;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type StringLimit = AssetStringLimit;
+	type NativeSymbol = NativeSymbol;
+	type NativeDecimals = NativeDecimals;
+	// A passed referendum dispatches as root.
+	type VerifyOrigin = EnsureRootOrHalfCouncil;
+	type WeightInfo = weights::subswap_asset::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const SubswapModuleId: ModuleId = ModuleId(*b"py/subsw");
+	// Hourly price snapshots, enough to consult a day long average.
+	pub const OracleSnapshotPeriod: Moment = HOURS as Moment * MILLISECS_PER_BLOCK;
+	pub const OracleSnapshotCount: u32 = 25;
+	// 0.3% of the input amount of every swap.
+	pub const SubswapDefaultFee: u32 = 30;
+	pub const MaxOpenOrders: u32 = 1_000;
+	pub const MaxOrdersPerAccount: u32 = 16;
+	pub const MaxOrdersPerBlock: u32 = 50;
+	pub const MaxInitializedTicks: u32 = 1_000;
+}
+
+impl subswap::Trait for Runtime {
+	type Event = Event;
+	type Assets = SubswapAsset;
+	type ModuleId = SubswapModuleId;
+	type OracleSnapshotPeriod = OracleSnapshotPeriod;
+	type OracleSnapshotCount = OracleSnapshotCount;
+	type DefaultFee = SubswapDefaultFee;
+	type GovernanceOrigin = EnsureRootOrHalfCouncil;
+	type Call = Call;
+	type MaxOpenOrders = MaxOpenOrders;
+	type MaxOrdersPerAccount = MaxOrdersPerAccount;
+	type MaxOrdersPerBlock = MaxOrdersPerBlock;
+	type MaxInitializedTicks = MaxInitializedTicks;
+	type WeightInfo = weights::subswap::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const AtomicSwapProofLimit: u32 = 1024;
+}
+
+impl pallet_atomic_swap::Trait for Runtime {
+	type Event = Event;
+	// Swaps either the native currency or an asset of the subswap ledger.
+	type SwapAction = SwapAction;
+	type ProofLimit = AtomicSwapProofLimit;
+}
+
+/// The pair behind the liquidity provider token `lpt`, as reported by the subswap runtime API.
+fn subswap_pair_info(lpt: u32) -> PairInfo<u32, Balance> {
+	let (token0, token1) = Subswap::reward(lpt);
+	let (reserve0, reserve1) = Subswap::reserves(lpt);
+	PairInfo {
+		lp_token: lpt,
+		token0,
+		token1,
+		reserve0,
+		reserve1,
+		fee: Subswap::fee(lpt),
+		total_liquidity: <SubswapAsset as MultiAsset<AccountId>>::total_issuance(lpt),
+	}
 }
 
 parameter_types! {
@@ -731,7 +807,7 @@ impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for R
 			frame_system::CheckEra::<Runtime>::from(era),
 			frame_system::CheckNonce::<Runtime>::from(nonce),
 			frame_system::CheckWeight::<Runtime>::new(),
-			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+			subswap_payment::ChargeAssetTxPayment::<Runtime>::from(tip, None, 0),
 		);
 		let raw_payload = SignedPayload::new(call, extra)
 			.map_err(|e| {
@@ -932,7 +1008,9 @@ construct_runtime!(
 		Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
 		Proxy: pallet_proxy::{Module, Call, Storage, Event<T>},
 		Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
-		Subswap: subswap::{Module, Call, Storage, Event<T>},
+		SubswapAsset: subswap_asset::{Module, Call, Storage, Config<T>, Event<T>},
+		Subswap: subswap::{Module, Call, Storage, Config<T>, Event<T>},
+		AtomicSwap: pallet_atomic_swap::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -958,7 +1036,7 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	subswap_payment::ChargeAssetTxPayment<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -1161,6 +1239,46 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl subswap_runtime_api::SubswapApi<
+		Block,
+		AccountId,
+		u32,
+		Balance,
+	> for Runtime {
+		fn quote_exact_in(path: Vec<u32>, amount_in: Balance) -> Option<Quote<Balance>> {
+			Subswap::get_amounts_out(&amount_in, &path).ok().map(|amounts| Quote { amounts })
+		}
+
+		fn quote_exact_out(path: Vec<u32>, amount_out: Balance) -> Option<Quote<Balance>> {
+			Subswap::get_amounts_in(&amount_out, &path).ok().map(|amounts| Quote { amounts })
+		}
+
+		fn get_reserves(asset_a: u32, asset_b: u32) -> Option<PairInfo<u32, Balance>> {
+			Subswap::pair((asset_a, asset_b)).map(subswap_pair_info)
+		}
+
+		fn list_pairs() -> Vec<PairInfo<u32, Balance>> {
+			Subswap::pairs().into_iter().map(|(lpt, _)| subswap_pair_info(lpt)).collect()
+		}
+
+		fn lp_share_value(lp_token: u32, amount: Balance) -> Option<LiquidityValue<u32, Balance>> {
+			let (token0, token1) = Subswap::reward(lp_token);
+			Subswap::lp_share_value(&lp_token, &amount).ok().map(|(amount0, amount1)| LiquidityValue {
+				token0,
+				amount0,
+				token1,
+				amount1,
+			})
+		}
+
+		fn balance_of(asset_id: u32, who: AccountId) -> AssetBalance<Balance> {
+			AssetBalance {
+				free: <SubswapAsset as MultiAsset<AccountId>>::balance(asset_id, &who),
+				reserved: <SubswapAsset as MultiAsset<AccountId>>::reserved_balance(asset_id, &who),
+			}
+		}
+	}
+
 	impl sp_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			SessionKeys::generate(seed)
@@ -1229,6 +1347,8 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_treasury, Treasury);
 			add_benchmark!(params, batches, pallet_utility, Utility);
 			add_benchmark!(params, batches, pallet_vesting, Vesting);
+			add_benchmark!(params, batches, subswap, Subswap);
+			add_benchmark!(params, batches, subswap_asset, SubswapAsset);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
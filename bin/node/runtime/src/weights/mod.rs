@@ -32,3 +32,5 @@ pub mod pallet_timestamp;
 pub mod pallet_treasury;
 pub mod pallet_utility;
 pub mod pallet_vesting;
+pub mod subswap;
+pub mod subswap_asset;
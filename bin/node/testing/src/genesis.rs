@@ -119,5 +119,7 @@ pub fn config_endowed(
 			max_members: 999,
 		}),
 		pallet_vesting: Some(Default::default()),
+		subswap_asset: Some(Default::default()),
+		subswap: Some(Default::default()),
 	}
 }
@@ -69,6 +69,17 @@ pub fn to_session_keys(
 
 /// Returns transaction extra.
 pub fn signed_extra(nonce: Index, extra_fee: Balance) -> SignedExtra {
+	signed_extra_in_asset(nonce, extra_fee, None, 0)
+}
+
+/// Returns transaction extra paying the fee in the subswap asset `asset_id`, swapping at most
+/// `max_asset_fee` of it for the native currency, or in the native currency if `None`.
+pub fn signed_extra_in_asset(
+	nonce: Index,
+	extra_fee: Balance,
+	asset_id: Option<u32>,
+	max_asset_fee: Balance,
+) -> SignedExtra {
 	(
 		frame_system::CheckSpecVersion::new(),
 		frame_system::CheckTxVersion::new(),
@@ -76,7 +87,7 @@ pub fn signed_extra(nonce: Index, extra_fee: Balance) -> SignedExtra {
 		frame_system::CheckEra::from(Era::mortal(256, 0)),
 		frame_system::CheckNonce::from(nonce),
 		frame_system::CheckWeight::new(),
-		pallet_transaction_payment::ChargeTransactionPayment::from(extra_fee),
+		subswap_payment::ChargeAssetTxPayment::from(extra_fee, asset_id, max_asset_fee),
 	)
 }
 
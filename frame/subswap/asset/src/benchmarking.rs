@@ -0,0 +1,303 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Subswap asset module benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_support::traits::UnfilteredDispatchable;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+use sp_runtime::traits::Bounded;
+
+use crate::Module as Assets;
+
+const SEED: u32 = 0;
+const SUPPLY: u32 = 1_000_000;
+
+/// Issue a new asset with a supply of `SUPPLY` and a minimum balance of one unit to `owner`,
+/// returning its identifier.
+fn issue_asset<T: Trait>(owner: &T::AccountId) -> Result<T::AssetId, &'static str> {
+	Assets::<T>::issue(RawOrigin::Signed(owner.clone()).into(), SUPPLY.into(), One::one())?;
+	Ok(Assets::<T>::next_asset_id() - One::one())
+}
+
+benchmarks! {
+	_ { }
+
+	issue {
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller.clone()), SUPPLY.into(), One::one())
+	verify {
+		let id = Assets::<T>::next_asset_id() - One::one();
+		assert_eq!(Assets::<T>::balance(id, caller), SUPPLY.into());
+		assert_eq!(Assets::<T>::min_balance(id), One::one());
+	}
+
+	mint {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(RawOrigin::Signed(caller), id, target_lookup, SUPPLY.into())
+	verify {
+		assert_eq!(Assets::<T>::balance(id, target), SUPPLY.into());
+		assert_eq!(Assets::<T>::total_supply(id), (2 * SUPPLY).into());
+	}
+
+	burn {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let caller_lookup = T::Lookup::unlookup(caller.clone());
+	}: _(RawOrigin::Signed(caller.clone()), id, caller_lookup, SUPPLY.into())
+	verify {
+		assert!(Assets::<T>::balance(id, caller).is_zero());
+	}
+
+	// Worst case: the transfer creates the balance of the recipient.
+	transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let recipient: T::AccountId = account("recipient", 0, SEED);
+		let recipient_lookup = T::Lookup::unlookup(recipient.clone());
+	}: _(RawOrigin::Signed(caller), id, recipient_lookup, SUPPLY.into())
+	verify {
+		assert_eq!(Assets::<T>::balance(id, recipient), SUPPLY.into());
+	}
+
+	destroy {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), id)
+	verify {
+		assert!(Assets::<T>::total_supply(id).is_zero());
+	}
+
+	approve {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let spender: T::AccountId = account("spender", 0, SEED);
+		let spender_lookup = T::Lookup::unlookup(spender.clone());
+	}: _(RawOrigin::Signed(caller.clone()), id, spender_lookup, SUPPLY.into())
+	verify {
+		assert_eq!(Assets::<T>::allowance(id, caller, spender), SUPPLY.into());
+	}
+
+	// Worst case: the transfer spends part of the allowance and creates the balance of the
+	// recipient.
+	transfer_from {
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let id = issue_asset::<T>(&owner)?;
+		let caller: T::AccountId = whitelisted_caller();
+		Assets::<T>::approve(RawOrigin::Signed(owner.clone()).into(), id, T::Lookup::unlookup(caller.clone()), SUPPLY.into())?;
+		let owner_lookup = T::Lookup::unlookup(owner.clone());
+		let recipient: T::AccountId = account("recipient", 0, SEED);
+		let recipient_lookup = T::Lookup::unlookup(recipient.clone());
+	}: _(RawOrigin::Signed(caller.clone()), id, owner_lookup, recipient_lookup, (SUPPLY / 2).into())
+	verify {
+		assert_eq!(Assets::<T>::balance(id, recipient), (SUPPLY / 2).into());
+		assert_eq!(Assets::<T>::allowance(id, owner, caller), (SUPPLY / 2).into());
+	}
+
+	increase_allowance {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let spender: T::AccountId = account("spender", 0, SEED);
+		Assets::<T>::approve(RawOrigin::Signed(caller.clone()).into(), id, T::Lookup::unlookup(spender.clone()), SUPPLY.into())?;
+		let spender_lookup = T::Lookup::unlookup(spender.clone());
+	}: _(RawOrigin::Signed(caller.clone()), id, spender_lookup, SUPPLY.into())
+	verify {
+		assert_eq!(Assets::<T>::allowance(id, caller, spender), (2 * SUPPLY).into());
+	}
+
+	decrease_allowance {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let spender: T::AccountId = account("spender", 0, SEED);
+		Assets::<T>::approve(RawOrigin::Signed(caller.clone()).into(), id, T::Lookup::unlookup(spender.clone()), SUPPLY.into())?;
+		let spender_lookup = T::Lookup::unlookup(spender.clone());
+	}: _(RawOrigin::Signed(caller.clone()), id, spender_lookup, (SUPPLY / 2).into())
+	verify {
+		assert_eq!(Assets::<T>::allowance(id, caller, spender), (SUPPLY / 2).into());
+	}
+
+	set_metadata {
+		let n in 0 .. T::StringLimit::get();
+		let s in 0 .. T::StringLimit::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let _ = <balances::Module<T> as Currency<_>>::make_free_balance_be(&caller, T::Balance::max_value());
+		let name = vec![0u8; n as usize];
+		let symbol = vec![0u8; s as usize];
+	}: _(RawOrigin::Signed(caller), id, name.clone(), symbol.clone(), 12)
+	verify {
+		assert_eq!(Assets::<T>::metadata(id).name, name);
+		assert_eq!(Assets::<T>::metadata(id).symbol, symbol);
+	}
+
+	clear_metadata {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let _ = <balances::Module<T> as Currency<_>>::make_free_balance_be(&caller, T::Balance::max_value());
+		let limit = T::StringLimit::get() as usize;
+		Assets::<T>::set_metadata(RawOrigin::Signed(caller.clone()).into(), id, vec![0u8; limit], vec![0u8; limit], 12)?;
+	}: _(RawOrigin::Signed(caller), id)
+	verify {
+		assert!(Assets::<T>::metadata(id).deposit.is_zero());
+	}
+
+	freeze {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let who: T::AccountId = account("who", 0, SEED);
+		let who_lookup = T::Lookup::unlookup(who.clone());
+	}: _(RawOrigin::Signed(caller), id, who_lookup)
+	verify {
+		assert!(Assets::<T>::is_frozen((id, who)));
+	}
+
+	thaw {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let who: T::AccountId = account("who", 0, SEED);
+		let who_lookup = T::Lookup::unlookup(who.clone());
+		Assets::<T>::freeze(RawOrigin::Signed(caller.clone()).into(), id, who_lookup.clone())?;
+	}: _(RawOrigin::Signed(caller), id, who_lookup)
+	verify {
+		assert!(!Assets::<T>::is_frozen((id, who)));
+	}
+
+	freeze_asset {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller), id)
+	verify {
+		assert!(Assets::<T>::is_asset_frozen(id));
+	}
+
+	thaw_asset {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		Assets::<T>::freeze_asset(RawOrigin::Signed(caller.clone()).into(), id)?;
+	}: _(RawOrigin::Signed(caller), id)
+	verify {
+		assert!(!Assets::<T>::is_asset_frozen(id));
+	}
+
+	// Worst case: the source is frozen and the transfer creates the balance of the recipient.
+	force_transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let source: T::AccountId = account("source", 0, SEED);
+		let source_lookup = T::Lookup::unlookup(source.clone());
+		Assets::<T>::transfer(RawOrigin::Signed(caller.clone()).into(), id, source_lookup.clone(), SUPPLY.into())?;
+		Assets::<T>::freeze(RawOrigin::Signed(caller.clone()).into(), id, source_lookup.clone())?;
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let dest_lookup = T::Lookup::unlookup(dest.clone());
+	}: _(RawOrigin::Signed(caller), id, source_lookup, dest_lookup, SUPPLY.into())
+	verify {
+		assert_eq!(Assets::<T>::balance(id, dest), SUPPLY.into());
+	}
+
+	// Worst case: the deposit of the metadata is moved to the new owner.
+	transfer_ownership {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let _ = <balances::Module<T> as Currency<_>>::make_free_balance_be(&caller, T::Balance::max_value());
+		let limit = T::StringLimit::get() as usize;
+		Assets::<T>::set_metadata(RawOrigin::Signed(caller.clone()).into(), id, vec![0u8; limit], vec![0u8; limit], 12)?;
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let owner_lookup = T::Lookup::unlookup(owner.clone());
+	}: _(RawOrigin::Signed(caller), id, owner_lookup)
+	verify {
+		assert_eq!(Assets::<T>::team(id).map(|team| team.issuer), Ok(owner));
+	}
+
+	set_team {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let issuer: T::AccountId = account("issuer", 0, SEED);
+		let admin: T::AccountId = account("admin", 0, SEED);
+		let freezer: T::AccountId = account("freezer", 0, SEED);
+	}: _(
+		RawOrigin::Signed(caller),
+		id,
+		T::Lookup::unlookup(issuer.clone()),
+		T::Lookup::unlookup(admin.clone()),
+		T::Lookup::unlookup(freezer.clone())
+	)
+	verify {
+		assert_eq!(Assets::<T>::team(id), Ok(AssetTeam { issuer, admin, freezer }));
+	}
+
+	verify_asset {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		let origin = T::VerifyOrigin::successful_origin();
+		let call = Call::<T>::verify_asset(id, VerificationProof::Referendum(0));
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Assets::<T>::is_verified(id));
+	}
+
+	revoke_verification {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = issue_asset::<T>(&caller)?;
+		Assets::<T>::verify_asset(T::VerifyOrigin::successful_origin(), id, VerificationProof::Referendum(0))?;
+		let origin = T::VerifyOrigin::successful_origin();
+		let call = Call::<T>::revoke_verification(id);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(!Assets::<T>::is_verified(id));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_issue::<Test>());
+			assert_ok!(test_benchmark_mint::<Test>());
+			assert_ok!(test_benchmark_burn::<Test>());
+			assert_ok!(test_benchmark_transfer::<Test>());
+			assert_ok!(test_benchmark_destroy::<Test>());
+			assert_ok!(test_benchmark_approve::<Test>());
+			assert_ok!(test_benchmark_transfer_from::<Test>());
+			assert_ok!(test_benchmark_increase_allowance::<Test>());
+			assert_ok!(test_benchmark_decrease_allowance::<Test>());
+			assert_ok!(test_benchmark_set_metadata::<Test>());
+			assert_ok!(test_benchmark_clear_metadata::<Test>());
+			assert_ok!(test_benchmark_freeze::<Test>());
+			assert_ok!(test_benchmark_thaw::<Test>());
+			assert_ok!(test_benchmark_freeze_asset::<Test>());
+			assert_ok!(test_benchmark_thaw_asset::<Test>());
+			assert_ok!(test_benchmark_force_transfer::<Test>());
+			assert_ok!(test_benchmark_transfer_ownership::<Test>());
+			assert_ok!(test_benchmark_set_team::<Test>());
+			assert_ok!(test_benchmark_verify_asset::<Test>());
+			assert_ok!(test_benchmark_revoke_verification::<Test>());
+		});
+	}
+}
@@ -0,0 +1,1455 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Subswap Asset Module
+//!
+//! The fungible asset ledger used by the [subswap](../subswap/index.html) market.
+//!
+//! ## Overview
+//!
+//! The Subswap Asset module provides functionality for management of fungible asset classes,
+//! including:
+//!
+//! * Asset issuance by accounts and by the system (e.g. liquidity provider tokens)
+//! * Asset transfer
+//! * Asset allowances for transfers by approved spenders
+//! * Asset metadata, with a deposit of the native currency reserved from the creator
+//! * Asset minting and burning
+//! * Asset reservation
+//! * Asset minimum balances, below which the balance of an account is removed from storage
+//! * Asset administration by a team of an issuer, an admin and a freezer appointed by the owner, which may freeze
+//! accounts or the whole asset and force transfers
+//! * Asset verification by governance, recording the referendum or proposal endorsing the asset
+//!
+//! Asset id `0` is reserved for the native currency, which is kept by the
+//! [balances](../pallet_balances/index.html) module and moved through its `Currency` and
+//! `ReservableCurrency` traits, so that existential deposits, locks and the total issuance of the
+//! native currency are respected. The native currency transferred to the system is held by the
+//! ledger's account derived from `Trait::ModuleId`, which is created with the existential deposit
+//! at genesis.
+//!
+//! ### Minimum Balance
+//!
+//! Every asset has a minimum balance, set when it is issued, which plays the role of the existential
+//! deposit of the native currency. No account can receive an amount leaving it with less than the
+//! minimum balance, and an account whose free and reserved balances together fall below it loses
+//! the rest of its free balance, the dust, which is swept to the owner of the asset, or burned if
+//! the asset is issued by the system or the owner cannot receive it. Accounts of modules, derived
+//! from a `ModuleId`, are exempt from the minimum balance, since they hold balances on behalf of
+//! others, such as the reserves of the market. Zero balances are removed from storage. While an account holds any asset, the ledger keeps a reference on it in the
+//! [system](../frame_system/index.html) module, so that an account holding only assets of the ledger
+//! is not reaped and its native balance cannot be transferred away below the existential deposit.
+//! Assets issued by the system and created at genesis have a minimum balance of zero.
+//!
+//! ### Asset Team
+//!
+//! The creator of an asset owns it, and may appoint a team of an issuer, who mints the asset, an
+//! admin and a freezer. Until a team is appointed, the owner fills every role. The freezer may
+//! freeze an account, which may then neither move nor burn its balance of the asset, or the whole
+//! asset, which halts every transfer of it, including the swaps of the
+//! [subswap](../subswap/index.html) market and the transfers to and from the system. The admin
+//! thaws them, and may force a transfer out of any account unless the whole asset is frozen. The
+//! native currency has no team.
+//!
+//! ### Verified Assets
+//!
+//! Anyone may issue an asset, so the ledger keeps a registry of the assets endorsed by the
+//! community. An issuer proposes the verification of an asset through the
+//! [democracy](../pallet_democracy/index.html) module, or asks the council, and once the proposal
+//! passes, `verify_asset` records the index of the referendum or the hash of the proposal as the
+//! proof of the endorsement. Front-ends tell verified assets from the others with `is_verified`.
+//! Governance may revoke the verification of an asset.
+//!
+//! To use it in your runtime, you need to implement the subswap asset [`Trait`](./trait.Trait.html).
+//!
+//! The supported dispatchable functions are documented in the [`Call`](./enum.Call.html) enum.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `issue` - Issues the total supply of a new fungible asset to the account of the caller of the function, with the
+//! minimum balance of its accounts.
+//! * `mint` - Mints the asset to the account in the argument with the requested amount from the caller. Caller must be the issuer of the asset.
+//! * `burn` - Burns the asset from the caller by the amount in the argument
+//! * `transfer` - Transfers an `amount` of units of fungible asset `id` from the balance of
+//! the function caller's account (`origin`) to a `target` account.
+//! * `destroy` - Destroys the entire holding of a fungible asset `id` associated with the account
+//! that called the function.
+//! * `approve` - Sets the amount of an asset which a `spender` may transfer from the caller's account.
+//! * `transfer_from` - Transfers an `amount` of an asset from an `owner` to a `target` account, spending the
+//! allowance approved by the owner to the caller.
+//! * `increase_allowance` - Increases the allowance of a `spender` on the caller's account.
+//! * `decrease_allowance` - Decreases the allowance of a `spender` on the caller's account.
+//! * `set_metadata` - Sets the name, symbol and decimals of an asset, reserving a deposit from the caller. Caller must be
+//! the creator of the asset.
+//! * `clear_metadata` - Clears the metadata of an asset and refunds its deposit. Caller must be the creator of the asset.
+//! * `freeze` - Freezes the balance of an account in an asset. Caller must be the freezer of the asset.
+//! * `thaw` - Thaws the balance of an account in an asset. Caller must be the admin of the asset.
+//! * `freeze_asset` - Freezes every balance of an asset. Caller must be the freezer of the asset.
+//! * `thaw_asset` - Thaws every balance of an asset. Caller must be the admin of the asset.
+//! * `force_transfer` - Transfers an amount of an asset from any account to another, even if the account is frozen.
+//! Caller must be the admin of the asset.
+//! * `transfer_ownership` - Makes another account the owner of an asset, moving the deposit of its metadata. Caller must
+//! be the creator of the asset.
+//! * `set_team` - Appoints the issuer, the admin and the freezer of an asset. Caller must be the creator of the asset.
+//! * `verify_asset` - Records an asset as endorsed by governance with the referendum or proposal proving it. Caller must
+//! be `VerifyOrigin`.
+//! * `revoke_verification` - Removes an asset from the verified assets. Caller must be `VerifyOrigin`.
+//!
+//! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
+//!
+//! ### Public Functions
+//!
+//! * `balance` - Get the balance of the account with the asset id
+//! * `total_supply` - Get the total supply of an asset.
+//! * `allowance` - Get the amount of an asset a spender may transfer from the account of an owner.
+//! * `metadata` - Get the name, symbol and decimals of an asset with the deposit reserved for them.
+//! * `min_balance` - Get the minimum balance of the accounts of an asset.
+//! * `is_verified` - Get whether an asset is endorsed by governance.
+//! * `verification` - Get the referendum or proposal proving the endorsement of an asset.
+//! * `team` - Get the issuer, the admin and the freezer of an asset.
+//! * `is_frozen` - Get whether the balance of an account in an asset is frozen.
+//! * `is_asset_frozen` - Get whether every balance of an asset is frozen.
+//! * `mint_from_system` - Mint asset from the system to an account, increasing total supply.
+//! * `burn_from_system` - Burn asset from the system to an account, decreasing total supply.
+//! * `account_id` - Get the account holding the native currency transferred to the system.
+//! * `transfer_from_system` - Transfer asset from the system to an account with no change in total supply.
+//! * `transfer_to_system` - Transfer asset from an account to the system with no change in total supply.
+//! * `issue_from_system` - Issue asset from system
+//!
+//! Please refer to the [`Module`](./struct.Module.html) struct for details on publicly available functions.
+//!
+//! ### Multi-asset Trait
+//!
+//! Other modules should hold and move assets through the [`MultiAsset`](./trait.MultiAsset.html)
+//! trait, which this module implements, rather than depending on this module directly.
+//!
+//! ### Atomic Swaps
+//!
+//! [`AssetSwapAction`](./struct.AssetSwapAction.html) is a `SwapAction` of the
+//! [atomic swap](../pallet_atomic_swap/index.html) module moving any asset of the ledger, so that
+//! assets can be traded between parties or chains with hashed time locks. The asset is reserved
+//! from the source when the swap is created, moved from the reserved balance of the source to the
+//! target when it is claimed, even if the asset was frozen since, and unreserved when it is
+//! cancelled.
+//!
+//! ### Genesis Configuration
+//!
+//! A chain may start with assets already created through `GenesisConfig::assets`, which sets the
+//! creator and the metadata of each asset without a deposit, and with balances of those assets
+//! through `GenesisConfig::balances`. Assets issued later take identifiers after the genesis ones.
+//!
+//! ## Related Modules
+//!
+//! * [`System`](../frame_system/index.html)
+//! * [`Support`](../frame_support/index.html)
+//! * [`Balances`](../pallet_balances/index.html)
+//! * [`Atomic Swap`](../pallet_atomic_swap/index.html)
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod tests;
+mod benchmarking;
+mod default_weights;
+mod swap_action;
+pub use swap_action::AssetSwapAction;
+
+use frame_support::{Parameter, decl_module, decl_event, decl_storage, decl_error, ensure, dispatch};
+use frame_support::weights::Weight;
+use frame_support::traits::{
+	Currency, ReservableCurrency, ExistenceRequirement, Get, Imbalance, WithdrawReason, WithdrawReasons, BalanceStatus,
+	EnsureOrigin,
+};
+use sp_runtime::traits::{
+	Member, AtLeast32Bit, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Zero, One, StaticLookup, Saturating,
+	CheckedAdd, AccountIdConversion,
+};
+use sp_runtime::{DispatchError, RuntimeDebug, ModuleId, TypeId};
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use frame_system::ensure_signed;
+use pallet_balances as balances;
+
+/// A ledger of fungible assets, each identified by an `AssetId`.
+///
+/// Asset id `0` is the native currency of the chain.
+pub trait MultiAsset<AccountId> {
+	/// The arithmetic type of asset identifier.
+	type AssetId: Parameter + Member + AtLeast32Bit + Default + Copy + MaybeSerializeDeserialize;
+
+	/// The units in which balances are recorded.
+	type Balance: Parameter + Member + AtLeast32BitUnsigned + Default + Copy + MaybeSerializeDeserialize;
+
+	/// The free balance of `who` in asset `id`.
+	fn balance(id: Self::AssetId, who: &AccountId) -> Self::Balance;
+
+	/// The reserved balance of `who` in asset `id`.
+	fn reserved_balance(id: Self::AssetId, who: &AccountId) -> Self::Balance;
+
+	/// The total issuance of asset `id`.
+	fn total_issuance(id: Self::AssetId) -> Self::Balance;
+
+	/// Issue a new asset owned by the system, returning its identifier.
+	fn issue_from_system(total: Self::Balance) -> Result<Self::AssetId, DispatchError>;
+
+	/// Move `amount` of asset `id` from `from` to `to`.
+	fn transfer(
+		id: Self::AssetId,
+		from: &AccountId,
+		to: &AccountId,
+		amount: Self::Balance,
+	) -> dispatch::DispatchResult;
+
+	/// Create `amount` of asset `id` in the account of `who`, increasing the total issuance.
+	fn mint_into(id: Self::AssetId, who: &AccountId, amount: Self::Balance) -> dispatch::DispatchResult;
+
+	/// Destroy `amount` of asset `id` from the account of `who`, decreasing the total issuance.
+	fn burn_from(id: Self::AssetId, who: &AccountId, amount: Self::Balance) -> dispatch::DispatchResult;
+
+	/// Move `amount` of asset `id` from the account of `who` into the custody of the system,
+	/// keeping the total issuance.
+	fn transfer_to_system(id: Self::AssetId, who: &AccountId, amount: Self::Balance) -> dispatch::DispatchResult;
+
+	/// Release `amount` of asset `id` from the custody of the system into the account of `who`,
+	/// keeping the total issuance.
+	fn transfer_from_system(id: Self::AssetId, who: &AccountId, amount: Self::Balance) -> dispatch::DispatchResult;
+
+	/// Move `amount` of asset `id` from the free balance of `who` to its reserved balance.
+	fn reserve(id: Self::AssetId, who: &AccountId, amount: Self::Balance) -> dispatch::DispatchResult;
+
+	/// Move up to `amount` of asset `id` from the reserved balance of `who` back to its free
+	/// balance. Returns the amount that could not be unreserved.
+	fn unreserve(id: Self::AssetId, who: &AccountId, amount: Self::Balance) -> Self::Balance;
+
+	/// Move up to `amount` of asset `id` from the reserved balance of `source` to the free balance
+	/// of `dest`, whether either is frozen or not and whatever the minimum balance of the asset,
+	/// which were checked when the balance was reserved. Returns the amount that could not be
+	/// moved.
+	fn repatriate_reserved(
+		id: Self::AssetId,
+		source: &AccountId,
+		dest: &AccountId,
+		amount: Self::Balance,
+	) -> Result<Self::Balance, DispatchError>;
+
+	/// Ensure asset `id` may move at all, which it may not while it is frozen as a whole.
+	fn ensure_transferable(id: Self::AssetId) -> dispatch::DispatchResult;
+
+	/// The symbol of asset `id`, empty if it has no metadata.
+	fn symbol(id: Self::AssetId) -> Vec<u8>;
+
+	/// The number of decimals of asset `id`, zero if it has no metadata.
+	fn decimals(id: Self::AssetId) -> u8;
+
+	/// Set the metadata of asset `id` issued by the system, without a deposit.
+	fn set_system_metadata(
+		id: Self::AssetId,
+		name: Vec<u8>,
+		symbol: Vec<u8>,
+		decimals: u8,
+	) -> dispatch::DispatchResult;
+}
+
+/// The name, symbol and decimals of an asset, shown by wallets.
+#[derive(Clone, Eq, PartialEq, Default, RuntimeDebug, Encode, Decode)]
+pub struct AssetMetadata<Balance> {
+	/// The native currency reserved from the creator of the asset for the metadata.
+	pub deposit: Balance,
+	/// The name of the asset.
+	pub name: Vec<u8>,
+	/// The ticker symbol of the asset.
+	pub symbol: Vec<u8>,
+	/// The number of decimals of the asset's balances.
+	pub decimals: u8,
+}
+
+/// The accounts administering an asset on behalf of its owner.
+#[derive(Clone, Eq, PartialEq, Default, RuntimeDebug, Encode, Decode)]
+pub struct AssetTeam<AccountId> {
+	/// The account which may mint the asset.
+	pub issuer: AccountId,
+	/// The account which may thaw accounts and the asset, and force transfers of the asset.
+	pub admin: AccountId,
+	/// The account which may freeze accounts and the asset.
+	pub freezer: AccountId,
+}
+
+/// The proof of the endorsement of an asset by governance.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub enum VerificationProof<Hash> {
+	/// The index of the referendum of the democracy module which passed the verification.
+	Referendum(u32),
+	/// The hash of the proposal of the democracy or council modules which passed the
+	/// verification.
+	Proposal(Hash),
+}
+
+/// Weight functions needed for the subswap asset module.
+pub trait WeightInfo {
+	fn issue() -> Weight;
+	fn mint() -> Weight;
+	fn burn() -> Weight;
+	fn transfer() -> Weight;
+	fn destroy() -> Weight;
+	fn approve() -> Weight;
+	fn transfer_from() -> Weight;
+	fn increase_allowance() -> Weight;
+	fn decrease_allowance() -> Weight;
+	fn set_metadata(n: u32, s: u32, ) -> Weight;
+	fn clear_metadata() -> Weight;
+	fn freeze() -> Weight;
+	fn thaw() -> Weight;
+	fn freeze_asset() -> Weight;
+	fn thaw_asset() -> Weight;
+	fn force_transfer() -> Weight;
+	fn transfer_ownership() -> Weight;
+	fn set_team() -> Weight;
+	fn verify_asset() -> Weight;
+	fn revoke_verification() -> Weight;
+}
+
+/// The module configuration trait.
+pub trait Trait: frame_system::Trait + balances::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The arithmetic type of asset identifier.
+	type AssetId: Parameter + Member + AtLeast32Bit + Default + Copy + MaybeSerializeDeserialize;
+
+	/// The ledger's module id, used for deriving the account which holds the native currency
+	/// transferred to the system.
+	type ModuleId: Get<ModuleId>;
+
+	/// The native currency reserved for the metadata of an asset.
+	type MetadataDepositBase: Get<Self::Balance>;
+
+	/// The native currency reserved per byte of the name and symbol of an asset.
+	type MetadataDepositPerByte: Get<Self::Balance>;
+
+	/// The maximum length of the name and symbol of an asset.
+	type StringLimit: Get<u32>;
+
+	/// The symbol of the native currency, asset id `0`.
+	type NativeSymbol: Get<&'static [u8]>;
+
+	/// The number of decimals of the native currency, asset id `0`.
+	type NativeDecimals: Get<u8>;
+
+	/// The origin which may verify assets and revoke their verification, such as a passed
+	/// referendum of the democracy module or a council majority.
+	type VerifyOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Weight information for extrinsics in this module.
+	type WeightInfo: WeightInfo;
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// The ledger's module id, used for deriving the account which holds the native currency
+		/// transferred to the system.
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		/// The native currency reserved for the metadata of an asset.
+		const MetadataDepositBase: T::Balance = T::MetadataDepositBase::get();
+
+		/// The native currency reserved per byte of the name and symbol of an asset.
+		const MetadataDepositPerByte: T::Balance = T::MetadataDepositPerByte::get();
+
+		/// The maximum length of the name and symbol of an asset.
+		const StringLimit: u32 = T::StringLimit::get();
+
+		fn deposit_event() = default;
+		/// Issue a new class of fungible assets. There are, and will only ever be, `total`
+		/// such assets and they'll all belong to the `origin` initially. It will have an
+		/// identifier `AssetId` instance: this will be specified in the `Issued` event.
+		///
+		/// No account may hold less than `min_balance` of the asset, which `total` must reach.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage mutation (codec `O(1)`).
+		/// - 3 storage writes (condec `O(1)`).
+		/// - 1 reference on the account of `origin`.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::issue()]
+		fn issue(origin, #[compact] total: T::Balance, #[compact] min_balance: T::Balance) {
+			let origin = ensure_signed(origin)?;
+			ensure!(total >= min_balance, Error::<T>::BelowMinimum);
+			let id = Self::next_id();
+
+			<MinBalance<T>>::insert(id, min_balance);
+			Self::deposit_free(id, &origin, total);
+			<TotalSupply<T>>::insert(id, total);
+			<Creator<T>>::insert(id, &origin);
+
+			Self::deposit_event(RawEvent::Issued(id, origin, total));
+		}
+
+		/// Mint any assets of `id` issued by `origin`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage mutation (codec `O(1)`).
+		/// - 1 storage deletion (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::mint()]
+		fn mint(origin,
+			#[compact] id: T::AssetId,
+			target: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			ensure!(origin == Self::team(id)?.issuer, Error::<T>::NoPermission);
+			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+			Self::ensure_asset_unfrozen(id)?;
+			// No balance exceeds the total supply, so neither does the balance of `target` overflow
+			let supply = <TotalSupply<T>>::get(id).checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+			Self::ensure_can_deposit(id, &target, amount)?;
+
+			Self::deposit_event(RawEvent::Minted(id, target.clone(), amount));
+			Self::deposit_free(id, &target, amount);
+			<TotalSupply<T>>::insert(id, supply);
+		}
+
+		/// Burn any assets of `id` owned by `origin`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage mutation (codec `O(1)`).
+		/// - 1 storage deletion (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::burn()]
+		fn burn(origin,
+			#[compact] id: T::AssetId,
+			target: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let origin_balance = <Balances<T>>::get((id, &origin));
+			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+			ensure!(origin_balance >= amount, Error::<T>::BalanceLow);
+			Self::ensure_unfrozen(id, &origin)?;
+
+			Self::deposit_event(RawEvent::Burned(id, origin.clone(), amount));
+			let dust = Self::withdraw_free(id, &origin, amount);
+			<TotalSupply<T>>::mutate(id, |supply| *supply -= amount);
+			Self::handle_dust(id, &origin, dust);
+		}
+
+		/// Move some assets from one holder to another.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 static lookup
+		/// - 2 storage mutations (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::transfer()]
+		fn transfer(origin,
+			#[compact] id: T::AssetId,
+			target: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			Self::do_transfer(id, &origin, &target, amount)?;
+		}
+
+		/// Destroy any assets of `id` owned by `origin`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage mutation (codec `O(1)`).
+		/// - 1 storage deletion (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::destroy()]
+		fn destroy(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+			Self::ensure_unfrozen(id, &origin)?;
+			let balance = <Balances<T>>::get((id, &origin));
+			ensure!(!balance.is_zero(), Error::<T>::BalanceZero);
+
+			Self::withdraw_free(id, &origin, balance);
+			<TotalSupply<T>>::mutate(id, |total_supply| *total_supply -= balance);
+			Self::deposit_event(RawEvent::Destroyed(id, origin, balance));
+		}
+
+		/// Allow `spender` to transfer up to `amount` of the assets of `id` owned by `origin`,
+		/// replacing any previous allowance.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 static lookup
+		/// - 1 storage write (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::approve()]
+		fn approve(origin,
+			#[compact] id: T::AssetId,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			Self::set_allowance(id, &origin, &spender, amount);
+		}
+
+		/// Move some assets of `id` from `owner` to `target`, spending the allowance approved by
+		/// `owner` to `origin`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 static lookups
+		/// - 3 storage mutations (codec `O(1)`).
+		/// - 2 events.
+		/// # </weight>
+		#[weight = T::WeightInfo::transfer_from()]
+		fn transfer_from(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			target: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let target = T::Lookup::lookup(target)?;
+			let allowance = <Allowances<T>>::get((id, &owner, &origin));
+			ensure!(allowance >= amount, Error::<T>::NotApproved);
+
+			Self::do_transfer(id, &owner, &target, amount)?;
+			Self::set_allowance(id, &owner, &origin, allowance - amount);
+		}
+
+		/// Increase the allowance of `spender` on the assets of `id` owned by `origin` by `delta`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 static lookup
+		/// - 1 storage mutation (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::increase_allowance()]
+		fn increase_allowance(origin,
+			#[compact] id: T::AssetId,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[compact] delta: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			let allowance = <Allowances<T>>::get((id, &origin, &spender));
+			Self::set_allowance(id, &origin, &spender, allowance.saturating_add(delta));
+		}
+
+		/// Decrease the allowance of `spender` on the assets of `id` owned by `origin` by `delta`.
+		/// Fails if the allowance is less than `delta`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 static lookup
+		/// - 1 storage mutation (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::decrease_allowance()]
+		fn decrease_allowance(origin,
+			#[compact] id: T::AssetId,
+			spender: <T::Lookup as StaticLookup>::Source,
+			#[compact] delta: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			let allowance = <Allowances<T>>::get((id, &origin, &spender));
+			ensure!(allowance >= delta, Error::<T>::NotApproved);
+			Self::set_allowance(id, &origin, &spender, allowance - delta);
+		}
+
+		/// Set the `name`, `symbol` and `decimals` of the asset `id` created by `origin`.
+		///
+		/// Reserves `MetadataDepositBase + MetadataDepositPerByte * (name.len() + symbol.len())` of
+		/// the native currency from `origin`, adjusting the deposit of any previous metadata.
+		///
+		/// # <weight>
+		/// - `O(N)` where `N` is the length of `name` and `symbol`, bounded by `StringLimit`.
+		/// - 1 storage read and 1 storage write (codec `O(N)`).
+		/// - 1 reserve or unreserve of the native currency.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::set_metadata(name.len() as u32, symbol.len() as u32)]
+		fn set_metadata(origin,
+			#[compact] id: T::AssetId,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8
+		) {
+			let origin = ensure_signed(origin)?;
+			Self::ensure_creator(id, &origin)?;
+			let limit = T::StringLimit::get() as usize;
+			ensure!(name.len() <= limit && symbol.len() <= limit, Error::<T>::BadMetadata);
+
+			let bytes = T::Balance::from((name.len() + symbol.len()) as u32);
+			let deposit = T::MetadataDepositPerByte::get()
+				.saturating_mul(bytes)
+				.saturating_add(T::MetadataDepositBase::get());
+			let old_deposit = <Metadata<T>>::get(id).deposit;
+			if deposit > old_deposit {
+				<balances::Module<T> as ReservableCurrency<_>>::reserve(&origin, deposit - old_deposit)?;
+			} else {
+				<balances::Module<T> as ReservableCurrency<_>>::unreserve(&origin, old_deposit - deposit);
+			}
+
+			Self::deposit_event(RawEvent::MetadataSet(id, name.clone(), symbol.clone(), decimals));
+			<Metadata<T>>::insert(id, AssetMetadata { deposit, name, symbol, decimals });
+		}
+
+		/// Clear the metadata of the asset `id` created by `origin`, refunding its deposit.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage deletion (codec `O(1)`).
+		/// - 1 unreserve of the native currency.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::clear_metadata()]
+		fn clear_metadata(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+			Self::ensure_creator(id, &origin)?;
+			ensure!(<Metadata<T>>::contains_key(id), Error::<T>::NoMetadata);
+
+			let metadata = <Metadata<T>>::take(id);
+			<balances::Module<T> as ReservableCurrency<_>>::unreserve(&origin, metadata.deposit);
+			Self::deposit_event(RawEvent::MetadataCleared(id));
+		}
+
+		/// Freeze the balance of `who` in the asset `id`, so that it can neither be moved nor
+		/// burned by `who`. `origin` must be the freezer of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 static lookup
+		/// - 2 storage reads and 1 storage write (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::freeze()]
+		fn freeze(origin, #[compact] id: T::AssetId, who: <T::Lookup as StaticLookup>::Source) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(origin == Self::team(id)?.freezer, Error::<T>::NoPermission);
+
+			<FrozenAccounts<T>>::insert((id, &who), true);
+			Self::deposit_event(RawEvent::Frozen(id, who));
+		}
+
+		/// Thaw the balance of `who` in the asset `id`. `origin` must be the admin of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 static lookup
+		/// - 2 storage reads and 1 storage deletion (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::thaw()]
+		fn thaw(origin, #[compact] id: T::AssetId, who: <T::Lookup as StaticLookup>::Source) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(origin == Self::team(id)?.admin, Error::<T>::NoPermission);
+
+			<FrozenAccounts<T>>::remove((id, &who));
+			Self::deposit_event(RawEvent::Thawed(id, who));
+		}
+
+		/// Freeze every balance of the asset `id`, halting all of its transfers, including swaps
+		/// of the asset and transfers to and from the system. `origin` must be the freezer of the
+		/// asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads and 1 storage write (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::freeze_asset()]
+		fn freeze_asset(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+			ensure!(origin == Self::team(id)?.freezer, Error::<T>::NoPermission);
+
+			<FrozenAssets<T>>::insert(id, true);
+			Self::deposit_event(RawEvent::AssetFrozen(id));
+		}
+
+		/// Thaw every balance of the asset `id`. `origin` must be the admin of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads and 1 storage deletion (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::thaw_asset()]
+		fn thaw_asset(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+			ensure!(origin == Self::team(id)?.admin, Error::<T>::NoPermission);
+
+			<FrozenAssets<T>>::remove(id);
+			Self::deposit_event(RawEvent::AssetThawed(id));
+		}
+
+		/// Move some assets of `id` from `source` to `dest`, even if `source` is frozen. `origin`
+		/// must be the admin of the asset, which must not be frozen as a whole.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 static lookups
+		/// - 3 storage reads and 2 storage mutations (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::force_transfer()]
+		fn force_transfer(origin,
+			#[compact] id: T::AssetId,
+			source: <T::Lookup as StaticLookup>::Source,
+			dest: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let source = T::Lookup::lookup(source)?;
+			let dest = T::Lookup::lookup(dest)?;
+			ensure!(origin == Self::team(id)?.admin, Error::<T>::NoPermission);
+			Self::ensure_asset_unfrozen(id)?;
+			Self::move_balance(id, &source, &dest, amount)?;
+		}
+
+		/// Make `owner` the owner of the asset `id` owned by `origin`, moving the deposit reserved
+		/// for its metadata to `owner`. The roles of an asset without a team follow its owner.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 static lookup
+		/// - 2 storage reads and 1 storage write (codec `O(1)`).
+		/// - 1 repatriation of reserved native currency.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::transfer_ownership()]
+		fn transfer_ownership(origin, #[compact] id: T::AssetId, owner: <T::Lookup as StaticLookup>::Source) {
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			Self::ensure_creator(id, &origin)?;
+			if origin == owner {
+				return Ok(());
+			}
+
+			let deposit = <Metadata<T>>::get(id).deposit;
+			if !deposit.is_zero() {
+				<balances::Module<T> as ReservableCurrency<_>>::repatriate_reserved(
+					&origin,
+					&owner,
+					deposit,
+					BalanceStatus::Reserved,
+				)?;
+			}
+			<Creator<T>>::insert(id, &owner);
+			Self::deposit_event(RawEvent::OwnerChanged(id, owner));
+		}
+
+		/// Appoint the `issuer`, the `admin` and the `freezer` of the asset `id` owned by `origin`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 3 static lookups
+		/// - 1 storage read and 1 storage write (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::set_team()]
+		fn set_team(origin,
+			#[compact] id: T::AssetId,
+			issuer: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source
+		) {
+			let origin = ensure_signed(origin)?;
+			let issuer = T::Lookup::lookup(issuer)?;
+			let admin = T::Lookup::lookup(admin)?;
+			let freezer = T::Lookup::lookup(freezer)?;
+			Self::ensure_creator(id, &origin)?;
+
+			Self::deposit_event(RawEvent::TeamChanged(id, issuer.clone(), admin.clone(), freezer.clone()));
+			<Team<T>>::insert(id, AssetTeam { issuer, admin, freezer });
+		}
+
+		/// Record the asset `id` as endorsed by governance, with the referendum or proposal
+		/// proving it, replacing any previous proof.
+		///
+		/// The dispatch origin for this call must be `VerifyOrigin`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads and 1 storage write (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::verify_asset()]
+		fn verify_asset(origin, #[compact] id: T::AssetId, proof: VerificationProof<T::Hash>) {
+			T::VerifyOrigin::ensure_origin(origin)?;
+			ensure!(id.is_zero() || id < Self::next_asset_id(), Error::<T>::UnknownAsset);
+			ensure!(<Creator<T>>::contains_key(id), Error::<T>::CreatedBySystem);
+
+			Self::deposit_event(RawEvent::AssetVerified(id, proof.clone()));
+			<Verifications<T>>::insert(id, proof);
+		}
+
+		/// Remove the asset `id` from the assets endorsed by governance.
+		///
+		/// The dispatch origin for this call must be `VerifyOrigin`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read and 1 storage deletion (codec `O(1)`).
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::WeightInfo::revoke_verification()]
+		fn revoke_verification(origin, #[compact] id: T::AssetId) {
+			T::VerifyOrigin::ensure_origin(origin)?;
+			ensure!(<Verifications<T>>::contains_key(id), Error::<T>::NotVerified);
+
+			<Verifications<T>>::remove(id);
+			Self::deposit_event(RawEvent::VerificationRevoked(id));
+		}
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where
+		<T as frame_system::Trait>::AccountId,
+		<T as frame_system::Trait>::Hash,
+		<T as balances::Trait>::Balance,
+		<T as Trait>::AssetId,
+	{
+		/// Some assets were issued. \[asset_id, owner, total_supply\]
+		Issued(AssetId, AccountId, Balance),
+		/// Some assets were issued by the system(e.g. lpt, pool tokens) \[asset_id, total_supply]
+		IssuedBySystem(AssetId, Balance),
+		/// Some assets were transferred. \[asset_id, from, to, amount\]
+		Transferred(AssetId, AccountId, AccountId, Balance),
+		/// Some assets were minted. \[asset_id, owner, balance]
+		Minted(AssetId, AccountId, Balance),
+		/// Some assets were burned. \[asset_id, owner, balance]
+		Burned(AssetId, AccountId, Balance),
+		/// Some assets were destroyed. \[asset_id, owner, balance\]
+		Destroyed(AssetId, AccountId, Balance),
+		/// Some assets were reserved. \[asset_id, owner, balance\]
+		Reserved(AssetId, AccountId, Balance),
+		/// Some assets were unreserved. \[asset_id, owner, balance\]
+		Unreserved(AssetId, AccountId, Balance),
+		/// The allowance of a spender was set. \[asset_id, owner, spender, amount\]
+		Approval(AssetId, AccountId, AccountId, Balance),
+		/// The metadata of an asset was set. \[asset_id, name, symbol, decimals\]
+		MetadataSet(AssetId, Vec<u8>, Vec<u8>, u8),
+		/// The metadata of an asset was cleared. \[asset_id\]
+		MetadataCleared(AssetId),
+		/// The balance of an account in an asset was frozen. \[asset_id, who\]
+		Frozen(AssetId, AccountId),
+		/// The balance of an account in an asset was thawed. \[asset_id, who\]
+		Thawed(AssetId, AccountId),
+		/// Every balance of an asset was frozen. \[asset_id\]
+		AssetFrozen(AssetId),
+		/// Every balance of an asset was thawed. \[asset_id\]
+		AssetThawed(AssetId),
+		/// The owner of an asset was changed. \[asset_id, owner\]
+		OwnerChanged(AssetId, AccountId),
+		/// The team of an asset was appointed. \[asset_id, issuer, admin, freezer\]
+		TeamChanged(AssetId, AccountId, AccountId, AccountId),
+		/// The balance of an account fell below the minimum balance of an asset, and its dust was
+		/// swept to the owner of the asset. \[asset_id, who, owner, dust\]
+		DustSwept(AssetId, AccountId, AccountId, Balance),
+		/// The balance of an account fell below the minimum balance of an asset, and its dust was
+		/// burned. \[asset_id, who, dust\]
+		DustBurned(AssetId, AccountId, Balance),
+		/// An asset was endorsed by governance. \[asset_id, proof\]
+		AssetVerified(AssetId, VerificationProof<Hash>),
+		/// The endorsement of an asset by governance was revoked. \[asset_id\]
+		VerificationRevoked(AssetId),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Transfer amount should be non-zero
+		AmountZero,
+		/// Account balance must be greater than or equal to the transfer amount
+		BalanceLow,
+		/// Balance should be non-zero
+		BalanceZero,
+		/// Not the creator of the asset
+		NotTheCreator,
+		/// Not the approver for the account
+		NotApproved,
+		/// Created by System
+		CreatedBySystem,
+		/// Name or symbol is longer than the string limit
+		BadMetadata,
+		/// Asset has no metadata
+		NoMetadata,
+		/// Amount would create an account below the minimum balance
+		BelowMinimum,
+		/// Total supply would overflow
+		Overflow,
+		/// Not the member of the team of the asset with the role
+		NoPermission,
+		/// The balance of the account in the asset is frozen
+		Frozen,
+		/// Every balance of the asset is frozen
+		AssetFrozen,
+		/// The asset is not verified
+		NotVerified,
+		/// The asset was never issued
+		UnknownAsset,
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Assets {
+		/// The number of units of assets held by any given account.
+		Balances: map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => T::Balance;
+		/// The number of units of assets reserved for any given account.
+		Reserved: map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => T::Balance;
+		/// The next asset identifier up for grabs.
+		pub NextAssetId get(fn next_asset_id): T::AssetId;
+		/// The total unit supply of an asset.
+		///
+		/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+		TotalSupply: map hasher(twox_64_concat) T::AssetId => T::Balance;
+		Creator: map hasher(blake2_128_concat) T::AssetId => T::AccountId;
+		/// The number of units of assets a spender may transfer from an owner.
+		Allowances: map hasher(blake2_128_concat) (T::AssetId, T::AccountId, T::AccountId) => T::Balance;
+		/// The name, symbol and decimals of an asset.
+		///
+		/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+		pub Metadata get(fn metadata): map hasher(twox_64_concat) T::AssetId => AssetMetadata<T::Balance>;
+		/// The team of an asset, if appointed by its owner.
+		///
+		/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+		Team: map hasher(twox_64_concat) T::AssetId => Option<AssetTeam<T::AccountId>>;
+		/// Whether the balance of an account in an asset is frozen.
+		pub FrozenAccounts get(fn is_frozen): map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => bool;
+		/// Whether every balance of an asset is frozen.
+		///
+		/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+		pub FrozenAssets get(fn is_asset_frozen): map hasher(twox_64_concat) T::AssetId => bool;
+		/// The minimum balance of the accounts of an asset, zero for assets issued by the system.
+		///
+		/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+		pub MinBalance get(fn min_balance): map hasher(twox_64_concat) T::AssetId => T::Balance;
+		/// The referendum or proposal proving the endorsement of a verified asset by governance.
+		///
+		/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+		pub Verifications get(fn verification): map hasher(twox_64_concat) T::AssetId => Option<VerificationProof<T::Hash>>;
+	}
+	add_extra_genesis {
+		/// The assets created at genesis, as `(id, creator, name, symbol, decimals)`.
+		config(assets): Vec<(T::AssetId, T::AccountId, Vec<u8>, Vec<u8>, u8)>;
+		/// The balances of the assets created at genesis, as `(id, who, amount)`.
+		config(balances): Vec<(T::AssetId, T::AccountId, T::Balance)>;
+		build(|config: &GenesisConfig<T>| {
+			// Create the account holding the native currency transferred to the system
+			let _ = <balances::Module<T> as Currency<_>>::make_free_balance_be(
+				&<Module<T>>::account_id(),
+				<balances::Module<T> as Currency<_>>::minimum_balance(),
+			);
+
+			let limit = T::StringLimit::get() as usize;
+			for (id, creator, name, symbol, decimals) in &config.assets {
+				assert!(!id.is_zero(), "Asset id 0 is the native currency");
+				assert!(!<Creator<T>>::contains_key(id), "Asset created twice at genesis");
+				assert!(name.len() <= limit && symbol.len() <= limit, "Asset metadata longer than StringLimit");
+				<Creator<T>>::insert(id, creator);
+				<Metadata<T>>::insert(id, AssetMetadata {
+					deposit: Zero::zero(),
+					name: name.clone(),
+					symbol: symbol.clone(),
+					decimals: *decimals,
+				});
+				// Keep the identifiers of the assets issued later clear of the genesis ones
+				<NextAssetId<T>>::mutate(|next| if *next <= *id {
+					*next = *id + One::one();
+				});
+			}
+
+			for (id, who, amount) in &config.balances {
+				assert!(<Creator<T>>::contains_key(id), "Balance of an asset not created at genesis");
+				<Module<T>>::deposit_free(*id, who, *amount);
+				<TotalSupply<T>>::mutate(id, |supply| *supply += *amount);
+			}
+		});
+	}
+}
+
+// The main implementation block for the module.
+impl<T: Trait> Module<T> {
+	// Public immutables
+
+	/// Get the asset `id` balance of `who`.
+	pub fn balance(id: T::AssetId, who: T::AccountId) -> T::Balance {
+		<Balances<T>>::get((id, who))
+	}
+
+	/// Get the total supply of an asset `id`.
+	pub fn total_supply(id: T::AssetId) -> T::Balance {
+		<TotalSupply<T>>::get(id)
+	}
+
+	/// Get the amount of asset `id` which `spender` may transfer from `owner`.
+	pub fn allowance(id: T::AssetId, owner: T::AccountId, spender: T::AccountId) -> T::Balance {
+		<Allowances<T>>::get((id, owner, spender))
+	}
+
+	/// Get the issuer, the admin and the freezer of the asset `id`, which are all its owner until
+	/// a team is appointed. Fails for assets issued by the system.
+	pub fn team(id: T::AssetId) -> Result<AssetTeam<T::AccountId>, DispatchError> {
+		ensure!(<Creator<T>>::contains_key(id), Error::<T>::CreatedBySystem);
+		Ok(<Team<T>>::get(id).unwrap_or_else(|| {
+			let owner = <Creator<T>>::get(id);
+			AssetTeam { issuer: owner.clone(), admin: owner.clone(), freezer: owner }
+		}))
+	}
+
+	/// Get whether the asset `id` is endorsed by governance.
+	pub fn is_verified(id: T::AssetId) -> bool {
+		<Verifications<T>>::contains_key(id)
+	}
+
+	/// The account holding the native currency transferred to the system.
+	pub fn account_id() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	pub fn mint_from_system(
+		id: &T::AssetId,
+		target: &T::AccountId,
+		amount: &T::Balance,
+	) -> dispatch::DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+		Self::ensure_asset_unfrozen(*id)?;
+		if *id == Zero::zero() {
+			// The imbalance raises the total issuance when dropped
+			let imbalance = <balances::Module<T> as Currency<_>>::deposit_creating(target, *amount);
+			ensure!(imbalance.peek() == *amount, Error::<T>::BelowMinimum);
+		} else {
+			let supply = <TotalSupply<T>>::get(*id).checked_add(amount).ok_or(Error::<T>::Overflow)?;
+			Self::ensure_can_deposit(*id, target, *amount)?;
+			Self::deposit_free(*id, target, *amount);
+			<TotalSupply<T>>::insert(*id, supply);
+		}
+		Self::deposit_event(RawEvent::Minted(*id, target.clone(), *amount));
+		Ok(())
+	}
+
+	pub fn burn_from_system(
+		id: &T::AssetId,
+		target: &T::AccountId,
+		amount: &T::Balance,
+	) -> dispatch::DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+		Self::ensure_unfrozen(*id, target)?;
+		if *id == Zero::zero() {
+			// The imbalance lowers the total issuance when dropped
+			let _ = <balances::Module<T> as Currency<_>>::withdraw(
+				target,
+				*amount,
+				WithdrawReasons::except(WithdrawReason::TransactionPayment),
+				ExistenceRequirement::AllowDeath,
+			)?;
+		} else {
+			ensure!(<Balances<T>>::get((*id, target)) >= *amount, Error::<T>::BalanceLow);
+			let dust = Self::withdraw_free(*id, target, *amount);
+			<TotalSupply<T>>::mutate(*id, |supply| *supply = supply.saturating_sub(*amount));
+			Self::handle_dust(*id, target, dust);
+		}
+		Self::deposit_event(RawEvent::Burned(*id, target.clone(), *amount));
+		Ok(())
+	}
+
+	pub fn transfer_from_system(
+		id: &T::AssetId,
+		target: &T::AccountId,
+		amount: &T::Balance,
+	) -> dispatch::DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+		Self::ensure_asset_unfrozen(*id)?;
+		if *id == Zero::zero() {
+			<balances::Module<T> as Currency<_>>::transfer(
+				&Self::account_id(),
+				target,
+				*amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+		} else {
+			Self::ensure_can_deposit(*id, target, *amount)?;
+			Self::deposit_free(*id, target, *amount);
+		}
+		Self::deposit_event(RawEvent::Minted(*id, target.clone(), *amount));
+		Ok(())
+	}
+
+	pub fn transfer_to_system(
+		id: &T::AssetId,
+		target: &T::AccountId,
+		amount: &T::Balance,
+	) -> dispatch::DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+		Self::ensure_unfrozen(*id, target)?;
+		if *id == Zero::zero() {
+			<balances::Module<T> as Currency<_>>::transfer(
+				target,
+				&Self::account_id(),
+				*amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+		} else {
+			ensure!(<Balances<T>>::get((*id, target)) >= *amount, Error::<T>::BalanceLow);
+			let dust = Self::withdraw_free(*id, target, *amount);
+			Self::handle_dust(*id, target, dust);
+		}
+		Self::deposit_event(RawEvent::Burned(*id, target.clone(), *amount));
+		Ok(())
+	}
+
+	pub fn issue_from_system(total: T::Balance) -> Result<T::AssetId, DispatchError> {
+		let id = Self::next_id();
+		<TotalSupply<T>>::insert(id, total);
+
+		Self::deposit_event(RawEvent::IssuedBySystem(id, total));
+		Ok(id)
+	}
+
+	/// Take the next asset identifier, skipping `0` which is saved for the native currency.
+	fn next_id() -> T::AssetId {
+		<NextAssetId<T>>::mutate(|id| {
+			if *id == Zero::zero() {
+				*id += One::one();
+			}
+			let current = *id;
+			*id += One::one();
+			current
+		})
+	}
+
+	fn do_transfer(
+		id: T::AssetId,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: T::Balance,
+	) -> dispatch::DispatchResult {
+		Self::ensure_unfrozen(id, from)?;
+		Self::move_balance(id, from, to, amount)
+	}
+
+	/// Move `amount` of the asset `id` from `from` to `to`, whether either is frozen or not.
+	fn move_balance(
+		id: T::AssetId,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: T::Balance,
+	) -> dispatch::DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+		if id.is_zero() {
+			<balances::Module<T> as Currency<_>>::transfer(from, to, amount, ExistenceRequirement::AllowDeath)?;
+		} else {
+			ensure!(<Balances<T>>::get((id, from)) >= amount, Error::<T>::BalanceLow);
+			// Moving a balance to its own account must not leave any dust
+			if from != to {
+				Self::ensure_can_deposit(id, to, amount)?;
+				let dust = Self::withdraw_free(id, from, amount);
+				Self::deposit_free(id, to, amount);
+				Self::handle_dust(id, from, dust);
+			}
+		}
+
+		Self::deposit_event(RawEvent::Transferred(id, from.clone(), to.clone(), amount));
+		Ok(())
+	}
+
+	/// Ensure no balance of the asset `id` is frozen. The native currency is never frozen.
+	fn ensure_asset_unfrozen(id: T::AssetId) -> dispatch::DispatchResult {
+		ensure!(id.is_zero() || !<FrozenAssets<T>>::get(id), Error::<T>::AssetFrozen);
+		Ok(())
+	}
+
+	/// Ensure the balance of `who` in the asset `id` may move.
+	fn ensure_unfrozen(id: T::AssetId, who: &T::AccountId) -> dispatch::DispatchResult {
+		Self::ensure_asset_unfrozen(id)?;
+		ensure!(id.is_zero() || !<FrozenAccounts<T>>::get((id, who)), Error::<T>::Frozen);
+		Ok(())
+	}
+
+	/// Ensure `who` is the creator of the asset `id`, which must not be issued by the system.
+	fn ensure_creator(id: T::AssetId, who: &T::AccountId) -> dispatch::DispatchResult {
+		ensure!(<Creator<T>>::contains_key(id), Error::<T>::CreatedBySystem);
+		ensure!(*who == <Creator<T>>::get(id), Error::<T>::NotTheCreator);
+		Ok(())
+	}
+
+	/// Ensure `who` may receive `amount` of the asset `id`, which must not leave it with less than
+	/// the minimum balance of the asset unless `who` is the account of a module.
+	fn ensure_can_deposit(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> dispatch::DispatchResult {
+		if Self::is_module_account(who) {
+			return Ok(());
+		}
+		let total = <Balances<T>>::get((id, who))
+			.saturating_add(<Reserved<T>>::get((id, who)))
+			.saturating_add(amount);
+		ensure!(total >= Self::min_balance(id), Error::<T>::BelowMinimum);
+		Ok(())
+	}
+
+	/// Add `amount` to the free balance of `who` in the asset `id`, which must be checked by
+	/// `ensure_can_deposit` first.
+	fn deposit_free(id: T::AssetId, who: &T::AccountId, amount: T::Balance) {
+		let free = <Balances<T>>::get((id, who));
+		Self::write_account(id, who, free + amount, <Reserved<T>>::get((id, who)));
+	}
+
+	/// Take `amount` from the free balance of `who` in the asset `id`, which must hold it. Returns
+	/// the dust, the rest of the free balance taken too for falling below the minimum balance of
+	/// the asset, to be given to `handle_dust`. The accounts of modules leave no dust.
+	fn withdraw_free(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> T::Balance {
+		let free = <Balances<T>>::get((id, who)) - amount;
+		let reserved = <Reserved<T>>::get((id, who));
+		if free.saturating_add(reserved) < Self::min_balance(id) && !Self::is_module_account(who) {
+			Self::write_account(id, who, Zero::zero(), reserved);
+			free
+		} else {
+			Self::write_account(id, who, free, reserved);
+			Zero::zero()
+		}
+	}
+
+	/// Whether `who` is the account of a module, derived from a `ModuleId` like the market pool
+	/// and the ledger's own account, which holds balances on behalf of others.
+	fn is_module_account(who: &T::AccountId) -> bool {
+		who.using_encoded(|encoded| encoded.starts_with(&ModuleId::TYPE_ID))
+	}
+
+	/// Sweep the `dust` taken from `who` in the asset `id` to the owner of the asset, or burn it if
+	/// the asset is issued by the system or the owner cannot receive it.
+	fn handle_dust(id: T::AssetId, who: &T::AccountId, dust: T::Balance) {
+		if dust.is_zero() {
+			return;
+		}
+		if <Creator<T>>::contains_key(id) {
+			let owner = <Creator<T>>::get(id);
+			if Self::ensure_can_deposit(id, &owner, dust).is_ok() {
+				Self::deposit_free(id, &owner, dust);
+				Self::deposit_event(RawEvent::DustSwept(id, who.clone(), owner, dust));
+				return;
+			}
+		}
+		<TotalSupply<T>>::mutate(id, |supply| *supply = supply.saturating_sub(dust));
+		Self::deposit_event(RawEvent::DustBurned(id, who.clone(), dust));
+	}
+
+	/// Write the `free` and `reserved` balances of `who` in the asset `id`, removing zero balances
+	/// from storage. `who` is referenced in the system module while it holds any of the asset.
+	fn write_account(id: T::AssetId, who: &T::AccountId, free: T::Balance, reserved: T::Balance) {
+		let existed = <Balances<T>>::contains_key((id, who)) || <Reserved<T>>::contains_key((id, who));
+		if free.is_zero() {
+			<Balances<T>>::remove((id, who));
+		} else {
+			<Balances<T>>::insert((id, who), free);
+		}
+		if reserved.is_zero() {
+			<Reserved<T>>::remove((id, who));
+		} else {
+			<Reserved<T>>::insert((id, who), reserved);
+		}
+		match (existed, !free.is_zero() || !reserved.is_zero()) {
+			(false, true) => frame_system::Module::<T>::inc_ref(who),
+			(true, false) => frame_system::Module::<T>::dec_ref(who),
+			_ => (),
+		}
+	}
+
+	fn set_allowance(id: T::AssetId, owner: &T::AccountId, spender: &T::AccountId, amount: T::Balance) {
+		if amount.is_zero() {
+			<Allowances<T>>::remove((id, owner, spender));
+		} else {
+			<Allowances<T>>::insert((id, owner, spender), amount);
+		}
+		Self::deposit_event(RawEvent::Approval(id, owner.clone(), spender.clone(), amount));
+	}
+}
+
+impl<T: Trait> MultiAsset<T::AccountId> for Module<T> {
+	type AssetId = T::AssetId;
+	type Balance = T::Balance;
+
+	fn balance(id: T::AssetId, who: &T::AccountId) -> T::Balance {
+		if id.is_zero() {
+			balances::Module::<T>::free_balance(who)
+		} else {
+			<Balances<T>>::get((id, who))
+		}
+	}
+
+	fn reserved_balance(id: T::AssetId, who: &T::AccountId) -> T::Balance {
+		if id.is_zero() {
+			balances::Module::<T>::reserved_balance(who)
+		} else {
+			<Reserved<T>>::get((id, who))
+		}
+	}
+
+	fn total_issuance(id: T::AssetId) -> T::Balance {
+		if id.is_zero() {
+			balances::Module::<T>::total_issuance()
+		} else {
+			<TotalSupply<T>>::get(id)
+		}
+	}
+
+	fn issue_from_system(total: T::Balance) -> Result<T::AssetId, DispatchError> {
+		Self::issue_from_system(total)
+	}
+
+	fn transfer(
+		id: T::AssetId,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: T::Balance,
+	) -> dispatch::DispatchResult {
+		Self::do_transfer(id, from, to, amount)
+	}
+
+	fn mint_into(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> dispatch::DispatchResult {
+		Self::mint_from_system(&id, who, &amount)
+	}
+
+	fn burn_from(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> dispatch::DispatchResult {
+		ensure!(<Self as MultiAsset<_>>::balance(id, who) >= amount, Error::<T>::BalanceLow);
+		Self::burn_from_system(&id, who, &amount)
+	}
+
+	fn transfer_to_system(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> dispatch::DispatchResult {
+		ensure!(<Self as MultiAsset<_>>::balance(id, who) >= amount, Error::<T>::BalanceLow);
+		Self::transfer_to_system(&id, who, &amount)
+	}
+
+	fn transfer_from_system(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> dispatch::DispatchResult {
+		Self::transfer_from_system(&id, who, &amount)
+	}
+
+	fn reserve(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> dispatch::DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+		Self::ensure_unfrozen(id, who)?;
+		if id.is_zero() {
+			<balances::Module<T> as ReservableCurrency<_>>::reserve(who, amount)?;
+		} else {
+			let free = <Balances<T>>::get((id, who));
+			ensure!(free >= amount, Error::<T>::BalanceLow);
+			let reserved = <Reserved<T>>::get((id, who));
+			Self::write_account(id, who, free - amount, reserved + amount);
+		}
+
+		Self::deposit_event(RawEvent::Reserved(id, who.clone(), amount));
+		Ok(())
+	}
+
+	fn unreserve(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> T::Balance {
+		if amount.is_zero() {
+			return Zero::zero();
+		}
+		let remaining = if id.is_zero() {
+			<balances::Module<T> as ReservableCurrency<_>>::unreserve(who, amount)
+		} else {
+			let reserved = <Reserved<T>>::get((id, who));
+			let actual = amount.min(reserved);
+			let free = <Balances<T>>::get((id, who));
+			Self::write_account(id, who, free + actual, reserved - actual);
+			amount - actual
+		};
+
+		Self::deposit_event(RawEvent::Unreserved(id, who.clone(), amount - remaining));
+		remaining
+	}
+
+	fn repatriate_reserved(
+		id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+	) -> Result<T::Balance, DispatchError> {
+		if amount.is_zero() {
+			return Ok(Zero::zero());
+		}
+		let remaining = if id.is_zero() {
+			<balances::Module<T> as ReservableCurrency<_>>::repatriate_reserved(
+				source,
+				dest,
+				amount,
+				BalanceStatus::Free,
+			)?
+		} else {
+			let reserved = <Reserved<T>>::get((id, source));
+			let actual = amount.min(reserved);
+			Self::write_account(id, source, <Balances<T>>::get((id, source)), reserved - actual);
+			Self::deposit_free(id, dest, actual);
+			amount - actual
+		};
+
+		Self::deposit_event(RawEvent::Transferred(id, source.clone(), dest.clone(), amount - remaining));
+		Ok(remaining)
+	}
+
+	fn ensure_transferable(id: T::AssetId) -> dispatch::DispatchResult {
+		Self::ensure_asset_unfrozen(id)
+	}
+
+	fn symbol(id: T::AssetId) -> Vec<u8> {
+		if id.is_zero() {
+			T::NativeSymbol::get().to_vec()
+		} else {
+			<Metadata<T>>::get(id).symbol
+		}
+	}
+
+	fn decimals(id: T::AssetId) -> u8 {
+		if id.is_zero() {
+			T::NativeDecimals::get()
+		} else {
+			<Metadata<T>>::get(id).decimals
+		}
+	}
+
+	fn set_system_metadata(
+		id: T::AssetId,
+		mut name: Vec<u8>,
+		mut symbol: Vec<u8>,
+		decimals: u8,
+	) -> dispatch::DispatchResult {
+		ensure!(!<Creator<T>>::contains_key(id), Error::<T>::NotTheCreator);
+		// Generated names and symbols are cut to the limit rather than rejected
+		let limit = T::StringLimit::get() as usize;
+		name.truncate(limit);
+		symbol.truncate(limit);
+
+		Self::deposit_event(RawEvent::MetadataSet(id, name.clone(), symbol.clone(), decimals));
+		<Metadata<T>>::insert(id, AssetMetadata { deposit: Zero::zero(), name, symbol, decimals });
+		Ok(())
+	}
+}
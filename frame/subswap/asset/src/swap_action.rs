@@ -0,0 +1,71 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Atomic swaps of the assets of the ledger.
+
+use codec::{Encode, Decode};
+use frame_support::{dispatch::DispatchResult, traits::Get, weights::Weight};
+use pallet_atomic_swap::SwapAction;
+use sp_runtime::RuntimeDebug;
+use crate::{Trait, Module, MultiAsset};
+
+/// A swap action moving `value` of the asset `asset_id` of the ledger, which may be the native
+/// currency.
+///
+/// The value is reserved from the source when the swap is created, moved from the reserved balance
+/// of the source to the target when it is claimed, even if the asset was frozen since, and
+/// unreserved when it is cancelled. The atomic swap module removes a swap whether its claim
+/// succeeds or not, so a claim which cannot move the value gives it back to the source.
+#[derive(Clone, RuntimeDebug, Eq, PartialEq, Encode, Decode)]
+pub struct AssetSwapAction<AssetId, Balance> {
+	asset_id: AssetId,
+	value: Balance,
+}
+
+impl<AssetId, Balance> AssetSwapAction<AssetId, Balance> {
+	/// Create a new swap action of `value` of the asset `asset_id`.
+	pub fn new(asset_id: AssetId, value: Balance) -> Self {
+		Self { asset_id, value }
+	}
+}
+
+impl<T> SwapAction<T::AccountId, T> for AssetSwapAction<T::AssetId, T::Balance>
+	where T: Trait + pallet_atomic_swap::Trait
+{
+	fn reserve(&self, source: &T::AccountId) -> DispatchResult {
+		<Module<T> as MultiAsset<_>>::reserve(self.asset_id, source, self.value)
+	}
+
+	fn claim(&self, source: &T::AccountId, target: &T::AccountId) -> bool {
+		match <Module<T> as MultiAsset<_>>::repatriate_reserved(self.asset_id, source, target, self.value) {
+			Ok(_) => true,
+			Err(_) => {
+				<Module<T> as MultiAsset<_>>::unreserve(self.asset_id, source, self.value);
+				false
+			}
+		}
+	}
+
+	fn weight(&self) -> Weight {
+		// The balances and reserved balances of both accounts, and the reference on the target
+		T::DbWeight::get().reads_writes(5, 5)
+	}
+
+	fn cancel(&self, source: &T::AccountId) {
+		<Module<T> as MultiAsset<_>>::unreserve(self.asset_id, source, self.value);
+	}
+}
@@ -0,0 +1,715 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the subswap asset module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{
+	impl_outer_origin, assert_ok, assert_noop, parameter_types, weights::Weight, traits::UnfilteredDispatchable,
+};
+use sp_core::H256;
+use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type MaximumBlockLength = MaximumBlockLength;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = frame_system::Module<Test>;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const AssetModuleId: ModuleId = ModuleId(*b"py/subas");
+	pub const MetadataDepositBase: u64 = 10;
+	pub const MetadataDepositPerByte: u64 = 1;
+	pub const StringLimit: u32 = 8;
+	pub const NativeSymbol: &'static [u8] = b"SUB";
+	pub const NativeDecimals: u8 = 12;
+}
+impl Trait for Test {
+	type Event = ();
+	type AssetId = u32;
+	type ModuleId = AssetModuleId;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type NativeSymbol = NativeSymbol;
+	type NativeDecimals = NativeDecimals;
+	type VerifyOrigin = frame_system::EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const ProofLimit: u32 = 1024;
+}
+impl pallet_atomic_swap::Trait for Test {
+	type Event = ();
+	type SwapAction = AssetSwapAction<u32, u64>;
+	type ProofLimit = ProofLimit;
+}
+type Assets = Module<Test>;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 100), (2, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	GenesisConfig::<Test>::default().assimilate_storage(&mut t).unwrap();
+	t.into()
+}
+
+#[test]
+fn issuing_asset_units_to_issuer_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_eq!(Assets::balance(1, 1), 100);
+		assert_eq!(Assets::next_asset_id(), 2);
+	});
+}
+
+#[test]
+fn querying_total_supply_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_eq!(Assets::balance(1, 1), 100);
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 50));
+		assert_eq!(Assets::balance(1, 1), 50);
+		assert_eq!(Assets::balance(1, 2), 50);
+		assert_ok!(Assets::transfer(Origin::signed(2), 1, 3, 31));
+		assert_eq!(Assets::balance(1, 1), 50);
+		assert_eq!(Assets::balance(1, 2), 19);
+		assert_eq!(Assets::balance(1, 3), 31);
+		assert_ok!(Assets::destroy(Origin::signed(3), 1));
+		assert_eq!(Assets::total_supply(1), 69);
+	});
+}
+
+#[test]
+fn transferring_amount_above_available_balance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_eq!(Assets::balance(1, 1), 100);
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 50));
+		assert_eq!(Assets::balance(1, 1), 50);
+		assert_eq!(Assets::balance(1, 2), 50);
+	});
+}
+
+#[test]
+fn transferring_amount_more_than_available_balance_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_eq!(Assets::balance(1, 1), 100);
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 50));
+		assert_eq!(Assets::balance(1, 1), 50);
+		assert_eq!(Assets::balance(1, 2), 50);
+		assert_ok!(Assets::destroy(Origin::signed(1), 1));
+		assert_eq!(Assets::balance(1, 1), 0);
+		assert_noop!(Assets::transfer(Origin::signed(1), 1, 1, 50), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn transferring_less_than_one_unit_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_eq!(Assets::balance(1, 1), 100);
+		assert_noop!(Assets::transfer(Origin::signed(1), 1, 2, 0), Error::<Test>::AmountZero);
+	});
+}
+
+#[test]
+fn transferring_more_units_than_total_supply_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_eq!(Assets::balance(1, 1), 100);
+		assert_noop!(Assets::transfer(Origin::signed(1), 1, 2, 101), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn destroying_asset_balance_with_positive_balance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_eq!(Assets::balance(1, 1), 100);
+		assert_ok!(Assets::destroy(Origin::signed(1), 1));
+	});
+}
+
+#[test]
+fn destroying_asset_balance_with_zero_balance_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_eq!(Assets::balance(1, 2), 0);
+		assert_noop!(Assets::destroy(Origin::signed(2), 1), Error::<Test>::BalanceZero);
+	});
+}
+
+#[test]
+fn minting_by_creator_should_increase_total_supply() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_noop!(Assets::mint(Origin::signed(2), 1, 2, 10), Error::<Test>::NoPermission);
+		assert_ok!(Assets::mint(Origin::signed(1), 1, 2, 10));
+		assert_eq!(Assets::balance(1, 2), 10);
+		assert_eq!(Assets::total_supply(1), 110);
+		assert_noop!(Assets::mint(Origin::signed(1), 1, 2, u64::max_value() - 109), Error::<Test>::Overflow);
+	});
+}
+
+#[test]
+fn issuing_from_system_should_skip_native_id() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Assets::issue_from_system(0), Ok(1));
+		assert_eq!(Assets::issue_from_system(0), Ok(2));
+		assert_eq!(<Assets as MultiAsset<u64>>::total_issuance(2), 0);
+	});
+}
+
+#[test]
+fn multi_asset_transfer_should_work_for_native_and_issued_assets() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer(1, &1, &3, 40));
+		assert_eq!(<Assets as MultiAsset<u64>>::balance(1, &3), 40);
+
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer(0, &1, &3, 40));
+		assert_eq!(<Assets as MultiAsset<u64>>::balance(0, &1), 60);
+		assert_eq!(<Assets as MultiAsset<u64>>::balance(0, &3), 40);
+	});
+}
+
+#[test]
+fn multi_asset_reserve_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_ok!(<Assets as MultiAsset<u64>>::reserve(1, &1, 30));
+		assert_eq!(<Assets as MultiAsset<u64>>::balance(1, &1), 70);
+		assert_eq!(<Assets as MultiAsset<u64>>::reserved_balance(1, &1), 30);
+		assert_noop!(<Assets as MultiAsset<u64>>::reserve(1, &1, 71), Error::<Test>::BalanceLow);
+
+		assert_eq!(<Assets as MultiAsset<u64>>::unreserve(1, &1, 40), 10);
+		assert_eq!(<Assets as MultiAsset<u64>>::balance(1, &1), 100);
+		assert_eq!(<Assets as MultiAsset<u64>>::reserved_balance(1, &1), 0);
+	});
+}
+
+#[test]
+fn multi_asset_burn_should_not_underflow() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_noop!(<Assets as MultiAsset<u64>>::burn_from(1, &1, 101), Error::<Test>::BalanceLow);
+		assert_ok!(<Assets as MultiAsset<u64>>::burn_from(1, &1, 100));
+		assert_eq!(<Assets as MultiAsset<u64>>::total_issuance(1), 0);
+	});
+}
+
+#[test]
+fn multi_asset_transfer_to_system_should_keep_total_issuance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_noop!(<Assets as MultiAsset<u64>>::transfer_to_system(1, &1, 101), Error::<Test>::BalanceLow);
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer_to_system(1, &1, 40));
+		assert_eq!(<Assets as MultiAsset<u64>>::balance(1, &1), 60);
+		assert_eq!(<Assets as MultiAsset<u64>>::total_issuance(1), 100);
+
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer_from_system(1, &2, 40));
+		assert_eq!(<Assets as MultiAsset<u64>>::balance(1, &2), 40);
+		assert_eq!(<Assets as MultiAsset<u64>>::total_issuance(1), 100);
+	});
+}
+
+#[test]
+fn transferring_from_owner_within_allowance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_ok!(Assets::approve(Origin::signed(1), 1, 2, 50));
+		assert_eq!(Assets::allowance(1, 1, 2), 50);
+
+		assert_ok!(Assets::transfer_from(Origin::signed(2), 1, 1, 3, 30));
+		assert_eq!(Assets::balance(1, 1), 70);
+		assert_eq!(Assets::balance(1, 3), 30);
+		assert_eq!(Assets::allowance(1, 1, 2), 20);
+		assert_noop!(Assets::transfer_from(Origin::signed(2), 1, 1, 3, 21), Error::<Test>::NotApproved);
+		assert_noop!(Assets::transfer_from(Origin::signed(3), 1, 1, 3, 1), Error::<Test>::NotApproved);
+	});
+}
+
+#[test]
+fn transferring_from_owner_above_balance_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_ok!(Assets::approve(Origin::signed(1), 1, 2, 200));
+		assert_noop!(Assets::transfer_from(Origin::signed(2), 1, 1, 3, 101), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn changing_allowance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::increase_allowance(Origin::signed(1), 1, 2, 40));
+		assert_ok!(Assets::increase_allowance(Origin::signed(1), 1, 2, 10));
+		assert_eq!(Assets::allowance(1, 1, 2), 50);
+
+		assert_noop!(Assets::decrease_allowance(Origin::signed(1), 1, 2, 51), Error::<Test>::NotApproved);
+		assert_ok!(Assets::decrease_allowance(Origin::signed(1), 1, 2, 50));
+		assert_eq!(Assets::allowance(1, 1, 2), 0);
+		assert!(!<Allowances<Test>>::contains_key((1, 1, 2)));
+
+		assert_ok!(Assets::approve(Origin::signed(1), 1, 2, 30));
+		assert_ok!(Assets::approve(Origin::signed(1), 1, 2, 5));
+		assert_eq!(Assets::allowance(1, 1, 2), 5);
+	});
+}
+
+#[test]
+fn setting_metadata_should_reserve_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 1, b"Tether".to_vec(), b"USDT".to_vec(), 6));
+		assert_eq!(Assets::metadata(1), AssetMetadata {
+			deposit: 20,
+			name: b"Tether".to_vec(),
+			symbol: b"USDT".to_vec(),
+			decimals: 6,
+		});
+		assert_eq!(pallet_balances::Module::<Test>::reserved_balance(1), 20);
+		assert_eq!(<Assets as MultiAsset<u64>>::symbol(1), b"USDT".to_vec());
+		assert_eq!(<Assets as MultiAsset<u64>>::decimals(1), 6);
+
+		// A shorter name releases part of the deposit
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 1, b"T".to_vec(), b"USDT".to_vec(), 6));
+		assert_eq!(pallet_balances::Module::<Test>::reserved_balance(1), 15);
+
+		assert_ok!(Assets::clear_metadata(Origin::signed(1), 1));
+		assert_eq!(pallet_balances::Module::<Test>::reserved_balance(1), 0);
+		assert_eq!(Assets::metadata(1), AssetMetadata::default());
+		assert_noop!(Assets::clear_metadata(Origin::signed(1), 1), Error::<Test>::NoMetadata);
+	});
+}
+
+#[test]
+fn setting_metadata_should_check_creator_and_length() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(2), 1, b"Tether".to_vec(), b"USDT".to_vec(), 6),
+			Error::<Test>::NotTheCreator
+		);
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 1, b"Tether USD".to_vec(), b"USDT".to_vec(), 6),
+			Error::<Test>::BadMetadata
+		);
+
+		assert_eq!(<Assets as MultiAsset<u64>>::issue_from_system(0), Ok(2));
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 2, b"LP".to_vec(), b"LP".to_vec(), 6),
+			Error::<Test>::CreatedBySystem
+		);
+		assert_ok!(<Assets as MultiAsset<u64>>::set_system_metadata(2, b"SUB-USDT LP".to_vec(), b"SUB-USDT".to_vec(), 9));
+		assert_eq!(Assets::metadata(2).name, b"SUB-USDT".to_vec());
+		assert_eq!(Assets::metadata(2).deposit, 0);
+		assert_eq!(<Assets as MultiAsset<u64>>::symbol(0), b"SUB".to_vec());
+	});
+}
+
+#[test]
+fn native_currency_should_move_through_the_system_account() {
+	new_test_ext().execute_with(|| {
+		let system = Assets::account_id();
+		assert_eq!(pallet_balances::Module::<Test>::free_balance(system), 1);
+		let issuance = pallet_balances::Module::<Test>::total_issuance();
+
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer_to_system(0, &1, 40));
+		assert_eq!(pallet_balances::Module::<Test>::free_balance(1), 60);
+		assert_eq!(pallet_balances::Module::<Test>::free_balance(system), 41);
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer_from_system(0, &2, 40));
+		assert_eq!(pallet_balances::Module::<Test>::free_balance(2), 140);
+		assert_eq!(pallet_balances::Module::<Test>::total_issuance(), issuance);
+
+		// The system account is kept alive
+		assert!(<Assets as MultiAsset<u64>>::transfer_from_system(0, &2, 1).is_err());
+	});
+}
+
+#[test]
+fn native_currency_mint_and_burn_should_update_total_issuance() {
+	new_test_ext().execute_with(|| {
+		let issuance = pallet_balances::Module::<Test>::total_issuance();
+		assert_ok!(<Assets as MultiAsset<u64>>::mint_into(0, &3, 50));
+		assert_eq!(pallet_balances::Module::<Test>::free_balance(3), 50);
+		assert_eq!(pallet_balances::Module::<Test>::total_issuance(), issuance + 50);
+
+		assert_noop!(<Assets as MultiAsset<u64>>::burn_from(0, &3, 51), Error::<Test>::BalanceLow);
+		assert_ok!(<Assets as MultiAsset<u64>>::burn_from(0, &3, 20));
+		assert_eq!(pallet_balances::Module::<Test>::free_balance(3), 30);
+		assert_eq!(pallet_balances::Module::<Test>::total_issuance(), issuance + 30);
+	});
+}
+
+#[test]
+fn native_currency_should_respect_locks() {
+	use frame_support::traits::LockableCurrency;
+
+	new_test_ext().execute_with(|| {
+		pallet_balances::Module::<Test>::set_lock(*b"testlock", &1, 80, WithdrawReasons::all());
+		assert!(<Assets as MultiAsset<u64>>::transfer_to_system(0, &1, 40).is_err());
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer_to_system(0, &1, 20));
+	});
+}
+
+#[test]
+fn genesis_should_create_assets_with_balances() {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig::<Test> {
+		assets: vec![(3, 1, b"Tether".to_vec(), b"USDT".to_vec(), 6)],
+		balances: vec![(3, 1, 500), (3, 2, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+
+	sp_io::TestExternalities::from(t).execute_with(|| {
+		assert_eq!(Assets::balance(3, 2), 100);
+		assert_eq!(Assets::total_supply(3), 600);
+		assert_eq!(Assets::metadata(3), AssetMetadata {
+			deposit: 0,
+			name: b"Tether".to_vec(),
+			symbol: b"USDT".to_vec(),
+			decimals: 6,
+		});
+		// The creator set at genesis may mint, and new assets come after the genesis ones
+		assert_ok!(Assets::mint(Origin::signed(1), 3, 2, 50));
+		assert_ok!(Assets::issue(Origin::signed(2), 100, 1));
+		assert_eq!(Assets::balance(4, 2), 100);
+	});
+}
+
+#[test]
+fn frozen_account_should_not_move_its_balance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 50));
+		assert_noop!(Assets::freeze(Origin::signed(2), 1, 2), Error::<Test>::NoPermission);
+		assert_ok!(Assets::freeze(Origin::signed(1), 1, 2));
+		assert!(Assets::is_frozen((1, 2)));
+
+		assert_noop!(Assets::transfer(Origin::signed(2), 1, 3, 10), Error::<Test>::Frozen);
+		assert_noop!(Assets::burn(Origin::signed(2), 1, 2, 10), Error::<Test>::Frozen);
+		assert_noop!(Assets::destroy(Origin::signed(2), 1), Error::<Test>::Frozen);
+		assert_noop!(<Assets as MultiAsset<u64>>::transfer(1, &2, &3, 10), Error::<Test>::Frozen);
+		assert_noop!(<Assets as MultiAsset<u64>>::transfer_to_system(1, &2, 10), Error::<Test>::Frozen);
+		assert_noop!(<Assets as MultiAsset<u64>>::reserve(1, &2, 10), Error::<Test>::Frozen);
+		// A frozen account still receives the asset
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 10));
+
+		assert_noop!(Assets::thaw(Origin::signed(2), 1, 2), Error::<Test>::NoPermission);
+		assert_ok!(Assets::thaw(Origin::signed(1), 1, 2));
+		assert_ok!(Assets::transfer(Origin::signed(2), 1, 3, 10));
+		assert_eq!(Assets::balance(1, 2), 50);
+	});
+}
+
+#[test]
+fn frozen_asset_should_halt_every_transfer() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer_to_system(1, &1, 10));
+		assert_noop!(Assets::freeze_asset(Origin::signed(2), 1), Error::<Test>::NoPermission);
+		assert_ok!(Assets::freeze_asset(Origin::signed(1), 1));
+		assert!(Assets::is_asset_frozen(1));
+
+		assert_noop!(Assets::transfer(Origin::signed(1), 1, 2, 10), Error::<Test>::AssetFrozen);
+		assert_noop!(Assets::mint(Origin::signed(1), 1, 2, 10), Error::<Test>::AssetFrozen);
+		assert_noop!(Assets::force_transfer(Origin::signed(1), 1, 1, 2, 10), Error::<Test>::AssetFrozen);
+		assert_noop!(<Assets as MultiAsset<u64>>::transfer(1, &1, &2, 10), Error::<Test>::AssetFrozen);
+		assert_noop!(<Assets as MultiAsset<u64>>::transfer_from_system(1, &2, 10), Error::<Test>::AssetFrozen);
+		assert_noop!(<Assets as MultiAsset<u64>>::mint_into(1, &2, 10), Error::<Test>::AssetFrozen);
+		assert_noop!(<Assets as MultiAsset<u64>>::burn_from(1, &1, 10), Error::<Test>::AssetFrozen);
+		// The native currency and other assets are not affected
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer(0, &1, &2, 10));
+
+		assert_noop!(Assets::thaw_asset(Origin::signed(2), 1), Error::<Test>::NoPermission);
+		assert_ok!(Assets::thaw_asset(Origin::signed(1), 1));
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer_from_system(1, &2, 10));
+		assert_eq!(Assets::balance(1, 2), 10);
+	});
+}
+
+#[test]
+fn team_should_hold_the_roles_of_the_asset() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_eq!(Assets::team(1), Ok(AssetTeam { issuer: 1, admin: 1, freezer: 1 }));
+		assert_noop!(Assets::set_team(Origin::signed(2), 1, 2, 3, 4), Error::<Test>::NotTheCreator);
+		assert_ok!(Assets::set_team(Origin::signed(1), 1, 2, 3, 4));
+		assert_eq!(Assets::team(1), Ok(AssetTeam { issuer: 2, admin: 3, freezer: 4 }));
+
+		assert_noop!(Assets::mint(Origin::signed(1), 1, 5, 10), Error::<Test>::NoPermission);
+		assert_ok!(Assets::mint(Origin::signed(2), 1, 5, 10));
+		assert_noop!(Assets::freeze(Origin::signed(3), 1, 5), Error::<Test>::NoPermission);
+		assert_ok!(Assets::freeze(Origin::signed(4), 1, 5));
+
+		// The admin moves the balance of a frozen account
+		assert_noop!(Assets::force_transfer(Origin::signed(4), 1, 5, 1, 10), Error::<Test>::NoPermission);
+		assert_ok!(Assets::force_transfer(Origin::signed(3), 1, 5, 1, 10));
+		assert_eq!(Assets::balance(1, 5), 0);
+		assert_eq!(Assets::balance(1, 1), 110);
+
+		assert_noop!(Assets::thaw(Origin::signed(4), 1, 5), Error::<Test>::NoPermission);
+		assert_ok!(Assets::thaw(Origin::signed(3), 1, 5));
+
+		// Assets issued by the system have no team
+		assert_eq!(<Assets as MultiAsset<u64>>::issue_from_system(0), Ok(2));
+		assert_noop!(Assets::freeze_asset(Origin::signed(1), 2), Error::<Test>::CreatedBySystem);
+	});
+}
+
+#[test]
+fn transferring_ownership_should_move_metadata_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 1, b"Tether".to_vec(), b"USDT".to_vec(), 6));
+		assert_noop!(Assets::transfer_ownership(Origin::signed(2), 1, 2), Error::<Test>::NotTheCreator);
+		assert_ok!(Assets::transfer_ownership(Origin::signed(1), 1, 2));
+
+		assert_eq!(pallet_balances::Module::<Test>::reserved_balance(1), 0);
+		assert_eq!(pallet_balances::Module::<Test>::reserved_balance(2), 20);
+		assert_noop!(Assets::clear_metadata(Origin::signed(1), 1), Error::<Test>::NotTheCreator);
+		assert_ok!(Assets::clear_metadata(Origin::signed(2), 1));
+		assert_eq!(pallet_balances::Module::<Test>::reserved_balance(2), 0);
+
+		// The roles of an asset without a team follow its owner
+		assert_noop!(Assets::mint(Origin::signed(1), 1, 3, 10), Error::<Test>::NoPermission);
+		assert_ok!(Assets::mint(Origin::signed(2), 1, 3, 10));
+	});
+}
+
+#[test]
+fn balance_below_minimum_should_be_swept_to_owner() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Assets::issue(Origin::signed(1), 5, 10), Error::<Test>::BelowMinimum);
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 10));
+		assert_eq!(Assets::min_balance(1), 10);
+		assert_noop!(Assets::transfer(Origin::signed(1), 1, 2, 5), Error::<Test>::BelowMinimum);
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 50));
+
+		// The dust of 5 left by account 2 goes back to the owner
+		assert_ok!(Assets::transfer(Origin::signed(2), 1, 3, 45));
+		assert_eq!(Assets::balance(1, 2), 0);
+		assert_eq!(Assets::balance(1, 3), 45);
+		assert_eq!(Assets::balance(1, 1), 55);
+		assert!(!<Balances<Test>>::contains_key((1, 2)));
+		assert_eq!(Assets::total_supply(1), 100);
+
+		// The owner cannot receive its own dust, which is burned
+		assert_ok!(Assets::burn(Origin::signed(1), 1, 1, 50));
+		assert_eq!(Assets::balance(1, 1), 0);
+		assert!(!<Balances<Test>>::contains_key((1, 1)));
+		assert_eq!(Assets::total_supply(1), 45);
+		assert_noop!(<Assets as MultiAsset<u64>>::transfer_from_system(1, &4, 9), Error::<Test>::BelowMinimum);
+	});
+}
+
+#[test]
+fn module_accounts_should_keep_balance_below_minimum() {
+	new_test_ext().execute_with(|| {
+		let pool: u64 = ModuleId(*b"py/subsw").into_account();
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 10));
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, pool, 50));
+
+		// The reserves held by the market pool are not dust
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer(1, &pool, &2, 45));
+		assert_eq!(Assets::balance(1, pool), 5);
+		assert_eq!(Assets::balance(1, 1), 50);
+		assert_eq!(Assets::total_supply(1), 100);
+		assert_ok!(Assets::transfer(Origin::signed(2), 1, pool, 3));
+		assert_eq!(Assets::balance(1, pool), 8);
+	});
+}
+
+#[test]
+fn accounts_holding_assets_should_not_be_reaped() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert_eq!(frame_system::Module::<Test>::refs(&2), 0);
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 10));
+		assert_eq!(frame_system::Module::<Test>::refs(&2), 1);
+
+		// A reserved balance keeps the account referenced
+		assert_ok!(<Assets as MultiAsset<u64>>::reserve(1, &2, 10));
+		assert!(!<Balances<Test>>::contains_key((1, 2)));
+		assert_eq!(frame_system::Module::<Test>::refs(&2), 1);
+		assert_noop!(
+			<Assets as MultiAsset<u64>>::transfer(0, &2, &3, 100),
+			pallet_balances::Error::<Test, _>::KeepAlive
+		);
+
+		assert_eq!(<Assets as MultiAsset<u64>>::unreserve(1, &2, 10), 0);
+		assert_ok!(Assets::destroy(Origin::signed(2), 1));
+		assert!(!<Reserved<Test>>::contains_key((1, 2)));
+		assert_eq!(frame_system::Module::<Test>::refs(&2), 0);
+		assert_ok!(<Assets as MultiAsset<u64>>::transfer(0, &2, &3, 100));
+		assert_eq!(pallet_balances::Module::<Test>::free_balance(2), 0);
+	});
+}
+
+#[test]
+fn governance_should_verify_assets() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		assert!(!Assets::is_verified(1));
+		assert_noop!(
+			Assets::verify_asset(Origin::signed(1), 1, VerificationProof::Referendum(0)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Assets::verify_asset(Origin::root(), 2, VerificationProof::Referendum(0)),
+			Error::<Test>::UnknownAsset
+		);
+		assert_eq!(Assets::issue_from_system(0), Ok(2));
+		assert_noop!(
+			Assets::verify_asset(Origin::root(), 2, VerificationProof::Referendum(0)),
+			Error::<Test>::CreatedBySystem
+		);
+
+		assert_ok!(Assets::verify_asset(Origin::root(), 1, VerificationProof::Referendum(0)));
+		assert!(Assets::is_verified(1));
+		let proposal = H256::repeat_byte(1);
+		assert_ok!(Assets::verify_asset(Origin::root(), 1, VerificationProof::Proposal(proposal)));
+		assert_eq!(Assets::verification(1), Some(VerificationProof::Proposal(proposal)));
+
+		assert_noop!(Assets::revoke_verification(Origin::signed(1), 1), DispatchError::BadOrigin);
+		assert_ok!(Assets::revoke_verification(Origin::root(), 1));
+		assert!(!Assets::is_verified(1));
+		assert_eq!(Assets::verification(1), None);
+		assert_noop!(Assets::revoke_verification(Origin::root(), 1), Error::<Test>::NotVerified);
+	});
+}
+
+#[test]
+fn atomic_swap_should_reserve_and_transfer_assets() {
+	use pallet_atomic_swap::Call as SwapCall;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		let proof = b"secret".to_vec();
+		let hashed_proof = sp_io::hashing::blake2_256(&proof);
+		assert_ok!(SwapCall::<Test>::create_swap(2, hashed_proof, AssetSwapAction::new(1, 50), 10)
+			.dispatch_bypass_filter(Origin::signed(1)));
+		assert_eq!(<Assets as MultiAsset<u64>>::balance(1, &1), 50);
+		assert_eq!(<Assets as MultiAsset<u64>>::reserved_balance(1, &1), 50);
+
+		assert_ok!(SwapCall::<Test>::claim_swap(proof, AssetSwapAction::new(1, 50))
+			.dispatch_bypass_filter(Origin::signed(2)));
+		assert_eq!(<Assets as MultiAsset<u64>>::reserved_balance(1, &1), 0);
+		assert_eq!(Assets::balance(1, 2), 50);
+
+		// The native currency swaps the same way
+		let proof = b"native".to_vec();
+		let hashed_proof = sp_io::hashing::blake2_256(&proof);
+		assert_ok!(SwapCall::<Test>::create_swap(3, hashed_proof, AssetSwapAction::new(0, 10), 10)
+			.dispatch_bypass_filter(Origin::signed(1)));
+		assert_eq!(pallet_balances::Module::<Test>::reserved_balance(1), 10);
+		assert_ok!(SwapCall::<Test>::claim_swap(proof, AssetSwapAction::new(0, 10))
+			.dispatch_bypass_filter(Origin::signed(3)));
+		assert_eq!(pallet_balances::Module::<Test>::free_balance(3), 10);
+	});
+}
+
+#[test]
+fn cancelled_atomic_swap_should_unreserve_assets() {
+	use pallet_atomic_swap::Call as SwapCall;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		let hashed_proof = sp_io::hashing::blake2_256(b"secret");
+		assert_ok!(SwapCall::<Test>::create_swap(2, hashed_proof, AssetSwapAction::new(1, 50), 10)
+			.dispatch_bypass_filter(Origin::signed(1)));
+		assert!(SwapCall::<Test>::cancel_swap(2, hashed_proof).dispatch_bypass_filter(Origin::signed(1)).is_err());
+
+		frame_system::Module::<Test>::set_block_number(10);
+		assert_ok!(SwapCall::<Test>::cancel_swap(2, hashed_proof).dispatch_bypass_filter(Origin::signed(1)));
+		assert_eq!(Assets::balance(1, 1), 100);
+		assert_eq!(<Assets as MultiAsset<u64>>::reserved_balance(1, &1), 0);
+		assert!(!<Reserved<Test>>::contains_key((1, 1)));
+	});
+}
+
+#[test]
+fn claiming_atomic_swap_of_frozen_asset_should_not_strand_it() {
+	use pallet_atomic_swap::Call as SwapCall;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+		let proof = b"secret".to_vec();
+		let hashed_proof = sp_io::hashing::blake2_256(&proof);
+		assert_ok!(SwapCall::<Test>::create_swap(2, hashed_proof, AssetSwapAction::new(1, 50), 10)
+			.dispatch_bypass_filter(Origin::signed(1)));
+		assert_ok!(Assets::freeze_asset(Origin::signed(1), 1));
+
+		// The value reserved before the freeze is still delivered
+		assert_ok!(SwapCall::<Test>::claim_swap(proof, AssetSwapAction::new(1, 50))
+			.dispatch_bypass_filter(Origin::signed(2)));
+		assert_eq!(<Assets as MultiAsset<u64>>::reserved_balance(1, &1), 0);
+		assert!(!<Reserved<Test>>::contains_key((1, 1)));
+		assert_eq!(Assets::balance(1, 1), 50);
+		assert_eq!(Assets::balance(1, 2), 50);
+		assert_eq!(Assets::total_supply(1), 100);
+	});
+}
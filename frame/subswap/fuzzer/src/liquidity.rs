@@ -0,0 +1,77 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Running
+//! Running this fuzzer can be done with `cargo hfuzz run liquidity`. `honggfuzz` CLI options can
+//! be used by setting `HFUZZ_RUN_ARGS`, such as `-n 4` to use 4 threads.
+//!
+//! # Debugging a panic
+//! Once a panic is found, it can be debugged with
+//! `cargo hfuzz run-debug liquidity hfuzz_workspace/liquidity/*.fuzz`.
+//!
+//! # More information
+//! More information about `honggfuzz` can be found
+//! [here](https://docs.rs/honggfuzz/).
+
+use honggfuzz::fuzz;
+use sp_core::U256;
+use subswap::{Curve, CurveType, math};
+
+fn main() {
+	loop {
+		fuzz!(|data: ((u128, u128), (u128, u128), u128, u16, u32)| {
+			let (amounts, reserves, total_supply, fee, amplification) = data;
+			let fee = fee as u32;
+
+			println!("++ Deposit: {:?} to reserves {:?} with supply {}", amounts, reserves, total_supply);
+
+			// No input may panic on either curve
+			let stable = CurveType::StableSwap(amplification);
+			let _ = stable.liquidity_minted(amounts, reserves, total_supply, fee);
+			let _ = stable.liquidity_value(amounts.0, reserves, total_supply);
+
+			let curve = CurveType::ConstantProduct;
+			// Creating a pair mints the geometric mean of the deposits
+			if let Ok(minted) = curve.liquidity_minted(amounts, (0, 0), 0, fee) {
+				let minted = U256::from(minted);
+				assert!(minted * minted <= U256::from(amounts.0) * U256::from(amounts.1));
+			}
+			// Burning what was just minted never redeems more than was deposited
+			let new_reserves = match (reserves.0.checked_add(amounts.0), reserves.1.checked_add(amounts.1)) {
+				(Some(reserve0), Some(reserve1)) => (reserve0, reserve1),
+				_ => return,
+			};
+			if total_supply == 0 {
+				return;
+			}
+			if let Ok(minted) = curve.liquidity_minted(amounts, reserves, total_supply, fee) {
+				if let Some(new_supply) = total_supply.checked_add(minted) {
+					let (value0, value1) = curve.liquidity_value(minted, new_reserves, new_supply)
+						.expect("Value of a part of the supply of a pair of existing reserves fits in a balance");
+					if value0 > amounts.0 || value1 > amounts.1 {
+						println!("++ Minted {} redeeming {} and {}", minted, value0, value1);
+						panic!("liquidity redeemed more than deposited");
+					}
+				}
+			}
+			// The pro-rata value of a part of the supply never exceeds the reserves
+			if let Ok((value0, value1)) = math::liquidity_value(amounts.0, reserves, total_supply) {
+				assert!(value0 <= reserves.0 && value1 <= reserves.1);
+			}
+		})
+	}
+}
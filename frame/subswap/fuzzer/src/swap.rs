@@ -0,0 +1,75 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Running
+//! Running this fuzzer can be done with `cargo hfuzz run swap`. `honggfuzz` CLI options can
+//! be used by setting `HFUZZ_RUN_ARGS`, such as `-n 4` to use 4 threads.
+//!
+//! # Debugging a panic
+//! Once a panic is found, it can be debugged with
+//! `cargo hfuzz run-debug swap hfuzz_workspace/swap/*.fuzz`.
+//!
+//! # More information
+//! More information about `honggfuzz` can be found
+//! [here](https://docs.rs/honggfuzz/).
+
+use honggfuzz::fuzz;
+use subswap::{Curve, CurveType};
+
+fn main() {
+	loop {
+		fuzz!(|data: (u128, u128, u128, u16, u32)| {
+			let (amount, reserve_in, reserve_out, fee, amplification) = data;
+			// Out of range fees and amplifications must fail rather than panic
+			let fee = fee as u32;
+
+			println!("++ Swap: {} with reserves {} and {}, fee {}", amount, reserve_in, reserve_out, fee);
+
+			// No input may panic on either curve
+			let stable = CurveType::StableSwap(amplification);
+			if let Ok(amount_out) = stable.amount_out(amount, reserve_in, reserve_out, fee) {
+				assert!(amount_out < reserve_out);
+			}
+			let _ = stable.amount_in(amount, reserve_in, reserve_out, fee);
+
+			// Swaps of the constant product never decrease `k`
+			let curve = CurveType::ConstantProduct;
+			let k = Curve::<u128>::invariant(&curve, (reserve_in, reserve_out)).expect("the product of balances fits");
+			if let Ok(amount_out) = curve.amount_out(amount, reserve_in, reserve_out, fee) {
+				assert!(amount_out < reserve_out);
+				// The pair rejects inputs overflowing its reserve
+				if let Some(new_reserve_in) = reserve_in.checked_add(amount) {
+					assert_k_not_decreased(k, curve.invariant((new_reserve_in, reserve_out - amount_out)).unwrap());
+				}
+			}
+			if let Ok(amount_in) = curve.amount_in(amount, reserve_in, reserve_out, fee) {
+				assert!(amount < reserve_out);
+				if let Some(new_reserve_in) = reserve_in.checked_add(amount_in) {
+					assert_k_not_decreased(k, curve.invariant((new_reserve_in, reserve_out - amount)).unwrap());
+				}
+			}
+		})
+	}
+}
+
+fn assert_k_not_decreased(k: sp_core::U256, new_k: sp_core::U256) {
+	if new_k < k {
+		println!("++ k before {}", k);
+		println!("+++++ k after {}", new_k);
+		panic!("k decreased");
+	}
+}
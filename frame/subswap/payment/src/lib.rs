@@ -0,0 +1,287 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Subswap Payment Module
+//!
+//! A signed extension paying transaction fees in any asset of the [subswap](../subswap/index.html)
+//! market.
+//!
+//! ## Overview
+//!
+//! [`ChargeAssetTxPayment`](./struct.ChargeAssetTxPayment.html) takes the place of
+//! `pallet_transaction_payment::ChargeTransactionPayment` in the signed extensions of a runtime.
+//! The fee is computed by the [transaction payment](../pallet_transaction_payment/index.html)
+//! module as usual, and then:
+//!
+//! * Without an asset, or with asset id `0`, the fee and the tip are withdrawn from the native
+//! balance of the transactor, exactly as `ChargeTransactionPayment` does.
+//! * With an asset, just enough of the asset is swapped for the native fee through the pair of the
+//! asset with the native currency (asset id `0`) before the transaction is dispatched, as long as
+//! it takes no more than the maximum asset fee signed by the transactor. The part of the fee left
+//! unused by the dispatch is swapped back to the asset afterwards.
+//!
+//! The native fee is handed to `pallet_transaction_payment::Trait::OnTransactionPayment` either
+//! way. When paying in an asset, the native currency is taken out of the pool account of the
+//! market rather than passing through the account of the transactor, which therefore needs no
+//! native balance. A refund which cannot be swapped back to the asset, such as one too small, is
+//! kept as fee.
+//!
+//! ## Usage
+//!
+//! Every runtime with both the transaction payment and the subswap modules implements the
+//! [`Trait`](./trait.Trait.html) of this module. Replace `ChargeTransactionPayment` in the
+//! `SignedExtra` of the runtime with `ChargeAssetTxPayment`, built by
+//! `ChargeAssetTxPayment::from(tip, asset_id, max_asset_fee)`.
+//!
+//! ## Related Modules
+//!
+//! * [`Transaction Payment`](../pallet_transaction_payment/index.html)
+//! * [`Subswap`](../subswap/index.html)
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod tests;
+
+use codec::{Encode, Decode};
+use frame_support::{
+	traits::{Currency, ExistenceRequirement, Imbalance, OnUnbalanced, WithdrawReason, WithdrawReasons},
+	weights::{DispatchInfo, PostDispatchInfo},
+	storage::{with_transaction, TransactionOutcome},
+	dispatch::DispatchResult,
+};
+use sp_runtime::{
+	FixedPointOperand, SaturatedConversion,
+	transaction_validity::{
+		ValidTransaction, InvalidTransaction, TransactionValidityError, TransactionValidity,
+	},
+	traits::{
+		Zero, Saturating, SignedExtension, Dispatchable, DispatchInfoOf, PostDispatchInfoOf,
+	},
+};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use subswap::{AssetIdOf, BalanceOf as AssetBalanceOf};
+
+/// The native balance in which transaction fees are computed.
+pub type BalanceOf<T> =
+	<<T as pallet_transaction_payment::Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as pallet_transaction_payment::Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::NegativeImbalance;
+type TransactionPayment<T> = pallet_transaction_payment::Module<T>;
+type Subswap<T> = subswap::Module<T>;
+
+/// The module configuration trait, implemented by every runtime with both the transaction payment
+/// and the subswap modules.
+///
+/// The `Currency` of the transaction payment module must be the native currency of the ledger of
+/// the subswap market, asset id `0`.
+pub trait Trait: pallet_transaction_payment::Trait + subswap::Trait {}
+
+impl<T: pallet_transaction_payment::Trait + subswap::Trait> Trait for T {}
+
+/// Require the transactor pay for themselves, in the native currency or in `asset_id` swapped for
+/// it through subswap, and maybe include a tip to gain additional priority in the queue.
+///
+/// No more than `max_asset_fee` of `asset_id` is swapped for the fee, including the tip. It is
+/// ignored when paying in the native currency.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeAssetTxPayment<T: Trait + Send + Sync> {
+	#[codec(compact)]
+	tip: BalanceOf<T>,
+	asset_id: Option<AssetIdOf<T>>,
+	#[codec(compact)]
+	max_asset_fee: AssetBalanceOf<T>,
+}
+
+impl<T: Trait + Send + Sync> ChargeAssetTxPayment<T> where
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+	BalanceOf<T>: Send + Sync + FixedPointOperand,
+{
+	/// utility constructor. Used only in client/factory code.
+	pub fn from(tip: BalanceOf<T>, asset_id: Option<AssetIdOf<T>>, max_asset_fee: AssetBalanceOf<T>) -> Self {
+		Self { tip, asset_id, max_asset_fee }
+	}
+
+	/// The asset swapped for the fee, if it is not paid in the native currency.
+	fn fee_asset(&self) -> Option<AssetIdOf<T>> {
+		self.asset_id.filter(|asset| !asset.is_zero())
+	}
+
+	fn withdraw_fee(
+		&self,
+		who: &T::AccountId,
+		info: &DispatchInfoOf<T::Call>,
+		len: usize,
+	) -> Result<(BalanceOf<T>, Option<NegativeImbalanceOf<T>>), TransactionValidityError> {
+		let tip = self.tip;
+		let fee = TransactionPayment::<T>::compute_fee(len as u32, info, tip);
+
+		// Only mess with balances if fee is not zero.
+		if fee.is_zero() {
+			return Ok((fee, None));
+		}
+
+		let reasons = if tip.is_zero() {
+			WithdrawReason::TransactionPayment.into()
+		} else {
+			WithdrawReason::TransactionPayment | WithdrawReason::Tip
+		};
+		let withdrawn = match self.fee_asset() {
+			// A failed swap or withdrawal leaves the pair untouched
+			Some(asset) => with_transaction(|| match self.swap_for_fee(who, asset, fee, reasons) {
+				Ok(imbalance) => TransactionOutcome::Commit(Ok(imbalance)),
+				Err(e) => TransactionOutcome::Rollback(Err(e)),
+			}),
+			None => T::Currency::withdraw(who, fee, reasons, ExistenceRequirement::KeepAlive),
+		};
+		match withdrawn {
+			Ok(imbalance) => Ok((fee, Some(imbalance))),
+			Err(_) => Err(InvalidTransaction::Payment.into()),
+		}
+	}
+
+	/// Swap at most `max_asset_fee` of `asset` of `who` for exactly `fee` of the native currency,
+	/// and withdraw it from the pool account of the market.
+	fn swap_for_fee(
+		&self,
+		who: &T::AccountId,
+		asset: AssetIdOf<T>,
+		fee: BalanceOf<T>,
+		reasons: WithdrawReasons,
+	) -> Result<NegativeImbalanceOf<T>, sp_runtime::DispatchError> {
+		let amount_out = fee.saturated_into::<u128>().saturated_into();
+		Subswap::<T>::swap_into_pool(who, asset, Zero::zero(), amount_out, self.max_asset_fee)?;
+		T::Currency::withdraw(&Subswap::<T>::account_id(), fee, reasons, ExistenceRequirement::KeepAlive)
+	}
+
+	/// Swap `refund` out of the fee `payed` by `who` back to `asset`, returning the rest of `payed`.
+	/// The whole of `payed` is returned if `refund` cannot be swapped, such as when it is too small.
+	fn refund_in_asset(
+		who: &T::AccountId,
+		asset: AssetIdOf<T>,
+		payed: NegativeImbalanceOf<T>,
+		refund: BalanceOf<T>,
+	) -> NegativeImbalanceOf<T> {
+		if refund.is_zero() {
+			return payed;
+		}
+		let native = Zero::zero();
+		let amount_in = refund.saturated_into::<u128>().saturated_into();
+		// The refund only reaches the pool account once the swap back succeeded, so a failed swap
+		// leaves both the pair and the fee untouched
+		with_transaction(|| match Subswap::<T>::swap_out_of_pool(who, native, asset, amount_in) {
+			Ok(_) => {
+				let (refund_imbalance, actual_payment) = payed.split(refund);
+				T::Currency::resolve_creating(&Subswap::<T>::account_id(), refund_imbalance);
+				TransactionOutcome::Commit(actual_payment)
+			}
+			Err(_) => TransactionOutcome::Rollback(payed),
+		})
+	}
+}
+
+impl<T: Trait + Send + Sync> sp_std::fmt::Debug for ChargeAssetTxPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeAssetTxPayment<{:?}, {:?}, {:?}>", self.tip, self.asset_id, self.max_asset_fee)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for ChargeAssetTxPayment<T> where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+{
+	const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type Pre = (
+		BalanceOf<T>,
+		Self::AccountId,
+		Option<NegativeImbalanceOf<T>>,
+		BalanceOf<T>,
+		Option<AssetIdOf<T>>,
+	);
+	fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> { Ok(()) }
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		_call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		let (fee, _) = self.withdraw_fee(who, info, len)?;
+		Ok(ValidTransaction {
+			priority: ChargeTransactionPayment::<T>::get_priority(len, info, fee),
+			..Default::default()
+		})
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		_call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let (fee, imbalance) = self.withdraw_fee(who, info, len)?;
+		Ok((self.tip, who.clone(), imbalance, fee, self.fee_asset()))
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		_result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let (tip, who, imbalance, fee, asset) = pre;
+		if let Some(payed) = imbalance {
+			let actual_fee = TransactionPayment::<T>::compute_actual_fee(
+				len as u32,
+				info,
+				post_info,
+				tip,
+			);
+			let refund = fee.saturating_sub(actual_fee);
+			let actual_payment = match asset {
+				Some(asset) => Self::refund_in_asset(&who, asset, payed, refund),
+				None => match T::Currency::deposit_into_existing(&who, refund) {
+					Ok(refund_imbalance) => {
+						// The refund cannot be larger than the up front payed max weight.
+						// `PostDispatchInfo::calc_unspent` guards against such a case.
+						match payed.offset(refund_imbalance) {
+							Ok(actual_payment) => actual_payment,
+							Err(_) => return Err(InvalidTransaction::Payment.into()),
+						}
+					}
+					// We do not recreate the account using the refund. The up front payment
+					// is gone in that case.
+					Err(_) => payed,
+				},
+			};
+			let imbalances = actual_payment.split(tip);
+			T::OnTransactionPayment::on_unbalanceds(Some(imbalances.0).into_iter()
+				.chain(Some(imbalances.1)));
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,308 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the subswap payment module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{
+	impl_outer_origin, impl_outer_dispatch, assert_ok, parameter_types,
+	traits::UnfilteredDispatchable,
+	weights::{Weight, IdentityFee},
+};
+use sp_core::H256;
+use sp_runtime::{Perbill, ModuleId, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		frame_system::System,
+		subswap::Subswap,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 4096;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type MaximumBlockLength = MaximumBlockLength;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = frame_system::Module<Test>;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const TransactionByteFee: u64 = 1;
+}
+impl pallet_transaction_payment::Trait for Test {
+	type Currency = pallet_balances::Module<Test>;
+	type OnTransactionPayment = ();
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = ();
+}
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const AssetModuleId: ModuleId = ModuleId(*b"py/subas");
+	pub const MetadataDepositBase: u64 = 10;
+	pub const MetadataDepositPerByte: u64 = 1;
+	pub const StringLimit: u32 = 8;
+	pub const NativeSymbol: &'static [u8] = b"SUB";
+	pub const NativeDecimals: u8 = 12;
+}
+impl subswap_asset::Trait for Test {
+	type Event = ();
+	type AssetId = u32;
+	type ModuleId = AssetModuleId;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type NativeSymbol = NativeSymbol;
+	type NativeDecimals = NativeDecimals;
+	type VerifyOrigin = frame_system::EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+parameter_types! {
+	pub const SubswapModuleId: ModuleId = ModuleId(*b"py/subsw");
+	pub const OracleSnapshotPeriod: u64 = 500;
+	pub const OracleSnapshotCount: u32 = 3;
+	pub const DefaultFee: u32 = 30;
+	pub const MaxOpenOrders: u32 = 3;
+	pub const MaxOrdersPerAccount: u32 = 2;
+	pub const MaxOrdersPerBlock: u32 = 2;
+	pub const MaxInitializedTicks: u32 = 4;
+}
+impl subswap::Trait for Test {
+	type Event = ();
+	type Assets = Assets;
+	type ModuleId = SubswapModuleId;
+	type OracleSnapshotPeriod = OracleSnapshotPeriod;
+	type OracleSnapshotCount = OracleSnapshotCount;
+	type DefaultFee = DefaultFee;
+	type GovernanceOrigin = frame_system::EnsureRoot<u64>;
+	type Call = Call;
+	type MaxOpenOrders = MaxOpenOrders;
+	type MaxOrdersPerAccount = MaxOrdersPerAccount;
+	type MaxOrdersPerBlock = MaxOrdersPerBlock;
+	type MaxInitializedTicks = MaxInitializedTicks;
+	type WeightInfo = ();
+}
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Assets = subswap_asset::Module<Test>;
+type Subswap = subswap::Module<Test>;
+
+const NATIVE: u32 = 0;
+const USDT: u32 = 1;
+const DOT: u32 = 2;
+const CALL: &<Test as frame_system::Trait>::Call = &Call::System(frame_system::Call::remark(Vec::new()));
+
+/// Account 1 holds every asset and provides the native-USDT pair. Account 2 holds USDT only.
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 1_000_000_000)],
+	}.assimilate_storage(&mut t).unwrap();
+	subswap_asset::GenesisConfig::<Test> {
+		assets: vec![
+			(USDT, 1, b"Tether".to_vec(), b"USDT".to_vec(), 6),
+			(DOT, 1, b"Polkadot".to_vec(), b"DOT".to_vec(), 10),
+		],
+		balances: vec![(USDT, 1, 1_000_000_000), (DOT, 1, 1_000_000_000), (USDT, 2, 1_000_000_000)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		assert_ok!(Subswap::mint_liquidity(Origin::signed(1), NATIVE, 1_000_000, USDT, 2_000_000));
+	});
+	ext
+}
+
+/// A dispatch info charging `weight` as fee.
+fn info_from_weight(weight: Weight) -> DispatchInfo {
+	DispatchInfo { weight, ..Default::default() }
+}
+
+/// A post dispatch info with the actual `weight` of the dispatch.
+fn post_info_from_weight(weight: Weight) -> PostDispatchInfo {
+	PostDispatchInfo { actual_weight: Some(weight), pays_fee: Default::default() }
+}
+
+#[test]
+fn pays_fee_in_native_currency_without_asset() {
+	new_test_ext().execute_with(|| {
+		let balance = Balances::free_balance(1);
+		let pre = ChargeAssetTxPayment::<Test>::from(0, None, 0)
+			.pre_dispatch(&1, CALL, &info_from_weight(1_000), 10)
+			.unwrap();
+		assert_eq!(Balances::free_balance(1), balance - 1_010);
+
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(
+			pre,
+			&info_from_weight(1_000),
+			&post_info_from_weight(500),
+			10,
+			&Ok(())
+		));
+		assert_eq!(Balances::free_balance(1), balance - 510);
+		assert_eq!(Subswap::reserves(3), (1_000_000, 2_000_000));
+	});
+}
+
+#[test]
+fn pays_fee_in_asset_swapped_through_pair() {
+	new_test_ext().execute_with(|| {
+		// 2_029 USDT is swapped for the fee of 1_010 in the native currency
+		let pre = ChargeAssetTxPayment::<Test>::from(0, Some(USDT), 10_000)
+			.pre_dispatch(&2, CALL, &info_from_weight(1_000), 10)
+			.unwrap();
+		assert_eq!(Assets::balance(USDT, 2), 1_000_000_000 - 2_029);
+		assert_eq!(Balances::free_balance(2), 0);
+		assert_eq!(Subswap::reserves(3), (1_000_000 - 1_010, 2_000_000 + 2_029));
+		assert_eq!(Balances::free_balance(Subswap::account_id()), 1_000_000 - 1_010);
+
+		// The unused 500 is swapped back for 998 USDT
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(
+			pre,
+			&info_from_weight(1_000),
+			&post_info_from_weight(500),
+			10,
+			&Ok(())
+		));
+		assert_eq!(Assets::balance(USDT, 2), 1_000_000_000 - 2_029 + 998);
+		assert_eq!(Balances::free_balance(2), 0);
+		assert_eq!(Subswap::reserves(3), (999_490, 2_001_031));
+		assert_eq!(Balances::free_balance(Subswap::account_id()), 999_490);
+	});
+}
+
+#[test]
+fn pays_fee_in_native_currency_with_native_asset_id() {
+	new_test_ext().execute_with(|| {
+		let balance = Balances::free_balance(1);
+		assert_ok!(ChargeAssetTxPayment::<Test>::from(0, Some(NATIVE), 10_000)
+			.pre_dispatch(&1, CALL, &info_from_weight(1_000), 10));
+		assert_eq!(Balances::free_balance(1), balance - 1_010);
+		assert_eq!(Subswap::reserves(3), (1_000_000, 2_000_000));
+	});
+}
+
+#[test]
+fn asset_without_pair_should_not_pay() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			ChargeAssetTxPayment::<Test>::from(0, Some(DOT), 10_000)
+				.validate(&1, CALL, &info_from_weight(1_000), 10),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert_eq!(
+			ChargeAssetTxPayment::<Test>::from(0, Some(USDT), 10_000)
+				.validate(&3, CALL, &info_from_weight(1_000), 10),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert_eq!(Assets::balance(DOT, 1), 1_000_000_000);
+		assert_eq!(Subswap::reserves(3), (1_000_000, 2_000_000));
+	});
+}
+
+#[test]
+fn failed_refund_in_asset_should_be_kept_as_fee() {
+	new_test_ext().execute_with(|| {
+		let pre = ChargeAssetTxPayment::<Test>::from(0, Some(USDT), 10_000)
+			.pre_dispatch(&2, CALL, &info_from_weight(1_000), 10)
+			.unwrap();
+		// The pool account can no longer pay out USDT
+		assert_ok!(subswap_asset::Call::<Test>::freeze(USDT, Subswap::account_id())
+			.dispatch_bypass_filter(Origin::signed(1)));
+
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(
+			pre,
+			&info_from_weight(1_000),
+			&post_info_from_weight(500),
+			10,
+			&Ok(())
+		));
+		assert_eq!(Assets::balance(USDT, 2), 1_000_000_000 - 2_029);
+		assert_eq!(Subswap::reserves(3), (1_000_000 - 1_010, 2_000_000 + 2_029));
+		assert_eq!(Balances::free_balance(Subswap::account_id()), 1_000_000 - 1_010);
+	});
+}
+
+#[test]
+fn asset_fee_above_signed_maximum_should_not_pay() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			ChargeAssetTxPayment::<Test>::from(0, Some(USDT), 2_028)
+				.validate(&2, CALL, &info_from_weight(1_000), 10),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert_eq!(Assets::balance(USDT, 2), 1_000_000_000);
+		assert_eq!(Subswap::reserves(3), (1_000_000, 2_000_000));
+
+		assert_ok!(ChargeAssetTxPayment::<Test>::from(0, Some(USDT), 2_029)
+			.pre_dispatch(&2, CALL, &info_from_weight(1_000), 10));
+		assert_eq!(Assets::balance(USDT, 2), 1_000_000_000 - 2_029);
+	});
+}
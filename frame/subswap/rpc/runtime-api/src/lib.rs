@@ -0,0 +1,207 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the subswap module.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding subswap quote and pool queries.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Encode, Codec, Decode};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+use sp_runtime::RuntimeDebug;
+use sp_runtime::traits::{MaybeDisplay, MaybeFromStr};
+
+/// The amounts of a swap along a path, from the input of the first pair to the output of the last.
+#[derive(Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Quote<Balance> {
+	/// The amount at every step of the path.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_vec_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_vec_from_string"))]
+	pub amounts: Vec<Balance>,
+}
+
+/// A pair of the market with its reserves, ordered by asset identifier.
+#[derive(Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PairInfo<AssetId, Balance> {
+	/// The liquidity provider token of the pair.
+	pub lp_token: AssetId,
+	/// The asset with the lower identifier.
+	pub token0: AssetId,
+	/// The asset with the higher identifier.
+	pub token1: AssetId,
+	/// The reserve of `token0`.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub reserve0: Balance,
+	/// The reserve of `token1`.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub reserve1: Balance,
+	/// The swap fee of the pair in basis points.
+	pub fee: u32,
+	/// The total issuance of the liquidity provider token.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub total_liquidity: Balance,
+}
+
+/// The assets redeemable by burning an amount of liquidity provider token.
+#[derive(Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct LiquidityValue<AssetId, Balance> {
+	/// The asset with the lower identifier.
+	pub token0: AssetId,
+	/// The amount of `token0`.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub amount0: Balance,
+	/// The asset with the higher identifier.
+	pub token1: AssetId,
+	/// The amount of `token1`.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub amount1: Balance,
+}
+
+/// The balance of an account in a single asset.
+#[derive(Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AssetBalance<Balance> {
+	/// The balance free to be transferred.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub free: Balance,
+	/// The balance reserved by the ledger.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub reserved: Balance,
+}
+
+#[cfg(feature = "std")]
+fn serialize_as_string<S: Serializer, T: std::fmt::Display>(t: &T, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&t.to_string())
+}
+
+#[cfg(feature = "std")]
+fn deserialize_from_string<'de, D: Deserializer<'de>, T: std::str::FromStr>(deserializer: D) -> Result<T, D::Error> {
+	let s = String::deserialize(deserializer)?;
+	s.parse::<T>().map_err(|_| serde::de::Error::custom("Parse from string failed"))
+}
+
+#[cfg(feature = "std")]
+fn serialize_vec_as_string<S: Serializer, T: std::fmt::Display>(t: &[T], serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.collect_seq(t.iter().map(|x| x.to_string()))
+}
+
+#[cfg(feature = "std")]
+fn deserialize_vec_from_string<'de, D: Deserializer<'de>, T: std::str::FromStr>(deserializer: D) -> Result<Vec<T>, D::Error> {
+	let v = Vec::<String>::deserialize(deserializer)?;
+	v.iter()
+		.map(|s| s.parse::<T>().map_err(|_| serde::de::Error::custom("Parse from string failed")))
+		.collect()
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the subswap market without submitting extrinsics.
+	pub trait SubswapApi<AccountId, AssetId, Balance> where
+		AccountId: Codec,
+		AssetId: Codec,
+		Balance: Codec + MaybeDisplay + MaybeFromStr,
+	{
+		/// Quote a swap of an exact `amount_in` along `path`.
+		///
+		/// Returns `None` if the path is invalid or a pair on it lacks liquidity.
+		fn quote_exact_in(path: Vec<AssetId>, amount_in: Balance) -> Option<Quote<Balance>>;
+
+		/// Quote a swap along `path` for an exact `amount_out`.
+		///
+		/// Returns `None` if the path is invalid or a pair on it lacks liquidity.
+		fn quote_exact_out(path: Vec<AssetId>, amount_out: Balance) -> Option<Quote<Balance>>;
+
+		/// Get the pair between two assets, in any order, with its reserves.
+		fn get_reserves(asset_a: AssetId, asset_b: AssetId) -> Option<PairInfo<AssetId, Balance>>;
+
+		/// List every pair of the market with its reserves.
+		fn list_pairs() -> Vec<PairInfo<AssetId, Balance>>;
+
+		/// Get the assets redeemable by burning `amount` of the liquidity provider token `lp_token`.
+		fn lp_share_value(lp_token: AssetId, amount: Balance) -> Option<LiquidityValue<AssetId, Balance>>;
+
+		/// Get the balance of `who` in the asset `asset_id`.
+		fn balance_of(asset_id: AssetId, who: AccountId) -> AssetBalance<Balance>;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_serialize_and_deserialize_properly_with_string() {
+		let pair = PairInfo {
+			lp_token: 3u32,
+			token0: 1,
+			token1: 2,
+			reserve0: 1_000_000_u128,
+			reserve1: u128::max_value(),
+			fee: 30,
+			total_liquidity: 1_999_999,
+		};
+
+		let json_str = r#"{"lpToken":3,"token0":1,"token1":2,"reserve0":"1000000","reserve1":"340282366920938463463374607431768211455","fee":30,"totalLiquidity":"1999999"}"#;
+
+		assert_eq!(serde_json::to_string(&pair).unwrap(), json_str);
+		assert_eq!(serde_json::from_str::<PairInfo<u32, u128>>(json_str).unwrap(), pair);
+	}
+
+	#[test]
+	fn should_serialize_and_deserialize_quotes_with_string() {
+		let quote = Quote { amounts: vec![10_000_u128, 39_486, 38_607] };
+
+		let json_str = r#"{"amounts":["10000","39486","38607"]}"#;
+
+		assert_eq!(serde_json::to_string(&quote).unwrap(), json_str);
+		assert_eq!(serde_json::from_str::<Quote<u128>>(json_str).unwrap(), quote);
+	}
+}
@@ -0,0 +1,229 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RPC interface for the subswap module.
+
+use std::sync::Arc;
+use codec::Codec;
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, MaybeDisplay, MaybeFromStr}};
+use sp_api::ProvideRuntimeApi;
+use subswap_runtime_api::{Quote, PairInfo, LiquidityValue, AssetBalance};
+pub use subswap_runtime_api::SubswapApi as SubswapRuntimeApi;
+pub use self::gen_client::Client as SubswapClient;
+
+/// Subswap RPC methods.
+///
+/// The response types are generic so that balances are serialized as strings, see
+/// [`subswap_runtime_api`].
+#[rpc]
+pub trait SubswapApi<BlockHash, AccountId, AssetId, Balance, QuoteResponse, PairResponse, ValueResponse, BalanceResponse> {
+	/// Quote a swap of an exact `amount_in` along `path`.
+	///
+	/// Returns `None` if the path is invalid or a pair on it lacks liquidity.
+	#[rpc(name = "subswap_quoteExactIn")]
+	fn quote_exact_in(
+		&self,
+		path: Vec<AssetId>,
+		amount_in: Balance,
+		at: Option<BlockHash>
+	) -> Result<Option<QuoteResponse>>;
+
+	/// Quote a swap along `path` for an exact `amount_out`.
+	///
+	/// Returns `None` if the path is invalid or a pair on it lacks liquidity.
+	#[rpc(name = "subswap_quoteExactOut")]
+	fn quote_exact_out(
+		&self,
+		path: Vec<AssetId>,
+		amount_out: Balance,
+		at: Option<BlockHash>
+	) -> Result<Option<QuoteResponse>>;
+
+	/// Get the pair between two assets, in any order, with its reserves.
+	#[rpc(name = "subswap_getReserves")]
+	fn get_reserves(
+		&self,
+		asset_a: AssetId,
+		asset_b: AssetId,
+		at: Option<BlockHash>
+	) -> Result<Option<PairResponse>>;
+
+	/// List every pair of the market with its reserves.
+	#[rpc(name = "subswap_listPairs")]
+	fn list_pairs(&self, at: Option<BlockHash>) -> Result<Vec<PairResponse>>;
+
+	/// Get the assets redeemable by burning `amount` of the liquidity provider token `lp_token`.
+	#[rpc(name = "subswap_lpShareValue")]
+	fn lp_share_value(
+		&self,
+		lp_token: AssetId,
+		amount: Balance,
+		at: Option<BlockHash>
+	) -> Result<Option<ValueResponse>>;
+
+	/// Get the balance of `who` in the asset `asset_id`.
+	#[rpc(name = "subswap_balanceOf")]
+	fn balance_of(
+		&self,
+		asset_id: AssetId,
+		who: AccountId,
+		at: Option<BlockHash>
+	) -> Result<BalanceResponse>;
+}
+
+/// A struct that implements the [`SubswapApi`].
+pub struct Subswap<C, P> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> Subswap<C, P> {
+	/// Create new `Subswap` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Subswap { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query subswap.".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+impl<C, Block, AccountId, AssetId, Balance> SubswapApi<
+	<Block as BlockT>::Hash,
+	AccountId,
+	AssetId,
+	Balance,
+	Quote<Balance>,
+	PairInfo<AssetId, Balance>,
+	LiquidityValue<AssetId, Balance>,
+	AssetBalance<Balance>,
+> for Subswap<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: SubswapRuntimeApi<Block, AccountId, AssetId, Balance>,
+	AccountId: Codec,
+	AssetId: Codec,
+	Balance: Codec + MaybeDisplay + MaybeFromStr,
+{
+	fn quote_exact_in(
+		&self,
+		path: Vec<AssetId>,
+		amount_in: Balance,
+		at: Option<<Block as BlockT>::Hash>
+	) -> Result<Option<Quote<Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.quote_exact_in(&at, path, amount_in).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn quote_exact_out(
+		&self,
+		path: Vec<AssetId>,
+		amount_out: Balance,
+		at: Option<<Block as BlockT>::Hash>
+	) -> Result<Option<Quote<Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.quote_exact_out(&at, path, amount_out).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn get_reserves(
+		&self,
+		asset_a: AssetId,
+		asset_b: AssetId,
+		at: Option<<Block as BlockT>::Hash>
+	) -> Result<Option<PairInfo<AssetId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.get_reserves(&at, asset_a, asset_b).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn list_pairs(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<PairInfo<AssetId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.list_pairs(&at).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn lp_share_value(
+		&self,
+		lp_token: AssetId,
+		amount: Balance,
+		at: Option<<Block as BlockT>::Hash>
+	) -> Result<Option<LiquidityValue<AssetId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.lp_share_value(&at, lp_token, amount).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn balance_of(
+		&self,
+		asset_id: AssetId,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>
+	) -> Result<AssetBalance<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.balance_of(&at, asset_id, who).map_err(runtime_error_into_rpc_err)
+	}
+}
@@ -0,0 +1,732 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Subswap module benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_support::traits::{OnInitialize, UnfilteredDispatchable};
+use frame_support::storage::IterableStorageMap;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+use sp_runtime::traits::Bounded;
+
+use crate::Module as Subswap;
+
+const SEED: u32 = 0;
+/// The amount of every asset minted to a benchmarking account.
+const FUNDS: u128 = 1_000_000_000_000_000_000_000;
+/// The reserve of every asset deposited in a benchmarking pair or pool.
+const RESERVE: u128 = 1_000_000_000_000_000_000;
+/// The amount swapped, deposited or withdrawn by a benchmarked call.
+const AMOUNT: u128 = 1_000_000_000_000_000;
+/// The liquidity of every benchmarking position of a concentrated liquidity pool.
+const LIQUIDITY: u128 = 1_000_000_000_000_000_000;
+/// The tick spacing of the benchmarking concentrated liquidity pools.
+const TICK_SPACING: u32 = 60;
+/// The most initialized ticks crossed by the benchmarked concentrated liquidity swap.
+const MAX_TICKS_CROSSED: u32 = 20;
+/// The native currency distributed to the mining pools per block.
+const REWARD: u128 = 10_000_000_000_000_000;
+/// The number of mining pools updated by the governance calls of the mining pools.
+const MINING_POOLS: u32 = 10;
+/// The length of the longest path of the multi-hop swaps.
+const MAX_PATH: u32 = 8;
+
+fn balance<T: Trait>(amount: u128) -> BalanceOf<T> {
+	amount.saturated_into()
+}
+
+/// Issue a new asset with a symbol, and fund `who` with it.
+fn new_asset<T: Trait>(who: &T::AccountId, index: u32) -> Result<AssetIdOf<T>, &'static str> {
+	let id = T::Assets::issue_from_system(Zero::zero())?;
+	T::Assets::set_system_metadata(id, b"Benchmark".to_vec(), vec![b'A' + (index % 26) as u8], 12)?;
+	T::Assets::mint_into(id, who, balance::<T>(FUNDS))?;
+	Ok(id)
+}
+
+/// Fund `who` with the native currency.
+fn fund_native<T: Trait>(who: &T::AccountId) -> Result<(), &'static str> {
+	T::Assets::mint_into(Zero::zero(), who, balance::<T>(FUNDS))?;
+	Ok(())
+}
+
+/// Create the constant product pair between `token0` and `token1` with the reserves of `who`.
+fn new_pair<T: Trait>(
+	who: &T::AccountId,
+	token0: AssetIdOf<T>,
+	token1: AssetIdOf<T>,
+) -> Result<AssetIdOf<T>, &'static str> {
+	Subswap::<T>::create_pair(
+		RawOrigin::Signed(who.clone()).into(),
+		token0,
+		balance::<T>(RESERVE),
+		token1,
+		balance::<T>(RESERVE),
+		T::DefaultFee::get(),
+		CurveType::ConstantProduct,
+	)?;
+	Subswap::<T>::pair((token0, token1)).ok_or("pair not created")
+}
+
+/// Create `length` new assets with a pair between every consecutive two, as a swap path.
+fn new_path<T: Trait>(who: &T::AccountId, length: u32) -> Result<Vec<AssetIdOf<T>>, &'static str> {
+	let mut path: Vec<AssetIdOf<T>> = Vec::new();
+	for i in 0..length {
+		let asset = new_asset::<T>(who, i)?;
+		if let Some(previous) = path.last() {
+			new_pair::<T>(who, *previous, asset)?;
+		}
+		path.push(asset);
+	}
+	Ok(path)
+}
+
+/// Create a weighted pool of new assets with `weights`, returning its liquidity provider token
+/// and its assets.
+fn new_weighted_pool<T: Trait>(
+	who: &T::AccountId,
+	weights: Vec<u32>,
+) -> Result<(AssetIdOf<T>, Vec<AssetIdOf<T>>), &'static str> {
+	let assets = (0..weights.len() as u32)
+		.map(|i| new_asset::<T>(who, i))
+		.collect::<Result<Vec<_>, _>>()?;
+	Subswap::<T>::create_weighted_pool(
+		RawOrigin::Signed(who.clone()).into(),
+		assets.clone(),
+		vec![balance::<T>(RESERVE); assets.len()],
+		weights,
+		T::DefaultFee::get(),
+	)?;
+	let lpt = weighted_pool_of::<T>(&assets).ok_or("pool not created")?;
+	Ok((lpt, assets))
+}
+
+/// The liquidity provider token of the weighted pool of `assets`.
+fn weighted_pool_of<T: Trait>(assets: &[AssetIdOf<T>]) -> Option<AssetIdOf<T>> {
+	WeightedPools::<T>::iter()
+		.find(|(_, pool)| pool.assets == assets)
+		.map(|(lpt, _)| lpt)
+}
+
+/// Create a concentrated liquidity pool of two new assets at the price of 1.
+fn new_concentrated_pool<T: Trait>(
+	who: &T::AccountId,
+) -> Result<(PoolId, AssetIdOf<T>, AssetIdOf<T>), &'static str> {
+	let token0 = new_asset::<T>(who, 0)?;
+	let token1 = new_asset::<T>(who, 1)?;
+	let pool_id = Subswap::<T>::next_pool_id();
+	Subswap::<T>::create_concentrated_pool(
+		RawOrigin::Signed(who.clone()).into(),
+		token0,
+		token1,
+		T::DefaultFee::get(),
+		TICK_SPACING,
+		concentrated::q96(),
+	)?;
+	Ok((pool_id, token0, token1))
+}
+
+/// Provide `LIQUIDITY` to the price range between `tick_lower` and `tick_upper` of `pool_id`.
+fn new_position<T: Trait>(
+	who: &T::AccountId,
+	pool_id: PoolId,
+	tick_lower: i32,
+	tick_upper: i32,
+) -> Result<PositionId, &'static str> {
+	let position_id = Subswap::<T>::next_position_id();
+	Subswap::<T>::mint_position(
+		RawOrigin::Signed(who.clone()).into(),
+		pool_id,
+		tick_lower,
+		tick_upper,
+		LIQUIDITY,
+		balance::<T>(FUNDS),
+		balance::<T>(FUNDS),
+	)?;
+	Ok(position_id)
+}
+
+/// Create a pair of new assets with a mining pool, distributing `REWARD` per block.
+fn new_mining_pool<T: Trait>(who: &T::AccountId) -> Result<AssetIdOf<T>, &'static str> {
+	let token0 = new_asset::<T>(who, 0)?;
+	let token1 = new_asset::<T>(who, 1)?;
+	let lpt = new_pair::<T>(who, token0, token1)?;
+	Subswap::<T>::set_mining_pool(T::GovernanceOrigin::successful_origin(), lpt, 100)?;
+	Subswap::<T>::set_reward_per_block(T::GovernanceOrigin::successful_origin(), balance::<T>(REWARD))?;
+	Ok(lpt)
+}
+
+/// Move on by `blocks` blocks, over which the mining pools accumulate rewards.
+fn advance_blocks<T: Trait>(blocks: u32) {
+	let now = frame_system::Module::<T>::block_number();
+	frame_system::Module::<T>::set_block_number(now + blocks.into());
+}
+
+/// Place a limit order selling `amount_in` of `asset_in` at a limit price just below the price of
+/// its pair, so that a large order may only be filled in part.
+fn new_limit_order<T: Trait>(
+	who: &T::AccountId,
+	asset_in: AssetIdOf<T>,
+	asset_out: AssetIdOf<T>,
+	amount_in: u128,
+) -> Result<OrderId, &'static str> {
+	let order_id = Subswap::<T>::next_order_id();
+	let expiry = frame_system::Module::<T>::block_number() + 10u32.into();
+	Subswap::<T>::place_limit_order(
+		RawOrigin::Signed(who.clone()).into(),
+		asset_in,
+		balance::<T>(amount_in),
+		asset_out,
+		balance::<T>(amount_in / 100 * 98),
+		expiry,
+	)?;
+	Ok(order_id)
+}
+
+/// A new account placing limit orders of `asset`, funded with it.
+fn order_owner<T: Trait>(index: u32, asset: AssetIdOf<T>) -> Result<T::AccountId, &'static str> {
+	let owner: T::AccountId = account("owner", index, SEED);
+	T::Assets::mint_into(asset, &owner, balance::<T>(FUNDS))?;
+	Ok(owner)
+}
+
+benchmarks! {
+	where_clause { where <T as Trait>::Call: From<frame_system::Call<T>> }
+
+	_ { }
+
+	// Worst case: the protocol fee is on and the pair grew since liquidity was last minted.
+	mint_liquidity {
+		let caller: T::AccountId = whitelisted_caller();
+		let fee_to: T::AccountId = account("fee_to", 0, SEED);
+		Subswap::<T>::set_fee_to(T::GovernanceOrigin::successful_origin(), Some(fee_to.clone()))?;
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		let lpt = new_pair::<T>(&caller, token0, token1)?;
+		Subswap::<T>::swap(RawOrigin::Signed(caller.clone()).into(), token0, balance::<T>(AMOUNT), token1)?;
+		let minted = T::Assets::balance(lpt, &caller);
+	}: _(RawOrigin::Signed(caller.clone()), token0, balance::<T>(AMOUNT), token1, balance::<T>(AMOUNT))
+	verify {
+		assert!(T::Assets::balance(lpt, &caller) > minted);
+		assert!(!T::Assets::balance(lpt, &fee_to).is_zero());
+	}
+
+	// Worst case: the pair prices with the StableSwap invariant, which is solved iteratively.
+	create_pair {
+		let caller: T::AccountId = whitelisted_caller();
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		token0,
+		balance::<T>(RESERVE),
+		token1,
+		balance::<T>(RESERVE),
+		T::DefaultFee::get(),
+		CurveType::StableSwap(100)
+	)
+	verify {
+		assert!(Subswap::<T>::pair((token0, token1)).is_some());
+	}
+
+	// Worst case: the protocol fee is on and the pair grew since liquidity was last minted.
+	burn_liquidity {
+		let caller: T::AccountId = whitelisted_caller();
+		let fee_to: T::AccountId = account("fee_to", 0, SEED);
+		Subswap::<T>::set_fee_to(T::GovernanceOrigin::successful_origin(), Some(fee_to))?;
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		let lpt = new_pair::<T>(&caller, token0, token1)?;
+		Subswap::<T>::swap(RawOrigin::Signed(caller.clone()).into(), token0, balance::<T>(AMOUNT), token1)?;
+		let minted = T::Assets::balance(lpt, &caller);
+		let amount = minted / balance::<T>(2);
+	}: _(RawOrigin::Signed(caller.clone()), lpt, amount)
+	verify {
+		assert_eq!(T::Assets::balance(lpt, &caller), minted - amount);
+	}
+
+	swap {
+		let caller: T::AccountId = whitelisted_caller();
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		new_pair::<T>(&caller, token0, token1)?;
+	}: _(RawOrigin::Signed(caller.clone()), token0, balance::<T>(AMOUNT), token1)
+	verify {
+		assert!(T::Assets::balance(token1, &caller) > balance::<T>(FUNDS - RESERVE));
+	}
+
+	swap_exact_in_along_path {
+		let p in 2 .. MAX_PATH;
+		let caller: T::AccountId = whitelisted_caller();
+		let path = new_path::<T>(&caller, p)?;
+		let asset_out = path[path.len() - 1];
+	}: _(RawOrigin::Signed(caller.clone()), path, balance::<T>(AMOUNT), balance::<T>(1), T::Moment::max_value())
+	verify {
+		assert!(T::Assets::balance(asset_out, &caller) > balance::<T>(FUNDS - RESERVE));
+	}
+
+	swap_exact_out {
+		let p in 2 .. MAX_PATH;
+		let caller: T::AccountId = whitelisted_caller();
+		let path = new_path::<T>(&caller, p)?;
+		let asset_out = path[path.len() - 1];
+	}: _(RawOrigin::Signed(caller.clone()), path, balance::<T>(AMOUNT), balance::<T>(FUNDS), T::Moment::max_value())
+	verify {
+		assert_eq!(T::Assets::balance(asset_out, &caller), balance::<T>(FUNDS - RESERVE + AMOUNT));
+	}
+
+	// The weight of the dispatched call is added to this one, so it is a remark here.
+	flash_swap {
+		let caller: T::AccountId = whitelisted_caller();
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		let lpt = new_pair::<T>(&caller, token0, token1)?;
+		let call: <T as Trait>::Call = frame_system::Call::<T>::remark(vec![]).into();
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		lpt,
+		token0,
+		balance::<T>(AMOUNT),
+		token0,
+		balance::<T>(AMOUNT + AMOUNT / 100),
+		Box::new(call)
+	)
+	verify {
+		let reserves = Subswap::<T>::reserves(lpt);
+		assert!(reserves.0 + reserves.1 > balance::<T>(2 * RESERVE));
+	}
+
+	set_fee {
+		let caller: T::AccountId = whitelisted_caller();
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		let lpt = new_pair::<T>(&caller, token0, token1)?;
+		let origin = T::GovernanceOrigin::successful_origin();
+		let call = Call::<T>::set_fee(lpt, 50);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Subswap::<T>::fee(lpt), 50);
+	}
+
+	set_fee_to {
+		let fee_to: T::AccountId = account("fee_to", 0, SEED);
+		let origin = T::GovernanceOrigin::successful_origin();
+		let call = Call::<T>::set_fee_to(Some(fee_to.clone()));
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Subswap::<T>::fee_to(), Some(fee_to));
+	}
+
+	create_weighted_pool {
+		let a in 2 .. weighted::MAX_ASSETS as u32;
+		let caller: T::AccountId = whitelisted_caller();
+		let assets = (0..a)
+			.map(|i| new_asset::<T>(&caller, i))
+			.collect::<Result<Vec<_>, _>>()?;
+		let amounts = vec![balance::<T>(RESERVE); a as usize];
+		let weights = vec![10; a as usize];
+	}: _(RawOrigin::Signed(caller.clone()), assets.clone(), amounts, weights, T::DefaultFee::get())
+	verify {
+		assert!(weighted_pool_of::<T>(&assets).is_some());
+	}
+
+	// Uneven weights raise the balance ratios to fractional powers, the slowest path of the math.
+	join_pool_single {
+		let caller: T::AccountId = whitelisted_caller();
+		let (lpt, assets) = new_weighted_pool::<T>(&caller, vec![80, 20])?;
+	}: _(RawOrigin::Signed(caller.clone()), lpt, assets[1], balance::<T>(AMOUNT), balance::<T>(1))
+	verify {
+		assert!(T::Assets::balance(lpt, &caller) > balance::<T>(weighted::INITIAL_POOL_SUPPLY));
+	}
+
+	exit_pool_single {
+		let caller: T::AccountId = whitelisted_caller();
+		let (lpt, assets) = new_weighted_pool::<T>(&caller, vec![80, 20])?;
+		let asset_out = assets[1];
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		lpt,
+		balance::<T>(weighted::INITIAL_POOL_SUPPLY / 100),
+		asset_out,
+		balance::<T>(1)
+	)
+	verify {
+		assert!(T::Assets::balance(asset_out, &caller) > balance::<T>(FUNDS - RESERVE));
+	}
+
+	exit_pool {
+		let a in 2 .. weighted::MAX_ASSETS as u32;
+		let caller: T::AccountId = whitelisted_caller();
+		let (lpt, _) = new_weighted_pool::<T>(&caller, vec![10; a as usize])?;
+	}: _(RawOrigin::Signed(caller.clone()), lpt, balance::<T>(weighted::INITIAL_POOL_SUPPLY / 2))
+	verify {
+		assert_eq!(T::Assets::balance(lpt, &caller), balance::<T>(weighted::INITIAL_POOL_SUPPLY / 2));
+	}
+
+	swap_weighted {
+		let caller: T::AccountId = whitelisted_caller();
+		let (lpt, assets) = new_weighted_pool::<T>(&caller, vec![80, 20])?;
+		let asset_out = assets[1];
+	}: _(RawOrigin::Signed(caller.clone()), lpt, assets[0], balance::<T>(AMOUNT), asset_out, balance::<T>(1))
+	verify {
+		assert!(T::Assets::balance(asset_out, &caller) > balance::<T>(FUNDS - RESERVE));
+	}
+
+	create_concentrated_pool {
+		let caller: T::AccountId = whitelisted_caller();
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		let pool_id = Subswap::<T>::next_pool_id();
+	}: _(RawOrigin::Signed(caller.clone()), token0, token1, T::DefaultFee::get(), TICK_SPACING, concentrated::q96())
+	verify {
+		assert!(Subswap::<T>::concentrated_pool(pool_id).is_some());
+	}
+
+	// Worst case: both ticks of the position are initialized next to the ticks of another one.
+	mint_position {
+		let caller: T::AccountId = whitelisted_caller();
+		let (pool_id, _, _) = new_concentrated_pool::<T>(&caller)?;
+		new_position::<T>(&caller, pool_id, -600, 600)?;
+		let position_id = Subswap::<T>::next_position_id();
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		pool_id,
+		-120,
+		120,
+		LIQUIDITY,
+		balance::<T>(FUNDS),
+		balance::<T>(FUNDS)
+	)
+	verify {
+		assert!(Subswap::<T>::position(position_id).is_some());
+	}
+
+	// Worst case: all of the liquidity is withdrawn, clearing both ticks of the position.
+	decrease_liquidity {
+		let caller: T::AccountId = whitelisted_caller();
+		let (pool_id, _, _) = new_concentrated_pool::<T>(&caller)?;
+		let position_id = new_position::<T>(&caller, pool_id, -600, 600)?;
+	}: _(RawOrigin::Signed(caller.clone()), position_id, LIQUIDITY, balance::<T>(0), balance::<T>(0))
+	verify {
+		assert_eq!(Subswap::<T>::position(position_id).map(|position| position.liquidity), Some(0));
+	}
+
+	// Worst case: the position earned fees in both assets and withdrew part of its liquidity.
+	collect {
+		let caller: T::AccountId = whitelisted_caller();
+		let (pool_id, token0, token1) = new_concentrated_pool::<T>(&caller)?;
+		let position_id = new_position::<T>(&caller, pool_id, -600, 600)?;
+		Subswap::<T>::swap_concentrated(RawOrigin::Signed(caller.clone()).into(), pool_id, token0, balance::<T>(AMOUNT), balance::<T>(1), None, 0)?;
+		Subswap::<T>::swap_concentrated(RawOrigin::Signed(caller.clone()).into(), pool_id, token1, balance::<T>(AMOUNT), balance::<T>(1), None, 0)?;
+		Subswap::<T>::decrease_liquidity(RawOrigin::Signed(caller.clone()).into(), position_id, LIQUIDITY / 2, balance::<T>(0), balance::<T>(0))?;
+	}: _(RawOrigin::Signed(caller.clone()), position_id)
+	verify {
+		let position = Subswap::<T>::position(position_id).ok_or("position removed")?;
+		assert!(position.tokens_owed0.is_zero() && position.tokens_owed1.is_zero());
+	}
+
+	burn_position {
+		let caller: T::AccountId = whitelisted_caller();
+		let (pool_id, _, _) = new_concentrated_pool::<T>(&caller)?;
+		let position_id = new_position::<T>(&caller, pool_id, -600, 600)?;
+		Subswap::<T>::decrease_liquidity(RawOrigin::Signed(caller.clone()).into(), position_id, LIQUIDITY, balance::<T>(0), balance::<T>(0))?;
+		Subswap::<T>::collect(RawOrigin::Signed(caller.clone()).into(), position_id)?;
+	}: _(RawOrigin::Signed(caller.clone()), position_id)
+	verify {
+		assert!(Subswap::<T>::position(position_id).is_none());
+	}
+
+	transfer_position {
+		let caller: T::AccountId = whitelisted_caller();
+		let (pool_id, _, _) = new_concentrated_pool::<T>(&caller)?;
+		let position_id = new_position::<T>(&caller, pool_id, -600, 600)?;
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let dest_lookup = T::Lookup::unlookup(dest.clone());
+	}: _(RawOrigin::Signed(caller.clone()), position_id, dest_lookup)
+	verify {
+		assert_eq!(Subswap::<T>::position(position_id).map(|position| position.owner), Some(dest));
+	}
+
+	// The swap crosses `t` initialized ticks, each changing the liquidity in range, and ends in
+	// the range of a position spanning the whole price range. Every position initializes two
+	// ticks.
+	swap_concentrated {
+		let t in 0 .. MAX_TICKS_CROSSED.min(T::MaxInitializedTicks::get().saturating_sub(2) / 2);
+		let caller: T::AccountId = whitelisted_caller();
+		let (pool_id, token0, token1) = new_concentrated_pool::<T>(&caller)?;
+		let max_tick = concentrated::MAX_TICK / TICK_SPACING as i32 * TICK_SPACING as i32;
+		new_position::<T>(&caller, pool_id, -max_tick, max_tick)?;
+		for i in 1..=t as i32 {
+			new_position::<T>(&caller, pool_id, -120 * i, 120 * i)?;
+		}
+		let asset_in = Subswap::<T>::concentrated_pool(pool_id).ok_or("pool not created")?.token0;
+		let asset_out = if asset_in == token0 { token1 } else { token0 };
+		let before = T::Assets::balance(asset_out, &caller);
+	}: _(RawOrigin::Signed(caller.clone()), pool_id, asset_in, balance::<T>(10 * RESERVE), balance::<T>(1), None, t)
+	verify {
+		assert!(T::Assets::balance(asset_out, &caller) > before);
+		assert!(Subswap::<T>::concentrated_pool(pool_id).ok_or("pool removed")?.tick < -120 * t as i32);
+	}
+
+	set_reward_per_block {
+		let caller: T::AccountId = whitelisted_caller();
+		for _ in 0..MINING_POOLS {
+			new_mining_pool::<T>(&caller)?;
+		}
+		advance_blocks::<T>(10);
+		let origin = T::GovernanceOrigin::successful_origin();
+		let call = Call::<T>::set_reward_per_block(balance::<T>(2 * REWARD));
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Subswap::<T>::reward_per_block(), balance::<T>(2 * REWARD));
+	}
+
+	set_mining_pool {
+		let caller: T::AccountId = whitelisted_caller();
+		for _ in 1..MINING_POOLS {
+			new_mining_pool::<T>(&caller)?;
+		}
+		advance_blocks::<T>(10);
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		let lpt = new_pair::<T>(&caller, token0, token1)?;
+		let origin = T::GovernanceOrigin::successful_origin();
+		let call = Call::<T>::set_mining_pool(lpt, 100);
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Subswap::<T>::mining_pool(lpt).is_some());
+	}
+
+	// Worst case: the tokens already staked are paid their reward.
+	stake_lp {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_native::<T>(&caller)?;
+		let lpt = new_mining_pool::<T>(&caller)?;
+		Subswap::<T>::stake_lp(RawOrigin::Signed(caller.clone()).into(), lpt, balance::<T>(AMOUNT))?;
+		advance_blocks::<T>(10);
+	}: _(RawOrigin::Signed(caller.clone()), lpt, balance::<T>(AMOUNT))
+	verify {
+		assert_eq!(Subswap::<T>::stake(lpt, &caller).amount, balance::<T>(2 * AMOUNT));
+		assert!(T::Assets::balance(Zero::zero(), &caller) > balance::<T>(FUNDS));
+	}
+
+	// Worst case: the tokens staked are paid their reward and part of them stays staked.
+	unstake_lp {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_native::<T>(&caller)?;
+		let lpt = new_mining_pool::<T>(&caller)?;
+		Subswap::<T>::stake_lp(RawOrigin::Signed(caller.clone()).into(), lpt, balance::<T>(2 * AMOUNT))?;
+		advance_blocks::<T>(10);
+	}: _(RawOrigin::Signed(caller.clone()), lpt, balance::<T>(AMOUNT))
+	verify {
+		assert_eq!(Subswap::<T>::stake(lpt, &caller).amount, balance::<T>(AMOUNT));
+		assert!(T::Assets::balance(Zero::zero(), &caller) > balance::<T>(FUNDS));
+	}
+
+	claim_rewards {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_native::<T>(&caller)?;
+		let lpt = new_mining_pool::<T>(&caller)?;
+		Subswap::<T>::stake_lp(RawOrigin::Signed(caller.clone()).into(), lpt, balance::<T>(AMOUNT))?;
+		advance_blocks::<T>(10);
+	}: _(RawOrigin::Signed(caller.clone()), lpt)
+	verify {
+		assert!(T::Assets::balance(Zero::zero(), &caller) > balance::<T>(FUNDS));
+	}
+
+	// Worst case: the order takes the last open slot, the others being taken by other accounts.
+	place_limit_order {
+		let caller: T::AccountId = whitelisted_caller();
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		new_pair::<T>(&caller, token0, token1)?;
+		for i in 1..T::MaxOpenOrders::get() {
+			new_limit_order::<T>(&order_owner::<T>(i, token0)?, token0, token1, AMOUNT)?;
+		}
+		let order_id = Subswap::<T>::next_order_id();
+		let expiry = frame_system::Module::<T>::block_number() + 10u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), token0, balance::<T>(AMOUNT), token1, balance::<T>(AMOUNT), expiry)
+	verify {
+		assert!(Subswap::<T>::order(order_id).is_some());
+		assert_eq!(Subswap::<T>::open_orders().len() as u32, T::MaxOpenOrders::get());
+	}
+
+	// Worst case: the order is the last of the most orders open at once.
+	cancel_order {
+		let caller: T::AccountId = whitelisted_caller();
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		new_pair::<T>(&caller, token0, token1)?;
+		for i in 1..T::MaxOpenOrders::get() {
+			new_limit_order::<T>(&order_owner::<T>(i, token0)?, token0, token1, AMOUNT)?;
+		}
+		let order_id = new_limit_order::<T>(&caller, token0, token1, AMOUNT)?;
+	}: _(RawOrigin::Signed(caller.clone()), order_id)
+	verify {
+		assert!(Subswap::<T>::order(order_id).is_none());
+	}
+
+	// Every order is on its own pair, of its own account, and large enough to move the price of
+	// the pair past its limit price, so it is filled in part and stays open.
+	settle_orders {
+		let n in 1 .. T::MaxOrdersPerBlock::get().min(T::MaxOpenOrders::get());
+		let caller: T::AccountId = whitelisted_caller();
+		let asset_in = new_asset::<T>(&caller, 0)?;
+		let mut orders = Vec::new();
+		for i in 0..n {
+			let asset_out = new_asset::<T>(&caller, i + 1)?;
+			new_pair::<T>(&caller, asset_in, asset_out)?;
+			let owner = order_owner::<T>(i, asset_in)?;
+			orders.push(new_limit_order::<T>(&owner, asset_in, asset_out, RESERVE / 10)?);
+		}
+		let now = frame_system::Module::<T>::block_number();
+	}: { Subswap::<T>::on_initialize(now); }
+	verify {
+		for order_id in orders {
+			let order = Subswap::<T>::order(order_id).ok_or("order closed")?;
+			assert!(!order.filled_out.is_zero() && order.remaining < order.amount_in);
+		}
+	}
+
+	// Worst case: the protocol fee is on, the pair grew since liquidity was last minted and
+	// part of the second asset is refunded.
+	add_liquidity {
+		let caller: T::AccountId = whitelisted_caller();
+		let fee_to: T::AccountId = account("fee_to", 0, SEED);
+		Subswap::<T>::set_fee_to(T::GovernanceOrigin::successful_origin(), Some(fee_to.clone()))?;
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		let lpt = new_pair::<T>(&caller, token0, token1)?;
+		Subswap::<T>::swap(RawOrigin::Signed(caller.clone()).into(), token0, balance::<T>(AMOUNT), token1)?;
+		let minted = T::Assets::balance(lpt, &caller);
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		token0,
+		token1,
+		balance::<T>(AMOUNT),
+		balance::<T>(2 * AMOUNT),
+		Zero::zero(),
+		Zero::zero(),
+		T::Moment::max_value()
+	)
+	verify {
+		assert!(T::Assets::balance(lpt, &caller) > minted);
+		assert!(!T::Assets::balance(lpt, &fee_to).is_zero());
+	}
+
+	// Worst case: the protocol fee is on and the pair grew since liquidity was last minted.
+	remove_liquidity {
+		let caller: T::AccountId = whitelisted_caller();
+		let fee_to: T::AccountId = account("fee_to", 0, SEED);
+		Subswap::<T>::set_fee_to(T::GovernanceOrigin::successful_origin(), Some(fee_to))?;
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		let lpt = new_pair::<T>(&caller, token0, token1)?;
+		Subswap::<T>::swap(RawOrigin::Signed(caller.clone()).into(), token0, balance::<T>(AMOUNT), token1)?;
+		let minted = T::Assets::balance(lpt, &caller);
+		let amount = minted / balance::<T>(2);
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		token0,
+		token1,
+		amount,
+		Zero::zero(),
+		Zero::zero(),
+		T::Moment::max_value()
+	)
+	verify {
+		assert_eq!(T::Assets::balance(lpt, &caller), minted - amount);
+	}
+
+	zap_in {
+		let caller: T::AccountId = whitelisted_caller();
+		let fee_to: T::AccountId = account("fee_to", 0, SEED);
+		Subswap::<T>::set_fee_to(T::GovernanceOrigin::successful_origin(), Some(fee_to))?;
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		let lpt = new_pair::<T>(&caller, token0, token1)?;
+		Subswap::<T>::swap(RawOrigin::Signed(caller.clone()).into(), token0, balance::<T>(AMOUNT), token1)?;
+		let minted = T::Assets::balance(lpt, &caller);
+	}: _(RawOrigin::Signed(caller.clone()), lpt, token0, balance::<T>(AMOUNT), Zero::zero())
+	verify {
+		assert!(T::Assets::balance(lpt, &caller) > minted);
+	}
+
+	zap_out {
+		let caller: T::AccountId = whitelisted_caller();
+		let fee_to: T::AccountId = account("fee_to", 0, SEED);
+		Subswap::<T>::set_fee_to(T::GovernanceOrigin::successful_origin(), Some(fee_to))?;
+		let token0 = new_asset::<T>(&caller, 0)?;
+		let token1 = new_asset::<T>(&caller, 1)?;
+		let lpt = new_pair::<T>(&caller, token0, token1)?;
+		Subswap::<T>::swap(RawOrigin::Signed(caller.clone()).into(), token0, balance::<T>(AMOUNT), token1)?;
+		let minted = T::Assets::balance(lpt, &caller);
+		let amount = minted / balance::<T>(2);
+	}: _(RawOrigin::Signed(caller.clone()), lpt, amount, token0, Zero::zero())
+	verify {
+		assert_eq!(T::Assets::balance(lpt, &caller), minted - amount);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_mint_liquidity::<Test>());
+			assert_ok!(test_benchmark_create_pair::<Test>());
+			assert_ok!(test_benchmark_burn_liquidity::<Test>());
+			assert_ok!(test_benchmark_swap::<Test>());
+			assert_ok!(test_benchmark_swap_exact_in_along_path::<Test>());
+			assert_ok!(test_benchmark_swap_exact_out::<Test>());
+			assert_ok!(test_benchmark_flash_swap::<Test>());
+			assert_ok!(test_benchmark_set_fee::<Test>());
+			assert_ok!(test_benchmark_set_fee_to::<Test>());
+			assert_ok!(test_benchmark_create_weighted_pool::<Test>());
+			assert_ok!(test_benchmark_join_pool_single::<Test>());
+			assert_ok!(test_benchmark_exit_pool_single::<Test>());
+			assert_ok!(test_benchmark_exit_pool::<Test>());
+			assert_ok!(test_benchmark_swap_weighted::<Test>());
+			assert_ok!(test_benchmark_create_concentrated_pool::<Test>());
+			assert_ok!(test_benchmark_mint_position::<Test>());
+			assert_ok!(test_benchmark_decrease_liquidity::<Test>());
+			assert_ok!(test_benchmark_collect::<Test>());
+			assert_ok!(test_benchmark_burn_position::<Test>());
+			assert_ok!(test_benchmark_transfer_position::<Test>());
+			assert_ok!(test_benchmark_swap_concentrated::<Test>());
+			assert_ok!(test_benchmark_set_reward_per_block::<Test>());
+			assert_ok!(test_benchmark_set_mining_pool::<Test>());
+			assert_ok!(test_benchmark_stake_lp::<Test>());
+			assert_ok!(test_benchmark_unstake_lp::<Test>());
+			assert_ok!(test_benchmark_claim_rewards::<Test>());
+			assert_ok!(test_benchmark_place_limit_order::<Test>());
+			assert_ok!(test_benchmark_cancel_order::<Test>());
+			assert_ok!(test_benchmark_settle_orders::<Test>());
+			assert_ok!(test_benchmark_add_liquidity::<Test>());
+			assert_ok!(test_benchmark_remove_liquidity::<Test>());
+			assert_ok!(test_benchmark_zap_in::<Test>());
+			assert_ok!(test_benchmark_zap_out::<Test>());
+		});
+	}
+}
@@ -0,0 +1,467 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Concentrated liquidity pools of Uniswap v3, where each position provides liquidity to a
+//! price range between two ticks.
+//!
+//! Prices are kept as their square root in Q64.96 fixed point, and the price at tick `i` is
+//! `1.0001 ^ i` units of `token1` per `token0`. Every function returns `None` on overflow or when
+//! the pool cannot satisfy the request.
+
+use codec::{Encode, Decode};
+use sp_core::{U256, U512};
+use sp_runtime::RuntimeDebug;
+use sp_std::convert::TryFrom;
+use crate::FEE_DENOMINATOR;
+
+/// The lowest tick, whose price is about `2 ^ -128`.
+pub const MIN_TICK: i32 = -887_272;
+
+/// The highest tick, whose price is about `2 ^ 128`.
+pub const MAX_TICK: i32 = 887_272;
+
+/// The largest tick spacing of a pool.
+pub const MAX_TICK_SPACING: u32 = 16_384;
+
+/// The square root price at `MIN_TICK`.
+pub const MIN_SQRT_RATIO: U256 = U256([4_295_128_739, 0, 0, 0]);
+
+/// The square root price at `MAX_TICK`.
+pub const MAX_SQRT_RATIO: U256 = U256([0x5d951d5263988d26, 0xefd1fc6a50648849, 0xfffd8963, 0]);
+
+/// `sqrt(1.0001) ^ -(2 ^ i)` in Q128.128 for every bit `i` of a tick.
+const TICK_FACTORS: [u128; 20] = [
+	0xfffcb933bd6fad37aa2d162d1a594001,
+	0xfff97272373d413259a46990580e213a,
+	0xfff2e50f5f656932ef12357cf3c7fdcc,
+	0xffe5caca7e10e4e61c3624eaa0941cd0,
+	0xffcb9843d60f6159c9db58835c926644,
+	0xff973b41fa98c081472e6896dfb254c0,
+	0xff2ea16466c96a3843ec78b326b52861,
+	0xfe5dee046a99a2a811c461f1969c3053,
+	0xfcbe86c7900a88aedcffc83b479aa3a4,
+	0xf987a7253ac413176f2b074cf7815e54,
+	0xf3392b0822b70005940c7a398e4b70f3,
+	0xe7159475a2c29b7443b29c7fa6e889d9,
+	0xd097f3bdfd2022b8845ad8f792aa5825,
+	0xa9f746462d870fdf8a65dc1f90e061e5,
+	0x70d869a156d2a1b890bb3df62baf32f7,
+	0x31be135f97d08fd981231505542fcfa6,
+	0x9aa508b5b7a84e1c677de54f3e99bc9,
+	0x5d6af8dedb81196699c329225ee604,
+	0x2216e584f5fa1ea926041bedfe98,
+	0x48a170391f7dc42444e8fa2,
+];
+
+/// A concentrated liquidity pool between two assets, ordered by identifier.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, Default)]
+pub struct ConcentratedPool<AssetId> {
+	/// The asset with the lower identifier.
+	pub token0: AssetId,
+	/// The asset with the higher identifier.
+	pub token1: AssetId,
+	/// The swap fee of the pool in basis points.
+	pub fee: u32,
+	/// Positions may only start and end at multiples of the tick spacing.
+	pub tick_spacing: i32,
+	/// The square root of the current price in Q64.96.
+	pub sqrt_price: U256,
+	/// The tick of the current price, rounded down.
+	pub tick: i32,
+	/// The liquidity of the positions in range of the current price.
+	pub liquidity: u128,
+	/// The fees of `token0` earned per unit of liquidity over the life of the pool, in Q128.128.
+	pub fee_growth_global0: U256,
+	/// The fees of `token1` earned per unit of liquidity over the life of the pool, in Q128.128.
+	pub fee_growth_global1: U256,
+}
+
+/// The liquidity referencing a tick of a pool.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, Default)]
+pub struct TickInfo {
+	/// The liquidity of every position starting or ending at the tick.
+	pub liquidity_gross: u128,
+	/// The liquidity added to the pool when the price crosses the tick upwards.
+	pub liquidity_net: i128,
+	/// The fee growth of `token0` on the other side of the tick from the current price.
+	pub fee_growth_outside0: U256,
+	/// The fee growth of `token1` on the other side of the tick from the current price.
+	pub fee_growth_outside1: U256,
+}
+
+/// A non-fungible record of liquidity provided to a price range of a pool.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, Default)]
+pub struct Position<AccountId, PoolId, Balance> {
+	/// The account owning the position.
+	pub owner: AccountId,
+	/// The pool the position provides liquidity to.
+	pub pool_id: PoolId,
+	/// The lower tick of the price range.
+	pub tick_lower: i32,
+	/// The upper tick of the price range.
+	pub tick_upper: i32,
+	/// The liquidity of the position.
+	pub liquidity: u128,
+	/// The fee growth of `token0` inside the range as of the last update of the position.
+	pub fee_growth_inside0_last: U256,
+	/// The fee growth of `token1` inside the range as of the last update of the position.
+	pub fee_growth_inside1_last: U256,
+	/// The fees and withdrawn liquidity of `token0` owed to the owner.
+	pub tokens_owed0: Balance,
+	/// The fees and withdrawn liquidity of `token1` owed to the owner.
+	pub tokens_owed1: Balance,
+}
+
+/// The result of a swap within a single tick range.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct SwapStep {
+	/// The square root price after the step.
+	pub sqrt_price_next: U256,
+	/// The input amount swapped, excluding the fee.
+	pub amount_in: U256,
+	/// The output amount of the step.
+	pub amount_out: U256,
+	/// The fee charged on the input amount.
+	pub fee_amount: U256,
+}
+
+/// `2 ^ 96`, one in Q64.96.
+pub fn q96() -> U256 {
+	U256::one() << 96
+}
+
+/// `2 ^ 128`, one in Q128.128.
+pub fn q128() -> U256 {
+	U256::one() << 128
+}
+
+/// `a * b / denominator` rounded down, with a 512 bit intermediate product.
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Option<U256> {
+	if denominator.is_zero() {
+		return None;
+	}
+	U256::try_from(a.full_mul(b) / U512::from(denominator)).ok()
+}
+
+/// `a * b / denominator` rounded up, with a 512 bit intermediate product.
+pub fn mul_div_rounding_up(a: U256, b: U256, denominator: U256) -> Option<U256> {
+	if denominator.is_zero() {
+		return None;
+	}
+	let product = a.full_mul(b);
+	let denominator = U512::from(denominator);
+	let mut result = product / denominator;
+	if !(product % denominator).is_zero() {
+		result = result + U512::one();
+	}
+	U256::try_from(result).ok()
+}
+
+fn div_rounding_up(a: U256, b: U256) -> Option<U256> {
+	if b.is_zero() {
+		return None;
+	}
+	let result = a / b;
+	if (a % b).is_zero() { Some(result) } else { result.checked_add(U256::one()) }
+}
+
+/// The square root price at `tick`, `sqrt(1.0001 ^ tick)` in Q64.96.
+pub fn sqrt_ratio_at_tick(tick: i32) -> Option<U256> {
+	let abs_tick = (tick as i64).abs() as u32;
+	if abs_tick > MAX_TICK as u32 {
+		return None;
+	}
+	let mut ratio = if abs_tick & 1 != 0 { U256::from(TICK_FACTORS[0]) } else { q128() };
+	for (i, factor) in TICK_FACTORS.iter().enumerate().skip(1) {
+		if abs_tick & (1 << i) != 0 {
+			ratio = ratio.checked_mul(U256::from(*factor))? >> 128;
+		}
+	}
+	if tick > 0 {
+		ratio = U256::MAX / ratio;
+	}
+	// Round up from Q128.128 to Q64.96 so that the tick of the result is `tick`
+	let remainder = ratio & U256::from(u32::max_value());
+	Some((ratio >> 32) + if remainder.is_zero() { U256::zero() } else { U256::one() })
+}
+
+/// The greatest tick whose square root price is at most `sqrt_price`.
+pub fn tick_at_sqrt_ratio(sqrt_price: U256) -> Option<i32> {
+	if sqrt_price < MIN_SQRT_RATIO || sqrt_price >= MAX_SQRT_RATIO {
+		return None;
+	}
+	let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+	while low < high {
+		let mid = low + (high - low + 1) / 2;
+		if sqrt_ratio_at_tick(mid)? <= sqrt_price {
+			low = mid;
+		} else {
+			high = mid - 1;
+		}
+	}
+	Some(low)
+}
+
+/// The amount of `token0` between two square root prices for `liquidity`.
+pub fn amount0_delta(sqrt_a: U256, sqrt_b: U256, liquidity: u128, round_up: bool) -> Option<U256> {
+	let (sqrt_a, sqrt_b) = if sqrt_a > sqrt_b { (sqrt_b, sqrt_a) } else { (sqrt_a, sqrt_b) };
+	if sqrt_a.is_zero() {
+		return None;
+	}
+	let numerator1 = U256::from(liquidity) << 96;
+	let numerator2 = sqrt_b - sqrt_a;
+	if round_up {
+		div_rounding_up(mul_div_rounding_up(numerator1, numerator2, sqrt_b)?, sqrt_a)
+	} else {
+		Some(mul_div(numerator1, numerator2, sqrt_b)? / sqrt_a)
+	}
+}
+
+/// The amount of `token1` between two square root prices for `liquidity`.
+pub fn amount1_delta(sqrt_a: U256, sqrt_b: U256, liquidity: u128, round_up: bool) -> Option<U256> {
+	let (sqrt_a, sqrt_b) = if sqrt_a > sqrt_b { (sqrt_b, sqrt_a) } else { (sqrt_a, sqrt_b) };
+	if round_up {
+		mul_div_rounding_up(U256::from(liquidity), sqrt_b - sqrt_a, q96())
+	} else {
+		mul_div(U256::from(liquidity), sqrt_b - sqrt_a, q96())
+	}
+}
+
+/// The square root price after adding or removing `amount` of `token0`, rounded up.
+fn next_sqrt_price_from_amount0(sqrt_price: U256, liquidity: u128, amount: U256, add: bool) -> Option<U256> {
+	if amount.is_zero() {
+		return Some(sqrt_price);
+	}
+	let numerator1 = U256::from(liquidity) << 96;
+	let product = amount.checked_mul(sqrt_price)?;
+	let denominator = if add {
+		numerator1.checked_add(product)?
+	} else {
+		numerator1.checked_sub(product).filter(|denominator| !denominator.is_zero())?
+	};
+	mul_div_rounding_up(numerator1, sqrt_price, denominator)
+}
+
+/// The square root price after adding or removing `amount` of `token1`, rounded down.
+fn next_sqrt_price_from_amount1(sqrt_price: U256, liquidity: u128, amount: U256, add: bool) -> Option<U256> {
+	if add {
+		sqrt_price.checked_add(mul_div(amount, q96(), U256::from(liquidity))?)
+	} else {
+		sqrt_price.checked_sub(mul_div_rounding_up(amount, q96(), U256::from(liquidity))?)
+	}
+}
+
+/// Swap as much of `amount_remaining` as possible, fee included, without moving the square root
+/// price past `sqrt_target`.
+pub fn compute_swap_step(
+	sqrt_current: U256,
+	sqrt_target: U256,
+	liquidity: u128,
+	amount_remaining: U256,
+	fee: u32,
+) -> Option<SwapStep> {
+	let zero_for_one = sqrt_current >= sqrt_target;
+	let denominator = U256::from(FEE_DENOMINATOR);
+	let fee_complement = U256::from(FEE_DENOMINATOR.checked_sub(fee)?);
+	let amount_remaining_less_fee = mul_div(amount_remaining, fee_complement, denominator)?;
+	let amount_in_to_target = if zero_for_one {
+		amount0_delta(sqrt_target, sqrt_current, liquidity, true)?
+	} else {
+		amount1_delta(sqrt_current, sqrt_target, liquidity, true)?
+	};
+	let sqrt_price_next = if amount_remaining_less_fee >= amount_in_to_target {
+		sqrt_target
+	} else if zero_for_one {
+		next_sqrt_price_from_amount0(sqrt_current, liquidity, amount_remaining_less_fee, true)?
+	} else {
+		next_sqrt_price_from_amount1(sqrt_current, liquidity, amount_remaining_less_fee, true)?
+	};
+	let reached_target = sqrt_price_next == sqrt_target;
+	let (amount_in, amount_out) = if zero_for_one {
+		(
+			if reached_target { amount_in_to_target } else { amount0_delta(sqrt_price_next, sqrt_current, liquidity, true)? },
+			amount1_delta(sqrt_price_next, sqrt_current, liquidity, false)?,
+		)
+	} else {
+		(
+			if reached_target { amount_in_to_target } else { amount1_delta(sqrt_current, sqrt_price_next, liquidity, true)? },
+			amount0_delta(sqrt_current, sqrt_price_next, liquidity, false)?,
+		)
+	};
+	// The remainder of a partial step is taken entirely as fee
+	let fee_amount = if reached_target {
+		mul_div_rounding_up(amount_in, U256::from(fee), fee_complement)?
+	} else {
+		amount_remaining.checked_sub(amount_in)?
+	};
+	Some(SwapStep { sqrt_price_next, amount_in, amount_out, fee_amount })
+}
+
+/// The amounts of `token0` and `token1` of `liquidity` in the range between `tick_lower` and
+/// `tick_upper` at the current square root price.
+pub fn amounts_for_liquidity(
+	sqrt_price: U256,
+	tick: i32,
+	tick_lower: i32,
+	tick_upper: i32,
+	liquidity: u128,
+	round_up: bool,
+) -> Option<(U256, U256)> {
+	let sqrt_lower = sqrt_ratio_at_tick(tick_lower)?;
+	let sqrt_upper = sqrt_ratio_at_tick(tick_upper)?;
+	if tick < tick_lower {
+		Some((amount0_delta(sqrt_lower, sqrt_upper, liquidity, round_up)?, U256::zero()))
+	} else if tick < tick_upper {
+		Some((
+			amount0_delta(sqrt_price, sqrt_upper, liquidity, round_up)?,
+			amount1_delta(sqrt_lower, sqrt_price, liquidity, round_up)?,
+		))
+	} else {
+		Some((U256::zero(), amount1_delta(sqrt_lower, sqrt_upper, liquidity, round_up)?))
+	}
+}
+
+/// The fee growth inside the range between two ticks. Fee growth wraps around on overflow, so
+/// only the difference between two readings is meaningful.
+pub fn fee_growth_inside(
+	lower: &TickInfo,
+	tick_lower: i32,
+	upper: &TickInfo,
+	tick_upper: i32,
+	tick: i32,
+	fee_growth_global0: U256,
+	fee_growth_global1: U256,
+) -> (U256, U256) {
+	let below = |outside: U256, global: U256| {
+		if tick >= tick_lower { outside } else { global.overflowing_sub(outside).0 }
+	};
+	let above = |outside: U256, global: U256| {
+		if tick < tick_upper { outside } else { global.overflowing_sub(outside).0 }
+	};
+	let inside = |global: U256, below: U256, above: U256| {
+		global.overflowing_sub(below).0.overflowing_sub(above).0
+	};
+	(
+		inside(
+			fee_growth_global0,
+			below(lower.fee_growth_outside0, fee_growth_global0),
+			above(upper.fee_growth_outside0, fee_growth_global0),
+		),
+		inside(
+			fee_growth_global1,
+			below(lower.fee_growth_outside1, fee_growth_global1),
+			above(upper.fee_growth_outside1, fee_growth_global1),
+		),
+	)
+}
+
+/// The fees earned by `liquidity` for a fee growth from `last` to `current`.
+pub fn fees_earned(current: U256, last: U256, liquidity: u128) -> Option<U256> {
+	mul_div(current.overflowing_sub(last).0, U256::from(liquidity), q128())
+}
+
+/// The next initialized tick from `tick` in ascending `ticks`: the greatest one at or below
+/// `tick` if `lte`, else the least one above it. Falls back to the end of the price range, which
+/// is not initialized.
+pub fn next_initialized_tick(ticks: &[i32], tick: i32, lte: bool) -> (i32, bool) {
+	let next = match (ticks.binary_search(&tick), lte) {
+		(Ok(i), true) => Some(i),
+		(Err(i), true) => i.checked_sub(1),
+		(Ok(i), false) => Some(i + 1),
+		(Err(i), false) => Some(i),
+	};
+	match next.and_then(|i| ticks.get(i)) {
+		Some(next) => (*next, true),
+		None if lte => (MIN_TICK, false),
+		None => (MAX_TICK, false),
+	}
+}
+
+/// `liquidity` changed by a signed `delta`.
+pub fn add_delta(liquidity: u128, delta: i128) -> Option<u128> {
+	if delta < 0 {
+		liquidity.checked_sub(delta.checked_neg()? as u128)
+	} else {
+		liquidity.checked_add(delta as u128)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sqrt_ratio_at_tick_matches_uniswap() {
+		assert_eq!(sqrt_ratio_at_tick(0), Some(q96()));
+		assert_eq!(sqrt_ratio_at_tick(MIN_TICK), Some(MIN_SQRT_RATIO));
+		assert_eq!(sqrt_ratio_at_tick(MAX_TICK), Some(MAX_SQRT_RATIO));
+		assert_eq!(sqrt_ratio_at_tick(MAX_TICK + 1), None);
+	}
+
+	#[test]
+	fn tick_at_sqrt_ratio_inverts_sqrt_ratio_at_tick() {
+		for tick in &[MIN_TICK, -60_000, -1, 0, 1, 60_000, MAX_TICK - 1] {
+			let sqrt_price = sqrt_ratio_at_tick(*tick).unwrap();
+			assert_eq!(tick_at_sqrt_ratio(sqrt_price), Some(*tick));
+			assert_eq!(tick_at_sqrt_ratio(sqrt_price + U256::one()), Some(*tick));
+		}
+		assert_eq!(tick_at_sqrt_ratio(MAX_SQRT_RATIO), None);
+	}
+
+	#[test]
+	fn amounts_round_in_favour_of_the_pool() {
+		let (sqrt_a, sqrt_b) = (sqrt_ratio_at_tick(-600).unwrap(), sqrt_ratio_at_tick(600).unwrap());
+		let down = amount0_delta(sqrt_a, sqrt_b, 1_000_000, false).unwrap();
+		let up = amount0_delta(sqrt_a, sqrt_b, 1_000_000, true).unwrap();
+		assert_eq!(up, down + U256::one());
+		let down = amount1_delta(sqrt_a, sqrt_b, 1_000_000, false).unwrap();
+		let up = amount1_delta(sqrt_a, sqrt_b, 1_000_000, true).unwrap();
+		assert_eq!(up, down + U256::one());
+	}
+
+	#[test]
+	fn swap_step_stops_at_target() {
+		let step = compute_swap_step(q96(), sqrt_ratio_at_tick(-10).unwrap(), 1_000_000, U256::from(1_000_000u32), 30).unwrap();
+		assert_eq!(step.sqrt_price_next, sqrt_ratio_at_tick(-10).unwrap());
+		assert!(step.amount_in + step.fee_amount < U256::from(1_000_000u32));
+	}
+
+	#[test]
+	fn swap_step_spends_whole_input_within_range() {
+		let step = compute_swap_step(q96(), MIN_SQRT_RATIO, 1_000_000, U256::from(1_000u32), 30).unwrap();
+		assert_eq!(step.amount_in + step.fee_amount, U256::from(1_000u32));
+		assert!(step.amount_out < U256::from(1_000u32));
+	}
+
+	#[test]
+	fn next_initialized_tick_searches_in_direction() {
+		let ticks = [-60, 0, 60];
+		assert_eq!(next_initialized_tick(&ticks, 0, true), (0, true));
+		assert_eq!(next_initialized_tick(&ticks, -1, true), (-60, true));
+		assert_eq!(next_initialized_tick(&ticks, -61, true), (MIN_TICK, false));
+		assert_eq!(next_initialized_tick(&ticks, 0, false), (60, true));
+		assert_eq!(next_initialized_tick(&ticks, -61, false), (-60, true));
+		assert_eq!(next_initialized_tick(&ticks, 60, false), (MAX_TICK, false));
+	}
+
+	#[test]
+	fn add_delta_is_checked() {
+		assert_eq!(add_delta(1, -1), Some(0));
+		assert_eq!(add_delta(0, -1), None);
+		assert_eq!(add_delta(u128::max_value(), 1), None);
+	}
+}
@@ -0,0 +1,360 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bonding curves pricing the swaps and the liquidity of a pair.
+
+use codec::{Encode, Decode};
+use sp_core::U256;
+use sp_runtime::RuntimeDebug;
+use sp_runtime::traits::{AtLeast32BitUnsigned, Zero};
+use crate::FEE_DENOMINATOR;
+use crate::math::{self, MathError, MathResult, to_u256, from_u256};
+
+/// The largest amplification coefficient of a StableSwap pair.
+pub const MAX_AMPLIFICATION: u32 = 1_000_000;
+
+/// The number of Newton iterations after which the StableSwap math gives up converging.
+const MAX_ITERATIONS: usize = 255;
+
+/// A bonding curve of a pair.
+///
+/// Reserves and deposits are ordered as the reserves of the pair, and `fee` is in basis points
+/// of the input amount. Every function fails with a `MathError` rather than panicking when a
+/// result does not fit in a balance or when the pair cannot satisfy the request.
+pub trait Curve<Balance: AtLeast32BitUnsigned + Copy> {
+	/// The output amount of swapping `amount_in`, rounded down.
+	fn amount_out(&self, amount_in: Balance, reserve_in: Balance, reserve_out: Balance, fee: u32) -> MathResult<Balance>;
+
+	/// The input amount required to swap for `amount_out`, rounded up.
+	fn amount_in(&self, amount_out: Balance, reserve_in: Balance, reserve_out: Balance, fee: u32) -> MathResult<Balance>;
+
+	/// The liquidity provider token minted for depositing `amounts` to a pair with `reserves` and
+	/// `total_supply` of liquidity provider token. A `total_supply` of zero creates the pair.
+	fn liquidity_minted(
+		&self,
+		amounts: (Balance, Balance),
+		reserves: (Balance, Balance),
+		total_supply: Balance,
+		fee: u32,
+	) -> MathResult<Balance>;
+
+	/// The amounts redeemed by burning `amount` of liquidity provider token, pro-rata to the
+	/// reserves.
+	fn liquidity_value(
+		&self,
+		amount: Balance,
+		reserves: (Balance, Balance),
+		total_supply: Balance,
+	) -> MathResult<(Balance, Balance)> {
+		math::liquidity_value(amount, reserves, total_supply)
+	}
+
+	/// An invariant of the reserves growing with the square of the liquidity, like `x * y`.
+	/// The protocol fee is minted from the growth of its square root.
+	fn invariant(&self, reserves: (Balance, Balance)) -> MathResult<U256>;
+}
+
+/// The kind of bonding curve of a pair.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub enum CurveType {
+	/// The constant product `x * y = k` of Uniswap.
+	ConstantProduct,
+	/// The StableSwap invariant of Curve finance with the given amplification coefficient,
+	/// for pairs of pegged assets.
+	StableSwap(u32),
+}
+
+impl Default for CurveType {
+	fn default() -> Self {
+		CurveType::ConstantProduct
+	}
+}
+
+impl CurveType {
+	/// Whether the parameters of the curve are in range.
+	pub fn is_valid(&self) -> bool {
+		match self {
+			CurveType::ConstantProduct => true,
+			CurveType::StableSwap(amplification) =>
+				*amplification > 0 && *amplification <= MAX_AMPLIFICATION,
+		}
+	}
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> Curve<Balance> for CurveType {
+	fn amount_out(&self, amount_in: Balance, reserve_in: Balance, reserve_out: Balance, fee: u32) -> MathResult<Balance> {
+		match self {
+			CurveType::ConstantProduct => ConstantProduct.amount_out(amount_in, reserve_in, reserve_out, fee),
+			CurveType::StableSwap(a) => StableSwap(*a).amount_out(amount_in, reserve_in, reserve_out, fee),
+		}
+	}
+
+	fn amount_in(&self, amount_out: Balance, reserve_in: Balance, reserve_out: Balance, fee: u32) -> MathResult<Balance> {
+		match self {
+			CurveType::ConstantProduct => ConstantProduct.amount_in(amount_out, reserve_in, reserve_out, fee),
+			CurveType::StableSwap(a) => StableSwap(*a).amount_in(amount_out, reserve_in, reserve_out, fee),
+		}
+	}
+
+	fn liquidity_minted(
+		&self,
+		amounts: (Balance, Balance),
+		reserves: (Balance, Balance),
+		total_supply: Balance,
+		fee: u32,
+	) -> MathResult<Balance> {
+		match self {
+			CurveType::ConstantProduct => ConstantProduct.liquidity_minted(amounts, reserves, total_supply, fee),
+			CurveType::StableSwap(a) => StableSwap(*a).liquidity_minted(amounts, reserves, total_supply, fee),
+		}
+	}
+
+	fn invariant(&self, reserves: (Balance, Balance)) -> MathResult<U256> {
+		match self {
+			CurveType::ConstantProduct => ConstantProduct.invariant(reserves),
+			CurveType::StableSwap(a) => StableSwap(*a).invariant(reserves),
+		}
+	}
+}
+
+/// The constant product curve `x * y = k`.
+pub struct ConstantProduct;
+
+impl<Balance: AtLeast32BitUnsigned + Copy> Curve<Balance> for ConstantProduct {
+	fn amount_out(&self, amount_in: Balance, reserve_in: Balance, reserve_out: Balance, fee: u32) -> MathResult<Balance> {
+		math::get_amount_out(amount_in, reserve_in, reserve_out, fee)
+	}
+
+	fn amount_in(&self, amount_out: Balance, reserve_in: Balance, reserve_out: Balance, fee: u32) -> MathResult<Balance> {
+		math::get_amount_in(amount_out, reserve_in, reserve_out, fee)
+	}
+
+	fn liquidity_minted(
+		&self,
+		amounts: (Balance, Balance),
+		reserves: (Balance, Balance),
+		total_supply: Balance,
+		_fee: u32,
+	) -> MathResult<Balance> {
+		math::liquidity_minted(amounts, reserves, total_supply)
+	}
+
+	fn invariant(&self, reserves: (Balance, Balance)) -> MathResult<U256> {
+		// The product of two `u128` fits in 256 bits
+		Ok(to_u256(reserves.0)? * to_u256(reserves.1)?)
+	}
+}
+
+/// The StableSwap curve `A * n^n * (x + y) + D = A * n^n * D + D^(n + 1) / (n^n * x * y)` for
+/// `n = 2` assets with the amplification coefficient `A`.
+///
+/// The curve is flat like a constant sum around balanced reserves and bends to a constant
+/// product as they drift apart, keeping the price of pegged assets close to one.
+pub struct StableSwap(pub u32);
+
+impl StableSwap {
+	/// `A * n^n`.
+	fn ann(&self) -> U256 {
+		U256::from(self.0) * U256::from(4u32)
+	}
+
+	/// Solve the invariant `D` of the reserves `x` and `y` with Newton's method.
+	fn d(&self, x: U256, y: U256) -> MathResult<U256> {
+		let s = x.checked_add(y).ok_or(MathError::Overflow)?;
+		if s.is_zero() {
+			return Ok(s);
+		}
+		if x.is_zero() || y.is_zero() {
+			return Err(MathError::DivisionByZero);
+		}
+		let mut d = s;
+		for _ in 0..MAX_ITERATIONS {
+			let d_prev = d;
+			d = self.d_step(d, s, x, y).ok_or(MathError::Overflow)?;
+			if abs_diff(d, d_prev) <= U256::one() {
+				return Ok(d);
+			}
+		}
+		Err(MathError::NotConverged)
+	}
+
+	/// An iteration of Newton's method solving `D` from the sum `s` of the reserves `x` and `y`.
+	fn d_step(&self, d: U256, s: U256, x: U256, y: U256) -> Option<U256> {
+		let two = U256::from(2u32);
+		let ann = self.ann();
+		// D^(n + 1) / (n^n * x * y)
+		let d_p = d.checked_mul(d)?.checked_div(x.checked_mul(two)?)?
+			.checked_mul(d)?.checked_div(y.checked_mul(two)?)?;
+		let numerator = ann.checked_mul(s)?.checked_add(d_p.checked_mul(two)?)?.checked_mul(d)?;
+		let denominator = ann.checked_sub(U256::one())?.checked_mul(d)?.checked_add(d_p.checked_mul(U256::from(3u32))?)?;
+		numerator.checked_div(denominator)
+	}
+
+	/// Solve the reserve `y` paired with the reserve `x` under the invariant `d` with Newton's
+	/// method. The curve is symmetric, so this solves `x` from `y` as well.
+	fn y(&self, x: U256, d: U256) -> MathResult<U256> {
+		let (b, c) = self.y_coefficients(x, d).ok_or(MathError::Overflow)?;
+		let mut y = d;
+		for _ in 0..MAX_ITERATIONS {
+			let y_prev = y;
+			y = Self::y_step(y, b, c, d).ok_or(MathError::Overflow)?;
+			if abs_diff(y, y_prev) <= U256::one() {
+				return Ok(y);
+			}
+		}
+		Err(MathError::NotConverged)
+	}
+
+	/// The coefficients `(b, c)` of `y^2 + (b - D) * y = c` solving the reserve `y` paired with
+	/// `x` under the invariant `d`.
+	fn y_coefficients(&self, x: U256, d: U256) -> Option<(U256, U256)> {
+		let two = U256::from(2u32);
+		let ann = self.ann();
+		let c = d.checked_mul(d)?.checked_div(x.checked_mul(two)?)?
+			.checked_mul(d)?.checked_div(ann.checked_mul(two)?)?;
+		let b = x.checked_add(d.checked_div(ann)?)?;
+		Some((b, c))
+	}
+
+	/// An iteration of Newton's method solving `y^2 + (b - D) * y = c`.
+	fn y_step(y: U256, b: U256, c: U256, d: U256) -> Option<U256> {
+		let numerator = y.checked_mul(y)?.checked_add(c)?;
+		let denominator = y.checked_mul(U256::from(2u32))?.checked_add(b)?.checked_sub(d)?;
+		numerator.checked_div(denominator)
+	}
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> Curve<Balance> for StableSwap {
+	fn amount_out(&self, amount_in: Balance, reserve_in: Balance, reserve_out: Balance, fee: u32) -> MathResult<Balance> {
+		if reserve_in.is_zero() || reserve_out.is_zero() {
+			return Err(MathError::InsufficientLiquidity);
+		}
+		let (x, y) = (to_u256(reserve_in)?, to_u256(reserve_out)?);
+		let d = self.d(x, y)?;
+		let fee_complement = FEE_DENOMINATOR.checked_sub(fee).ok_or(MathError::InvalidFee)?;
+		// Balances are below 2^128, so neither the product nor the sum overflows
+		let amount_in_with_fee = to_u256(amount_in)? * U256::from(fee_complement) / U256::from(FEE_DENOMINATOR);
+		let y_new = self.y(x + amount_in_with_fee, d)?;
+		// Round down in favour of the pair
+		from_u256(y.saturating_sub(y_new).saturating_sub(U256::one()))
+	}
+
+	fn amount_in(&self, amount_out: Balance, reserve_in: Balance, reserve_out: Balance, fee: u32) -> MathResult<Balance> {
+		if reserve_in.is_zero() || amount_out >= reserve_out {
+			return Err(MathError::InsufficientLiquidity);
+		}
+		let fee_complement = match FEE_DENOMINATOR.checked_sub(fee) {
+			Some(complement) if complement > 0 => U256::from(complement),
+			_ => return Err(MathError::InvalidFee),
+		};
+		let (x, y) = (to_u256(reserve_in)?, to_u256(reserve_out)?);
+		let d = self.d(x, y)?;
+		let x_new = self.y(y - to_u256(amount_out)?, d)?;
+		// Round up in favour of the pair
+		let amount_in_with_fee = x_new.checked_sub(x).ok_or(MathError::InsufficientLiquidity)?;
+		let amount_in = amount_in_with_fee.checked_add(U256::one())
+			.and_then(|amount| amount.checked_mul(U256::from(FEE_DENOMINATOR)))
+			.and_then(|amount| (amount / fee_complement).checked_add(U256::one()))
+			.ok_or(MathError::Overflow)?;
+		from_u256(amount_in)
+	}
+
+	fn liquidity_minted(
+		&self,
+		amounts: (Balance, Balance),
+		reserves: (Balance, Balance),
+		total_supply: Balance,
+		fee: u32,
+	) -> MathResult<Balance> {
+		let amounts = (to_u256(amounts.0)?, to_u256(amounts.1)?);
+		if total_supply.is_zero() {
+			return from_u256(self.d(amounts.0, amounts.1)?);
+		}
+		let reserves = (to_u256(reserves.0)?, to_u256(reserves.1)?);
+		let d0 = self.d(reserves.0, reserves.1)?;
+		if d0.is_zero() {
+			return Err(MathError::DivisionByZero);
+		}
+		// Balances are below 2^128, so the sums do not overflow
+		let mut new_reserves = (reserves.0 + amounts.0, reserves.1 + amounts.1);
+		let d1 = self.d(new_reserves.0, new_reserves.1)?;
+		if d1 <= d0 {
+			return Ok(Zero::zero());
+		}
+		// Charge the swap fee on the imbalance of the deposit, of which half would be swapped
+		// to deposit in the ratio of the reserves
+		let charge = |reserve: U256, new_reserve: U256| -> MathResult<U256> {
+			let ideal = d1.checked_mul(reserve).ok_or(MathError::Overflow)? / d0;
+			let difference = abs_diff(ideal, new_reserve);
+			let charged = difference.checked_mul(U256::from(fee)).ok_or(MathError::Overflow)?
+				/ U256::from(2 * FEE_DENOMINATOR);
+			new_reserve.checked_sub(charged).ok_or(MathError::InsufficientLiquidity)
+		};
+		new_reserves = (charge(reserves.0, new_reserves.0)?, charge(reserves.1, new_reserves.1)?);
+		let d2 = self.d(new_reserves.0, new_reserves.1)?;
+		let minted = to_u256(total_supply)?.checked_mul(d2.saturating_sub(d0)).ok_or(MathError::Overflow)? / d0;
+		from_u256(minted)
+	}
+
+	fn invariant(&self, reserves: (Balance, Balance)) -> MathResult<U256> {
+		let d = self.d(to_u256(reserves.0)?, to_u256(reserves.1)?)?;
+		d.checked_mul(d).ok_or(MathError::Overflow)
+	}
+}
+
+fn abs_diff(a: U256, b: U256) -> U256 {
+	if a > b { a - b } else { b - a }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn constant_product_quotes_match_uniswap() {
+		assert_eq!(ConstantProduct.amount_out(1_000u128, 1_000_000, 4_000_000, 30), Ok(3_984));
+		assert_eq!(ConstantProduct.amount_in(3_984u128, 1_000_000, 4_000_000, 30), Ok(1_000));
+		assert_eq!(
+			ConstantProduct.amount_in(4_000_000u128, 1_000_000, 4_000_000, 30),
+			Err(MathError::InsufficientLiquidity)
+		);
+	}
+
+	#[test]
+	fn stable_swap_is_flat_around_balanced_reserves() {
+		let curve = StableSwap(100);
+		let amount_out = curve.amount_out(10_000u128, 1_000_000, 1_000_000, 0).unwrap();
+		let product_out = ConstantProduct.amount_out(10_000u128, 1_000_000, 1_000_000, 0).unwrap();
+		assert!(amount_out > product_out);
+		assert!(amount_out < 10_000);
+		assert!(curve.amount_in(amount_out, 1_000_000, 1_000_000, 0).unwrap() <= 10_002);
+	}
+
+	#[test]
+	fn stable_swap_invariant_of_balanced_reserves_is_their_sum() {
+		let d = StableSwap(100).d(U256::from(1_000_000u32), U256::from(1_000_000u32));
+		assert_eq!(d, Ok(U256::from(2_000_000u32)));
+	}
+
+	#[test]
+	fn curve_type_validates_amplification() {
+		assert!(CurveType::ConstantProduct.is_valid());
+		assert!(CurveType::StableSwap(MAX_AMPLIFICATION).is_valid());
+		assert!(!CurveType::StableSwap(0).is_valid());
+		assert!(!CurveType::StableSwap(MAX_AMPLIFICATION + 1).is_valid());
+	}
+}
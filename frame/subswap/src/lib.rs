@@ -17,18 +17,21 @@
 
 //! # Subswap Module
 //!
-//! An automated market maker module extended from the [asset](../asset/Module.html) module.
+//! An automated market maker module built on top of the [subswap asset](../subswap_asset/index.html) ledger.
 //!
 //! ## Overview
 //!
-//! The Subswap module provides functionality for management and exchange of fungible asset classes
-//! with a fixed supply, including:
+//! The Subswap module provides functionality for exchange of fungible asset classes, including:
 //!
 //! * Liquidity provider token issuance
 //! * Compensation for providing liquidity
 //! * Automated liquidity provisioning
 //! * Asset exchange
 //!
+//! The module only depends on the [`MultiAsset`](../subswap_asset/trait.MultiAsset.html) trait, so any
+//! ledger implementing it can back the market. Pool reserves are held by the module account derived
+//! from `Trait::ModuleId`.
+//!
 //! To use it in your runtime, you need to implement the subswap [`Trait`](./trait.Trait.html).
 //!
 //! The supported dispatchable functions are documented in the [`Call`](./enum.Call.html) enum.
@@ -36,6 +39,32 @@
 //! ### Terminology
 //!
 //! * **Liquidity provider token:** The creation of a new asset by providing liquidity between two fungible assets. Liquidity provider token act as the share of the pool and gets the profit created from exchange fee.
+//! * **Minimum liquidity:** The liquidity provider token minted to a lock account out of the first deposit to a pair,
+//! so that the total supply of the pair never returns to zero.
+//! * **Bonding curve:** The invariant a pair keeps between its reserves, which prices its swaps and liquidity. A pair
+//! uses either the constant product `x * y = k`, or the StableSwap invariant of Curve finance for pegged assets,
+//! which stays close to a constant sum around balanced reserves depending on its amplification coefficient.
+//! * **Protocol fee:** A share of the swap fees of a pair minted as liquidity provider token to an account chosen by
+//! governance, such as the treasury, while it is switched on.
+//! * **Weighted pool:** A pool of 2 to 8 assets keeping the weighted product `Π balance_i ^ weight_i = k` of Balancer,
+//! so that each asset holds a fixed share of the value of the pool given by its normalized weight, such as 80/20.
+//! Liquidity can be added or removed with a single asset, paying the swap fee on the part swapped to the other assets.
+//! * **Concentrated liquidity:** A pool where each position provides liquidity to a price range between two ticks, as in
+//! Uniswap v3, so that liquidity is only used while the price is in range. The price at tick `i` is `1.0001 ^ i`. Positions
+//! are non-fungible records owned by an account, which earn the swap fees paid while the price is in their range.
+//! * **Liquidity mining:** Staking liquidity provider token in its mining pool to earn the native currency SUB. Governance
+//! sets the reward per block and splits it between the mining pools by their allocation points. The reward of each pool is
+//! accumulated per staked token, so that it is settled lazily whenever a staker interacts with the pool.
+//! * **Flash swap:** Borrowing the reserves of a pair within a single extrinsic. The borrowed asset is sent first and a
+//! call of the borrower is dispatched, after which the pair must be repaid so that its invariant holds after the swap fee.
+//! * **Limit order:** An order selling an asset through a pair once the pair pays at least a limit price, given by the
+//! minimum output for the whole input. The input is held by the system while the order is open. Open orders are settled
+//! in turn at the beginning of each block, filling as much of an order as meets its limit price and refunding it once it
+//! expires. An expired order which cannot be refunded yet, such as while its input asset is frozen, stays open until it
+//! can. Both the orders open at once and the orders of a single account open at once are limited.
+//! * **Zap:** Providing liquidity with a single asset of a pair, or removing it for a single asset. Zapping in swaps the
+//! part of the input whose output leaves the rest in the ratio of the reserves after the swap, solved in closed form for
+//! the swap fee of the pair, so that almost nothing is left over.
 //! * **Asset exchange:** The process of an account transferring an asset to exchange with other kind of fungible asset.
 //! * **Fungible asset:** An asset whose units are interchangeable.
 //! * **Non-fungible asset:** An asset for which each unit has unique characteristics.
@@ -46,71 +75,109 @@
 //!
 //! * Reward liquidity providers with tokens to receive exchanges fees which is proportional to their contribution.
 //! * Swap assets with automated market price equation(e.g. X*Y=K or curve function from Kyber, dodoex, etc).
-//! * Issue an fungible asset which can be backed with opening exchange with other assets 
+//! * Issue an fungible asset which can be backed with opening exchange with other assets
 //!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
 //!
-//! * `issue` - Issues the total supply of a new fungible asset to the account of the caller of the function.
-//! * `mint` - Mints the asset to the account in the argument with the requested amount from the caller. Caller must be the creator of the asset.
-//! * `burn` - Burns the asset from the caller by the amount in the argument 
-//! * `transfer` - Transfers an `amount` of units of fungible asset `id` from the balance of
-//! the function caller's account (`origin`) to a `target` account.
-//! * `destroy` - Destroys the entire holding of a fungible asset `id` associated with the account
-//! that called the function.
 //! * `mint_liquidity` - Mints liquidity token by adding deposits to a certain pair for exchange. The assets must have different identifier.
-//! * `burn_liquidity` - Burns liquidity token for a pair and receives each asset in the pair.  
-//! * `swap` - Swaps from one asset to the another, paying 0.3% fee to the liquidity providers.
+//! * `burn_liquidity` - Burns liquidity token for a pair and receives each asset in the pair.
+//! * `add_liquidity` - Deposits two assets to their pair in the ratio of its reserves, refunding the rest, with minimum
+//! deposits and a deadline.
+//! * `remove_liquidity` - Burns liquidity token of the pair of two assets, with minimum outputs and a deadline.
+//! * `zap_in` - Provides liquidity to a constant product pair with a single asset, swapping the part of it which keeps
+//! the rest in the ratio of the reserves, with a minimum of liquidity minted.
+//! * `zap_out` - Burns liquidity token of a pair for a single asset, swapping the other asset redeemed, with a minimum
+//! output.
+//! * `create_pair` - Creates a pair with initial liquidity, a bonding curve and a swap fee in basis points.
+//! * `swap` - Swaps from one asset to the another, paying the swap fee of the pair to the liquidity providers.
+//! * `swap_exact_in_along_path` - Swaps an exact amount of an asset along a path of pairs, with a minimum output
+//! and a deadline.
+//! * `swap_exact_out` - Swaps as little of an asset as possible along a path of pairs for an exact output amount, with a
+//! maximum input and a deadline.
+//! * `flash_swap` - Borrows an asset of a pair, dispatches a call with the borrower's origin and then repays either
+//! asset of the pair, keeping the invariant of the pair after the swap fee.
+//! * `set_fee` - Changes the swap fee of a pair. Requires the governance origin.
+//! * `set_fee_to` - Sets or clears the account receiving the protocol fee. Requires the governance origin.
+//! * `create_weighted_pool` - Creates a weighted pool of 2 to 8 assets with initial balances, weights and a swap fee.
+//! * `join_pool_single` - Deposits a single asset to a weighted pool for its liquidity provider token.
+//! * `exit_pool_single` - Burns liquidity provider token of a weighted pool for a single asset.
+//! * `exit_pool` - Burns liquidity provider token of a weighted pool for its share of every asset.
+//! * `swap_weighted` - Swaps an exact amount of an asset for another asset of a weighted pool, with a minimum output.
+//! * `create_concentrated_pool` - Creates a concentrated liquidity pool at a price with a swap fee and a tick spacing.
+//! * `mint_position` - Provides liquidity to a price range of a concentrated liquidity pool as a new position.
+//! * `decrease_liquidity` - Withdraws liquidity from a position, owing the assets to the position.
+//! * `collect` - Collects the fees earned and the assets withdrawn by a position.
+//! * `burn_position` - Removes an empty position.
+//! * `transfer_position` - Transfers a position to another account.
+//! * `swap_concentrated` - Swaps an exact amount of an asset with a concentrated liquidity pool, with a minimum output, an
+//! optional price limit and a maximum number of initialized ticks crossed.
+//! * `set_reward_per_block` - Sets the native currency distributed to stakers per block. Requires the governance origin.
+//! * `set_mining_pool` - Creates the mining pool of a liquidity provider token or changes its allocation points. Requires
+//! the governance origin.
+//! * `stake_lp` - Stakes liquidity provider token in its mining pool.
+//! * `unstake_lp` - Unstakes liquidity provider token from its mining pool, claiming its reward.
+//! * `claim_rewards` - Claims the reward of the liquidity provider token staked in a mining pool.
+//! * `place_limit_order` - Places an order selling an asset for another at a limit price until a block, holding its input.
+//! * `cancel_order` - Cancels an open limit order, returning the input not sold yet.
 //!
 //! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
 //!
 //! ### Public Functions
 //!
-//! * `balance` - Get the balance of the account with the asset id
-//! * `total_supply` - Get the total supply of an asset.
-//! * `mint_from_system` - Mint asset from the system to an account, increasing total supply.
-//! * `burn_from_system` - Burn asset from the system to an account, decreasing total supply.
-//! * `transfer_from_system - Transfer asset from an account to the system with no change in total supply.
-//! * `transfer_to_system - Transfer asset from system to the user with no chang in total supply.
-//! * `issue_from_system` - Issue asset from system 
-//! * `swap` - Swap one asset to another asset
-//! 
+//! * `account_id` - Get the account holding the reserves of every pair.
+//! * `lock_account_id` - Get the account holding the minimum liquidity locked by every pair.
+//! * `swap_into_pool` - Swap an asset of an account for an exact output left in the pool account, for other modules
+//! taking it out, such as a transaction fee paid in another asset.
+//! * `swap_out_of_pool` - Swap an input put into the pool account by another module for an output paid to an account.
+//! * `optimal_amounts` - Get the amounts of two assets deposited to their pair in the ratio of its reserves.
+//! * `_get_amount_out` - Get the output amount of a swap for the given curve, reserves and fee.
+//! * `_get_amount_in` - Get the input amount required by a swap for the given curve, reserves and fee.
+//! * `get_reserves` - Get the reserves of a pair in the direction of a swap.
+//! * `get_amounts_out` - Get the output amounts of every step of a swap along a path.
+//! * `get_amounts_in` - Get the input amounts of every step of a swap along a path.
+//! * `pairs` - Get every pair with its liquidity provider token.
+//! * `lp_share_value` - Get the assets redeemed by burning an amount of liquidity provider token.
+//! * `pending_rewards` - Get the reward of a stake in a mining pool which is not claimed yet.
+//! * `current_cumulative_prices` - Get the accumulated prices of a pair as of now.
+//! * `consult` - Get the time weighted average prices of a pair over a window of time.
+//!
 //! Please refer to the [`Module`](./struct.Module.html) struct for details on publicly available functions.
 //!
+//! ### Genesis Configuration
+//!
+//! A chain may start with pairs already seeded through `GenesisConfig::pairs`. Each provider of a
+//! pair deposits assets from its balances, which are set up by the genesis of the asset ledger,
+//! and receives liquidity provider token as if it minted liquidity at genesis.
+//!
 //! ## Usage
 //!
 //! The following example shows how to use the Subswap module in your runtime by exposing public functions to:
 //!
-//! * Issue and manage a new fungible asset.
-//! * Query the fungible asset holding balance of an account.
-//! * Query the total supply of a fungible asset that has been issued.
-//! * Manage existing asset for other business logic
+//! * Exchange assets from another module.
 //!
 //! ### Prerequisites
 //!
-//! Import the Subswap module and types and derive your runtime's configuration traits from the Assets module trait.
+//! Import the Subswap module and types and derive your runtime's configuration traits from the Subswap module trait.
 //!
 //! ### Simple Code Snippet
 //!
 //! ```rust,ignore
 //! use subswap;
-//! use pallet_balances as balances;
 //! use frame_support::{decl_module, dispatch, ensure};
 //! use frame_system::ensure_signed;
 //!
-//! pub trait Trait: subswap::Trait + balances::Trait {
-//! 
+//! pub trait Trait: subswap::Trait {
+//!
 //!  }
 //!
 //! decl_module! {
 //! 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-//! 		pub fn trade(origin, token0: T::AssetId, amount0: <T as balances::Trait>::Balance, token1: T::AssetId) -> dispatch::DispatchResult {
-//! 			let sender = ensure_signed(origin).map_err(|e| e.as_str())?;
+//! 		pub fn trade(origin, token0: subswap::AssetIdOf<T>, amount0: subswap::BalanceOf<T>, token1: subswap::AssetIdOf<T>) -> dispatch::DispatchResult {
+//! 			subswap::Module::<T>::swap(origin, token0, amount0, token1)?;
 //!
-//!             let amount_out = subswap::Module<T>::swap(&token0, &amount0, &token1); 
-//! 			
-//! 			Self::deposit_event(RawEvent::Trade(token0, amount0, token1, amount_out));
+//! 			Self::deposit_event(RawEvent::Trade(token0, amount0, token1));
 //! 			Ok(())
 //! 		}
 //! 	}
@@ -123,310 +190,1180 @@
 //! them are violated, the behavior of this module is undefined.
 //!
 //! * The total count of assets should be less than
-//!   `Trait::AssetId::max_value()`.
+//!   `MultiAsset::AssetId::max_value()`.
 //!
 //! ## Related Modules
 //!
 //! * [`System`](../frame_system/index.html)
 //! * [`Support`](../frame_support/index.html)
+//! * [`Subswap Asset`](../subswap_asset/index.html)
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{Parameter, decl_module, decl_event, decl_storage, decl_error, ensure, dispatch};
-use sp_runtime::traits::{AtLeast32Bit, Zero, StaticLookup};
+use frame_support::{decl_module, decl_event, decl_storage, decl_error, ensure, dispatch, transactional};
+use frame_support::traits::{Get, EnsureOrigin};
+use frame_support::weights::{Weight, GetDispatchInfo};
+use frame_support::storage::{with_transaction, TransactionOutcome};
+use frame_support::dispatch::{Dispatchable, PostDispatchInfo, Parameter};
+use sp_std::prelude::*;
+use sp_std::convert::TryFrom;
+use sp_runtime::traits::{Zero, AccountIdConversion, CheckedAdd, CheckedSub, Saturating, StaticLookup};
+use sp_runtime::{ModuleId, FixedU128, FixedPointNumber, SaturatedConversion, RuntimeDebug};
+use sp_core::U256;
+use codec::{Encode, Decode};
 use frame_system::ensure_signed;
-use sp_runtime::traits::One;
-use pallet_balances as balances;
 use pallet_timestamp as timestamp;
-use sp_runtime::{FixedU128, FixedPointNumber, SaturatedConversion};
-use sp_runtime::traits::{CheckedMul, CheckedAdd, CheckedDiv, CheckedSub};
-use crate::sp_api_hidden_includes_decl_storage::hidden_include::traits::Get;
-mod math;
+use subswap_asset::MultiAsset;
+pub mod math;
+pub mod curve;
+pub mod weighted;
+pub mod concentrated;
+pub use curve::{Curve, CurveType};
+pub use math::MathError;
+pub use weighted::WeightedPool;
+pub use concentrated::{ConcentratedPool, TickInfo, Position};
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod benchmarking;
+mod default_weights;
+
+/// The asset identifier of the ledger backing the market.
+pub type AssetIdOf<T> =
+	<<T as Trait>::Assets as MultiAsset<<T as frame_system::Trait>::AccountId>>::AssetId;
+/// The balance type of the ledger backing the market.
+pub type BalanceOf<T> =
+	<<T as Trait>::Assets as MultiAsset<<T as frame_system::Trait>::AccountId>>::Balance;
+
+/// The identifier of a concentrated liquidity pool.
+pub type PoolId = u32;
+/// The identifier of a concentrated liquidity position.
+pub type PositionId = u64;
+/// The identifier of a limit order.
+pub type OrderId = u64;
+
+/// Swap fees are expressed in basis points of the input amount.
+pub const FEE_DENOMINATOR: u32 = 10_000;
+
+/// The liquidity provider token minted to the lock account when a pair is created, so that the
+/// total supply of a pair never returns to zero.
+pub const MINIMUM_LIQUIDITY: u32 = 1;
+
+/// Accumulated prices of a pair at a point in time, used for time weighted average prices.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, Default)]
+pub struct PriceSnapshot<Moment> {
+	/// The timestamp of the snapshot.
+	pub timestamp: Moment,
+	/// The accumulated price of `token0` in units of `token1`.
+	pub price0_cumulative: U256,
+	/// The accumulated price of `token1` in units of `token0`.
+	pub price1_cumulative: U256,
+}
+
+/// The precision of the accumulated reward per staked liquidity provider token.
+pub const ACC_REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// A pool distributing the native currency to the stakers of a liquidity provider token.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, Default)]
+pub struct MiningPool<BlockNumber, Balance> {
+	/// The share of the reward per block of the pool, out of the total allocation points.
+	pub alloc_point: u32,
+	/// The reward accumulated per staked token since the creation of the pool, scaled by
+	/// `ACC_REWARD_PRECISION`.
+	pub acc_reward_per_share: U256,
+	/// The block up to which the reward is accumulated.
+	pub last_reward_block: BlockNumber,
+	/// The liquidity provider token staked in the pool.
+	pub total_staked: Balance,
+}
+
+/// The liquidity provider token staked by an account in a mining pool.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, Default)]
+pub struct StakeInfo<Balance> {
+	/// The staked amount.
+	pub amount: Balance,
+	/// The reward accumulated by `amount` before it was staked or last claimed, scaled by
+	/// `ACC_REWARD_PRECISION`.
+	pub reward_debt: U256,
+}
+
+/// An order selling an asset for another through their pair whenever the pair pays at least the
+/// limit price of the order.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub struct LimitOrder<AccountId, AssetId, Balance, BlockNumber> {
+	/// The account placing the order and receiving its output.
+	pub owner: AccountId,
+	/// The asset sold by the order.
+	pub asset_in: AssetId,
+	/// The asset bought by the order.
+	pub asset_out: AssetId,
+	/// The amount of `asset_in` sold by the order as placed.
+	pub amount_in: Balance,
+	/// The minimum amount of `asset_out` for the whole `amount_in`, which sets the limit price.
+	pub min_amount_out: Balance,
+	/// The amount of `asset_in` not sold yet, held by the system.
+	pub remaining: Balance,
+	/// The amount of `asset_out` paid to the owner so far.
+	pub filled_out: Balance,
+	/// The last block in which the order may be filled.
+	pub expiry: BlockNumber,
+}
+
+/// Weight functions needed for the subswap module.
+pub trait WeightInfo {
+	fn mint_liquidity() -> Weight;
+	fn create_pair() -> Weight;
+	fn burn_liquidity() -> Weight;
+	fn swap() -> Weight;
+	fn swap_exact_in_along_path(p: u32, ) -> Weight;
+	fn swap_exact_out(p: u32, ) -> Weight;
+	fn flash_swap() -> Weight;
+	fn set_fee() -> Weight;
+	fn set_fee_to() -> Weight;
+	fn create_weighted_pool(a: u32, ) -> Weight;
+	fn join_pool_single() -> Weight;
+	fn exit_pool_single() -> Weight;
+	fn exit_pool(a: u32, ) -> Weight;
+	fn swap_weighted() -> Weight;
+	fn create_concentrated_pool() -> Weight;
+	fn mint_position() -> Weight;
+	fn decrease_liquidity() -> Weight;
+	fn collect() -> Weight;
+	fn burn_position() -> Weight;
+	fn transfer_position() -> Weight;
+	fn swap_concentrated(t: u32, ) -> Weight;
+	fn set_reward_per_block() -> Weight;
+	fn set_mining_pool() -> Weight;
+	fn stake_lp() -> Weight;
+	fn unstake_lp() -> Weight;
+	fn claim_rewards() -> Weight;
+	fn place_limit_order() -> Weight;
+	fn cancel_order() -> Weight;
+	fn settle_orders(n: u32, ) -> Weight;
+	fn add_liquidity() -> Weight;
+	fn remove_liquidity() -> Weight;
+	fn zap_in() -> Weight;
+	fn zap_out() -> Weight;
+}
 
 /// The module configuration trait.
-pub trait Trait: frame_system::Trait + balances::Trait + timestamp::Trait {
+pub trait Trait: frame_system::Trait + timestamp::Trait {
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 
-	/// The units in which we record balances.
-///	type Balance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy;
+	/// The asset ledger which holds the pool reserves and the liquidity provider tokens.
+	type Assets: MultiAsset<Self::AccountId>;
 
-	/// The arithmetic type of asset identifier.
-	type AssetId: Parameter + AtLeast32Bit + Default + Copy;
+	/// The market's module id, used for deriving the account which holds the pool reserves.
+	type ModuleId: Get<ModuleId>;
+
+	/// The minimum time between two price snapshots of a pair.
+	type OracleSnapshotPeriod: Get<Self::Moment>;
+
+	/// The maximum number of price snapshots kept for each pair.
+	type OracleSnapshotCount: Get<u32>;
+
+	/// The swap fee of pairs created by `mint_liquidity`, in basis points.
+	type DefaultFee: Get<u32>;
+
+	/// The origin which may change the swap fee of a pair and the protocol fee recipient.
+	type GovernanceOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The overarching call type, dispatched by flash swaps while the borrowed assets are out.
+	type Call: Parameter + Dispatchable<Origin = Self::Origin, PostInfo = PostDispatchInfo> + GetDispatchInfo;
+
+	/// The maximum number of limit orders open at once.
+	type MaxOpenOrders: Get<u32>;
+
+	/// The maximum number of limit orders of a single account open at once, so that no account
+	/// takes every open slot.
+	type MaxOrdersPerAccount: Get<u32>;
+
+	/// The maximum number of limit orders settled at the beginning of each block.
+	type MaxOrdersPerBlock: Get<u32>;
+
+	/// The maximum number of initialized ticks of a concentrated liquidity pool, all of which a
+	/// swap may look through.
+	type MaxInitializedTicks: Get<u32>;
+
+	/// Weight information for extrinsics in this module.
+	type WeightInfo: WeightInfo;
 }
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		type Error = Error<T>;
 
+		/// The market's module id, used for deriving the account which holds the pool reserves.
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		/// The minimum time between two price snapshots of a pair.
+		const OracleSnapshotPeriod: T::Moment = T::OracleSnapshotPeriod::get();
+
+		/// The maximum number of price snapshots kept for each pair.
+		const OracleSnapshotCount: u32 = T::OracleSnapshotCount::get();
+
+		/// The swap fee of pairs created by `mint_liquidity`, in basis points.
+		const DefaultFee: u32 = T::DefaultFee::get();
+
+		/// The maximum number of limit orders open at once.
+		const MaxOpenOrders: u32 = T::MaxOpenOrders::get();
+
+		/// The maximum number of limit orders of a single account open at once.
+		const MaxOrdersPerAccount: u32 = T::MaxOrdersPerAccount::get();
+
+		/// The maximum number of limit orders settled at the beginning of each block.
+		const MaxOrdersPerBlock: u32 = T::MaxOrdersPerBlock::get();
+
+		/// The maximum number of initialized ticks of a concentrated liquidity pool.
+		const MaxInitializedTicks: u32 = T::MaxInitializedTicks::get();
+
 		fn deposit_event() = default;
-		/// Issue a new class of fungible assets. There are, and will only ever be, `total`
-		/// such assets and they'll all belong to the `origin` initially. It will have an
-		/// identifier `AssetId` instance: this will be specified in the `Issued` event.
+
+		/// Settle up to `MaxOrdersPerBlock` open limit orders against the reserves of their pairs,
+		/// filling the ones whose limit price is met and refunding the expired ones.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			Self::_settle_orders(now)
+		}
+
+		/// Deposit `amount0` of `token0` and `amount1` of `token1` to their pair, minting liquidity
+		/// provider token to the sender.
+		///
+		/// The deposits are taken in the ratio of the reserves of a constant product pair, and the
+		/// part of either deposit exceeding it stays with the sender. The pair is created with the
+		/// given amounts if it does not exist yet.
+		#[weight = T::WeightInfo::mint_liquidity()]
+		#[transactional]
+		pub fn mint_liquidity(origin, token0: AssetIdOf<T>, amount0: BalanceOf<T>, token1: AssetIdOf<T>, amount1: BalanceOf<T>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::_add_liquidity(&sender, token0, token1, amount0, amount1, Zero::zero(), Zero::zero())?;
+			Ok(())
+		}
+
+		/// Create the pair between `token0` and `token1` with the initial liquidity `amount0` and
+		/// `amount1`, pricing swaps with `curve` and charging `fee` basis points of the input
+		/// amount of every swap.
+		#[weight = T::WeightInfo::create_pair()]
+		#[transactional]
+		pub fn create_pair(
+			origin,
+			token0: AssetIdOf<T>,
+			amount0: BalanceOf<T>,
+			token1: AssetIdOf<T>,
+			amount1: BalanceOf<T>,
+			fee: u32,
+			curve: CurveType
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(token0 != token1, Error::<T>::IdenticalIdentifier);
+			ensure!(Self::pair((token0, token1)).is_none(), Error::<T>::PairExists);
+			Self::_create_pair(&sender, token0, amount0, token1, amount1, fee, curve)
+		}
+
+		#[weight = T::WeightInfo::burn_liquidity()]
+		#[transactional]
+		pub fn burn_liquidity(origin, lpt: AssetIdOf<T>, amount: BalanceOf<T>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::_burn_liquidity(&sender, lpt, amount)?;
+			Ok(())
+		}
+
+		#[weight = T::WeightInfo::swap()]
+		#[transactional]
+		pub fn swap(origin, from: AssetIdOf<T>, amount_in: BalanceOf<T>, to: AssetIdOf<T>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(amount_in > Zero::zero(), Error::<T>::InsufficientAmount);
+			let pool = Self::account_id();
+			// transfer amount in to the pool
+			T::Assets::transfer(from, &sender, &pool, amount_in)?;
+			let amount_out = Self::_swap_exact_in(&from, &to, &amount_in)?;
+			// transfer swapped amount
+			T::Assets::transfer(to, &pool, &sender, amount_out)?;
+			Ok(())
+		}
+
+		/// Swap an exact `amount_in` of `path[0]` for as much of the last asset in `path` as
+		/// possible, hopping through the pair of each consecutive assets in `path`.
+		///
+		/// Fails if the output is less than `min_amount_out` or if the current timestamp is past
+		/// `deadline`.
+		///
+		/// # <weight>
+		/// - `O(P)` where `P` is the length of `path`.
+		/// - 2 transfers, `P - 1` reserve updates and events.
+		/// # </weight>
+		#[weight = T::WeightInfo::swap_exact_in_along_path(path.len() as u32)]
+		#[transactional]
+		pub fn swap_exact_in_along_path(
+			origin,
+			path: Vec<AssetIdOf<T>>,
+			amount_in: BalanceOf<T>,
+			min_amount_out: BalanceOf<T>,
+			deadline: T::Moment
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_deadline(deadline)?;
+			ensure!(path.len() >= 2, Error::<T>::InvalidPath);
+			ensure!(amount_in > Zero::zero(), Error::<T>::InsufficientAmount);
+			let pool = Self::account_id();
+			T::Assets::transfer(path[0], &sender, &pool, amount_in)?;
+			// Each intermediate amount stays in the pool account, only the reserves move
+			let mut amount_out = amount_in;
+			for hop in path.windows(2) {
+				amount_out = Self::_swap_exact_in(&hop[0], &hop[1], &amount_out)?;
+			}
+			ensure!(amount_out >= min_amount_out, Error::<T>::InsufficientOutputAmount);
+			T::Assets::transfer(path[path.len() - 1], &pool, &sender, amount_out)?;
+			Ok(())
+		}
+
+		/// Swap as little of `path[0]` as possible for an exact `amount_out` of the last asset in
+		/// `path`, hopping through the pair of each consecutive assets in `path`.
+		///
+		/// The input of every hop is computed backwards from `amount_out`, rounding in favour of
+		/// the pools. Fails if the required input exceeds `max_amount_in` or if the current
+		/// timestamp is past `deadline`.
 		///
 		/// # <weight>
-		/// - `O(1)`
-		/// - 1 storage mutation (codec `O(1)`).
-		/// - 2 storage writes (condec `O(1)`).
-		/// - 1 event.
+		/// - `O(P)` where `P` is the length of `path`.
+		/// - 2 transfers, `P - 1` reserve updates and events.
 		/// # </weight>
-		#[weight = 0]
-		fn issue(origin, #[compact] total: T::Balance) {
-			let origin = ensure_signed(origin)?;
-			// save 0 for native currency
-			let mut id = Self::next_asset_id();
-			if id == Zero::zero() {
-				id += One::one();
+		#[weight = T::WeightInfo::swap_exact_out(path.len() as u32)]
+		#[transactional]
+		pub fn swap_exact_out(
+			origin,
+			path: Vec<AssetIdOf<T>>,
+			amount_out: BalanceOf<T>,
+			max_amount_in: BalanceOf<T>,
+			deadline: T::Moment
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_deadline(deadline)?;
+			ensure!(amount_out > Zero::zero(), Error::<T>::InsufficientOutputAmount);
+			let amounts = Self::get_amounts_in(&amount_out, &path)?;
+			ensure!(amounts[0] <= max_amount_in, Error::<T>::ExcessiveInputAmount);
+			let pool = Self::account_id();
+			T::Assets::transfer(path[0], &sender, &pool, amounts[0])?;
+			for (i, hop) in path.windows(2).enumerate() {
+				Self::_swap(&hop[0], &hop[1], &amounts[i], &amounts[i + 1])?;
 			}
-			<NextAssetId<T>>::mutate(|id| {
-                if *id == Zero::zero() {
-                    *id += One::one();
-                }
-                *id += One::one();
-            });
-
-			<Balances<T>>::insert((id, &origin), total);
-			<TotalSupply<T>>::insert(id, total);
-			<Creator<T>>::insert(id, &origin);
-
-			Self::deposit_event(RawEvent::Issued(id, origin, total));
-		}
-
-		/// Mint any assets of `id` owned by `origin`.
-        ///
-        /// # <weight>
-        /// - `O(1)`
-        /// - 1 storage mutation (codec `O(1)`).
-        /// - 1 storage deletion (codec `O(1)`).
-        /// - 1 event.
-        /// # </weight>
-        #[weight = 0]
-        fn mint(origin,
-             #[compact] id: T::AssetId,
-            target: <T::Lookup as StaticLookup>::Source,
-            #[compact] amount: <T as balances::Trait>::Balance
-        ){
-            let origin = ensure_signed(origin)?;
-            let target = T::Lookup::lookup(target)?;
-            let creator = <Creator<T>>::get(id);
-            ensure!(origin == creator, Error::<T>::NotTheCreator);
-            ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-
-            Self::deposit_event(RawEvent::Minted(id, target.clone(), amount));
-            <Balances<T>>::mutate((id, target), |balance| *balance += amount);
-        }
-
-
-        /// Burn any assets of `id` owned by `origin`.
-        ///
-        /// # <weight>
-        /// - `O(1)`
-        /// - 1 storage mutation (codec `O(1)`).
-        /// - 1 storage deletion (codec `O(1)`).
-        /// - 1 event.
-        /// # </weight>
-        #[weight = 0]
-        fn burn(origin,
-            #[compact] id: T::AssetId,
-           target: <T::Lookup as StaticLookup>::Source,
-           #[compact] amount: <T as balances::Trait>::Balance
-       ){
-           let origin = ensure_signed(origin)?;
-           let origin_account = (id, origin.clone());
-           let origin_balance = <Balances<T>>::get(&origin_account);
-           ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-           ensure!(origin_balance >= amount, Error::<T>::BalanceLow);
-
-           Self::deposit_event(RawEvent::Burned(id, origin, amount));
-           <Balances<T>>::insert(origin_account, origin_balance - amount);
-       }
-
-		/// Move some assets from one holder to another.
+			T::Assets::transfer(path[path.len() - 1], &pool, &sender, amount_out)?;
+			Ok(())
+		}
+
+		/// Borrow `amount_out` of `asset_out` from the pair of `lpt`, dispatch `call` with the
+		/// sender's origin, and then repay `amount_in` of `asset_in`.
+		///
+		/// `asset_out` and `asset_in` must be assets of the pair. Repaying in the borrowed asset is
+		/// a flash loan and repaying in the other asset is a flash swap. Fails with `K` unless the
+		/// invariant of the pair holds after charging the swap fee on the repayment, in which
+		/// case everything, including `call`, is reverted. The pair is locked while `call` runs.
 		///
 		/// # <weight>
-		/// - `O(1)`
-		/// - 1 static lookup
-		/// - 2 storage mutations (codec `O(1)`).
-		/// - 1 event.
+		/// - 2 transfers, 1 reserve update and event.
+		/// - Weight of derivative `call` execution + `WeightInfo::flash_swap`.
 		/// # </weight>
-		#[weight = 0]
-		fn transfer(origin,
-			#[compact] id: T::AssetId,
-			target: <T::Lookup as StaticLookup>::Source,
-			#[compact] amount: T::Balance
-		) {
-			let origin = ensure_signed(origin)?;
-			let origin_account = (id, origin.clone());
-			let origin_balance = <Balances<T>>::get(&origin_account);
-			let target = T::Lookup::lookup(target)?;
-			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-			ensure!(origin_balance >= amount, Error::<T>::BalanceLow);
-
-			Self::deposit_event(RawEvent::Transferred(id, origin, target.clone(), amount));
-			<Balances<T>>::insert(origin_account, origin_balance - amount);
-			<Balances<T>>::mutate((id, target), |balance| *balance += amount);
-		}
-
-		/// Destroy any assets of `id` owned by `origin`.
+		#[weight = (
+			T::WeightInfo::flash_swap().saturating_add(call.get_dispatch_info().weight),
+			call.get_dispatch_info().class,
+		)]
+		#[transactional]
+		pub fn flash_swap(
+			origin,
+			lpt: AssetIdOf<T>,
+			asset_out: AssetIdOf<T>,
+			amount_out: BalanceOf<T>,
+			asset_in: AssetIdOf<T>,
+			amount_in: BalanceOf<T>,
+			call: Box<<T as Trait>::Call>
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(<Rewards<T>>::contains_key(lpt), Error::<T>::InvalidPair);
+			ensure!(!Self::flash_locked(lpt), Error::<T>::Locked);
+			let (token0, token1) = Self::reward(lpt);
+			ensure!(asset_out == token0 || asset_out == token1, Error::<T>::InvalidPair);
+			ensure!(asset_in == token0 || asset_in == token1, Error::<T>::InvalidPair);
+			T::Assets::ensure_transferable(token0)?;
+			T::Assets::ensure_transferable(token1)?;
+			ensure!(amount_out > Zero::zero(), Error::<T>::InsufficientOutputAmount);
+			let reserves = Self::reserves(lpt);
+			// Order the amounts as the reserves
+			let order = |asset: AssetIdOf<T>, amount: BalanceOf<T>| match asset == token0 {
+				true => (amount, Zero::zero()),
+				false => (Zero::zero(), amount),
+			};
+			let amounts_out = order(asset_out, amount_out);
+			let amounts_in = order(asset_in, amount_in);
+			ensure!(amounts_out.0 < reserves.0 && amounts_out.1 < reserves.1, Error::<T>::InsufficientLiquidity);
+
+			// Lend the output while the callback runs
+			let pool = Self::account_id();
+			<FlashLocked<T>>::insert(lpt, true);
+			T::Assets::transfer(asset_out, &pool, &sender, amount_out)?;
+			call.dispatch(frame_system::RawOrigin::Signed(sender.clone()).into()).map_err(|e| e.error)?;
+			<FlashLocked<T>>::remove(lpt);
+			if !amount_in.is_zero() {
+				T::Assets::transfer(asset_in, &sender, &pool, amount_in)?;
+			}
+
+			// The reserves less the swap fee of the repayment must keep the invariant
+			let balances = (
+				(reserves.0 - amounts_out.0).checked_add(&amounts_in.0).ok_or(Error::<T>::Overflow)?,
+				(reserves.1 - amounts_out.1).checked_add(&amounts_in.1).ok_or(Error::<T>::Overflow)?,
+			);
+			let fee = Self::fee(lpt);
+			let charge = |amount: BalanceOf<T>| {
+				let charged = U256::from(amount.saturated_into::<u128>()) * U256::from(fee);
+				let denominator = U256::from(FEE_DENOMINATOR);
+				((charged + denominator - U256::one()) / denominator).as_u128().saturated_into::<BalanceOf<T>>()
+			};
+			let adjusted = (
+				balances.0.saturating_sub(charge(amounts_in.0)),
+				balances.1.saturating_sub(charge(amounts_in.1)),
+			);
+			// Reserves the invariant cannot be solved for do not keep it
+			let k = Self::_k(&lpt).map_err(|_| Error::<T>::K)?;
+			let adjusted_k = Self::curve(lpt).invariant(adjusted).map_err(|_| Error::<T>::K)?;
+			ensure!(adjusted_k >= k, Error::<T>::K);
+			Self::_set_reserves(&token0, &token1, &balances.0, &balances.1, &lpt);
+			Self::deposit_event(RawEvent::FlashSwap(sender, lpt, asset_out, amount_out, asset_in, amount_in));
+			Ok(())
+		}
+
+		/// Set the swap fee of the pair of `lpt`, in basis points of the input amount.
+		///
+		/// The dispatch origin for this call must be `GovernanceOrigin`.
+		#[weight = T::WeightInfo::set_fee()]
+		pub fn set_fee(origin, lpt: AssetIdOf<T>, fee: u32) -> dispatch::DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(<Rewards<T>>::contains_key(lpt), Error::<T>::InvalidPair);
+			ensure!(fee < FEE_DENOMINATOR, Error::<T>::InvalidFee);
+			<Fees<T>>::insert(lpt, fee);
+			Self::deposit_event(RawEvent::FeeChanged(lpt, fee));
+			Ok(())
+		}
+
+		/// Set the account receiving the protocol fee, or turn the protocol fee off with `None`.
+		///
+		/// While it is on, one sixth of the growth of `sqrt(k)` of a pair is minted as liquidity
+		/// provider token to `fee_to` whenever liquidity is minted or burned.
+		///
+		/// The dispatch origin for this call must be `GovernanceOrigin`.
+		#[weight = T::WeightInfo::set_fee_to()]
+		pub fn set_fee_to(origin, fee_to: Option<T::AccountId>) -> dispatch::DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			<FeeTo<T>>::set(fee_to.clone());
+			Self::deposit_event(RawEvent::FeeToChanged(fee_to));
+			Ok(())
+		}
+
+		/// Create a weighted pool of 2 to 8 distinct `assets`, depositing `amounts` of each and
+		/// minting the initial supply of a new liquidity provider token.
+		///
+		/// The value of the pool is split between its assets by their `weights`, normalized by
+		/// their sum, so that weights of 80 and 20 keep 80% of the value in the first asset.
+		/// Swaps with the pool are charged `fee` basis points of the input amount.
 		///
 		/// # <weight>
-		/// - `O(1)`
-		/// - 1 storage mutation (codec `O(1)`).
-		/// - 1 storage deletion (codec `O(1)`).
-		/// - 1 event.
+		/// - `O(A)` where `A` is the number of assets.
+		/// - `A` transfers, 1 issuance and 1 pool write.
 		/// # </weight>
-		#[weight = 0]
-		fn destroy(origin, #[compact] id: T::AssetId) {
-			let origin = ensure_signed(origin)?;
-			let balance = <Balances<T>>::take((id, &origin));
-			ensure!(!balance.is_zero(), Error::<T>::BalanceZero);
-
-			<TotalSupply<T>>::mutate(id, |total_supply| *total_supply -= balance);
-			Self::deposit_event(RawEvent::Destroyed(id, origin, balance));
-		}
-
-
-
-		// Market Module functions
-		// TODO: Separate this functions as separate module and share same primitives
-		
-		
-		// Mint liquidity by adding a liquidity in a pair
-        #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn mint_liquidity(origin, token0: T::AssetId, amount0: <T as balances::Trait>::Balance, token1: T::AssetId, amount1: <T as balances::Trait>::Balance) -> dispatch::DispatchResult {
-            let minimum_liquidity = <T as balances::Trait>::Balance::from(1);
-            let sender = ensure_signed(origin)?;
-            ensure!(token0 != token1, Error::<T>::IdenticalIdentifier);
-            // Burn assets from user to deposit to reserves
-            Module::<T>::transfer_to_system(&token0, &sender, &amount0)?;
-            Module::<T>::transfer_to_system(&token1, &sender, &amount1)?;
-            match Pairs::<T>::get((token0.clone(), token1.clone())) {
-                // create pair if lpt does not exist
-                None => {
-                    let mut lptoken_amount: <T as balances::Trait>::Balance = math::sqrt::<T>(amount0 * amount1);
-                    lptoken_amount = lptoken_amount.checked_sub(&minimum_liquidity).expect("Integer overflow");
-                    // Issue LPtoken
-                    Module::<T>::issue_from_system(Zero::zero())?;
-                    let mut lptoken_id: T::AssetId = NextAssetId::<T>::get();
-                    lptoken_id -= One::one();
-                    // Deposit assets to the reserve
-                    Self::_set_reserves(&token0, &token1, &amount0, &amount1, &lptoken_id);
-                    // Set pairs for swap lookup
-                    Self::_set_pair(&token0, &token1, &lptoken_id);
-                    Self::_set_rewards(&token0, &token1, &lptoken_id);
-                    // Mint LPtoken to the sender
-                    Module::<T>::mint_from_system(&lptoken_id, &sender, &lptoken_amount)?;
-                    Self::deposit_event(RawEvent::CreatePair(token0, token1, lptoken_id));
-                    Ok(())
-                },
-                // when lpt exists and total supply is superset of 0
-                Some(lpt) if Module::<T>::total_supply(lpt) > Zero::zero() => {
-                    let total_supply = Module::<T>::total_supply(lpt);
-                    let mut reserves = Self::reserves(lpt);
-                    let left = amount0.checked_mul(&total_supply).expect("Multiplicaiton overflow").checked_div(&reserves.0).expect("Divide by zero error");
-                    let right = amount1.checked_mul(&total_supply).expect("Multiplicaiton overflow").checked_div(&reserves.1).expect("Divide by zero error");
-                    let lptoken_amount = math::min::<T>(left, right);
-                    // Deposit assets to the reserve
-                    reserves.0 += amount0;
-                    reserves.1 += amount1;
-                    Self::_set_reserves(&token0, &token1, &reserves.0, &reserves.1, &lpt);
-                    // Mint LPtoken to the sender
-                    Module::<T>::mint_from_system(&lpt, &sender, &lptoken_amount)?;
-                    Self::deposit_event(RawEvent::MintedLiquidity(token0, token1, lpt));
-                    //Self::_update(&lpt)?;
-                    Ok(())
-                },
-                Some(lpt) if Module::<T>::total_supply(lpt) < <T as balances::Trait>::Balance::from(0) => {
-                    Err(Error::<T>::InsufficientLiquidityMinted)?
-                },
-                Some(_) => Err(Error::<T>::NoneValue)?,
+		#[weight = T::WeightInfo::create_weighted_pool(assets.len() as u32)]
+		#[transactional]
+		pub fn create_weighted_pool(
+			origin,
+			assets: Vec<AssetIdOf<T>>,
+			amounts: Vec<BalanceOf<T>>,
+			weights: Vec<u32>,
+			fee: u32
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(
+				assets.len() >= weighted::MIN_ASSETS && assets.len() <= weighted::MAX_ASSETS,
+				Error::<T>::InvalidPoolSize
+			);
+			ensure!(amounts.len() == assets.len() && weights.len() == assets.len(), Error::<T>::InvalidPoolSize);
+			for (i, asset) in assets.iter().enumerate() {
+				ensure!(!assets[..i].contains(asset), Error::<T>::IdenticalIdentifier);
+			}
+			ensure!(weighted::valid_weights(&weights), Error::<T>::InvalidWeight);
+			ensure!(fee < FEE_DENOMINATOR, Error::<T>::InvalidFee);
+			ensure!(amounts.iter().all(|amount| !amount.is_zero()), Error::<T>::InsufficientAmount);
+			// Deposit assets from user to the pool account
+			let pool = Self::account_id();
+			for (asset, amount) in assets.iter().zip(amounts.iter()) {
+				T::Assets::transfer(*asset, &sender, &pool, *amount)?;
 			}
+			// Issue LPtoken and mint the initial supply to the sender
+			let lptoken_id = T::Assets::issue_from_system(Zero::zero())?;
+			Self::_set_lp_metadata(lptoken_id, &assets)?;
+			T::Assets::mint_into(lptoken_id, &sender, weighted::INITIAL_POOL_SUPPLY.saturated_into())?;
+			<WeightedPools<T>>::insert(lptoken_id, WeightedPool { assets, weights, balances: amounts, fee });
+			Self::deposit_event(RawEvent::CreateWeightedPool(lptoken_id));
+			Ok(())
 		}
-		
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn burn_liquidity(origin, lpt: T::AssetId, amount: <T as balances::Trait>::Balance) -> dispatch::DispatchResult{
-            let sender = ensure_signed(origin)?;
-            let mut reserves = Self::reserves(lpt);
-            let tokens = Self::reward(lpt);
-            let total_supply = Module::<T>::total_supply(lpt);
-
-            // Calculate rewards for providing liquidity with pro-rata distribution
-            let reward0 = amount.checked_mul(&reserves.0).expect("Multiplicaiton overflow").checked_div(&total_supply).expect("Divide by zero error");
-            let reward1 = amount.checked_mul(&reserves.1).expect("Multiplicaiton overflow").checked_div(&total_supply).expect("Divide by zero error");
-
-            // Ensure rewards exist
-            ensure!(reward0 > Zero::zero() && reward1 > Zero::zero(), Error::<T>::InsufficientLiquidityBurned);
-
-            // Distribute reward to the sender
-            Module::<T>::burn_from_system(&lpt, &sender, &amount)?;
-            Module::<T>::transfer_from_system(&tokens.0, &sender, &reward0)?;
-            Module::<T>::transfer_from_system(&tokens.1, &sender, &reward1)?;
-
-            // Update reserve when the balance is set
-            reserves.0 -= reward0;
-            reserves.1 -= reward1;
-            Self::_set_reserves(&tokens.0, &tokens.1, &reserves.0, &reserves.1, &lpt);
-            // Deposit event that the liquidity is burned successfully
-            Self::deposit_event(RawEvent::BurnedLiquidity(lpt, tokens.0, tokens.1));
-            // Update price
-            //Self::_update(&lpt)?;
-            Ok(())
-		}
-		
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn swap(origin, from: T::AssetId, amount_in: <T as balances::Trait>::Balance, to: T::AssetId) -> dispatch::DispatchResult {
-            let sender = ensure_signed(origin)?;
-            ensure!(amount_in > Zero::zero(), Error::<T>::InsufficientAmount);
-            // Find pair
-            let lpt = Self::pair((from, to));
-            ensure!(lpt.is_some(), Error::<T>::InvalidPair);
-            let reserves = Self::reserves(lpt.unwrap());
-            ensure!(reserves.0 > Zero::zero() && reserves.1 > Zero::zero(), Error::<T>::InsufficientLiquidity);
-            let (mut reserve_in, mut reserve_out) = match from > to {
-                true => (reserves.1, reserves.0),
-                false => (reserves.0, reserves.1)
-            };
-            // get amount out
-            let amount_out = Self::_get_amount_out(&amount_in, &reserve_in, &reserve_out);
-            // transfer amount in to system
-            Module::<T>::transfer_to_system(&from, &sender, &amount_in)?;
-            // transfer swapped amount
-            Module::<T>::transfer_from_system(&to, &sender, &amount_out)?;
-            // update reserves
-            reserve_in += amount_in;
-            reserve_out -= amount_out;
-            Self::_set_reserves(&from, &to, &reserve_in, &reserve_out, &lpt.unwrap());
-            // Deposit event that the liquidity is burned successfully
-            Self::deposit_event(RawEvent::Swap(from, amount_in, to, amount_out));
-            // Update price
-            //Self::_update(&lpt.unwrap())?;
-            Ok(())
-        }
 
+		/// Deposit `amount_in` of a single asset of the weighted pool of `lpt` for at least
+		/// `min_pool_amount_out` of its liquidity provider token.
+		///
+		/// The part of the deposit the pool would swap to its other assets pays the swap fee.
+		#[weight = T::WeightInfo::join_pool_single()]
+		#[transactional]
+		pub fn join_pool_single(
+			origin,
+			lpt: AssetIdOf<T>,
+			asset_in: AssetIdOf<T>,
+			amount_in: BalanceOf<T>,
+			min_pool_amount_out: BalanceOf<T>
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(amount_in > Zero::zero(), Error::<T>::InsufficientAmount);
+			let mut pool = Self::weighted_pool(lpt).ok_or(Error::<T>::InvalidPool)?;
+			let i = pool.index_of(&asset_in).ok_or(Error::<T>::InvalidPool)?;
+			let pool_amount_out = weighted::pool_out_given_single_in(
+				amount_in,
+				pool.balances[i],
+				pool.weights[i],
+				pool.total_weight(),
+				T::Assets::total_issuance(lpt),
+				pool.fee,
+			).ok_or(Error::<T>::InsufficientLiquidity)?;
+			ensure!(pool_amount_out > Zero::zero(), Error::<T>::InsufficientLiquidityMinted);
+			ensure!(pool_amount_out >= min_pool_amount_out, Error::<T>::InsufficientOutputAmount);
+			T::Assets::transfer(asset_in, &sender, &Self::account_id(), amount_in)?;
+			pool.balances[i] += amount_in;
+			<WeightedPools<T>>::insert(lpt, pool);
+			T::Assets::mint_into(lpt, &sender, pool_amount_out)?;
+			Self::deposit_event(RawEvent::JoinedPool(lpt, asset_in, amount_in, pool_amount_out));
+			Ok(())
+		}
+
+		/// Burn `pool_amount_in` of the liquidity provider token of the weighted pool of `lpt` for
+		/// at least `min_amount_out` of a single asset of the pool.
+		///
+		/// The part of the withdrawal the pool would swap from its other assets pays the swap fee.
+		#[weight = T::WeightInfo::exit_pool_single()]
+		#[transactional]
+		pub fn exit_pool_single(
+			origin,
+			lpt: AssetIdOf<T>,
+			pool_amount_in: BalanceOf<T>,
+			asset_out: AssetIdOf<T>,
+			min_amount_out: BalanceOf<T>
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut pool = Self::weighted_pool(lpt).ok_or(Error::<T>::InvalidPool)?;
+			let i = pool.index_of(&asset_out).ok_or(Error::<T>::InvalidPool)?;
+			let amount_out = weighted::single_out_given_pool_in(
+				pool_amount_in,
+				pool.balances[i],
+				pool.weights[i],
+				pool.total_weight(),
+				T::Assets::total_issuance(lpt),
+				pool.fee,
+			).ok_or(Error::<T>::InsufficientLiquidity)?;
+			ensure!(amount_out > Zero::zero(), Error::<T>::InsufficientLiquidityBurned);
+			ensure!(amount_out >= min_amount_out, Error::<T>::InsufficientOutputAmount);
+			T::Assets::burn_from(lpt, &sender, pool_amount_in)?;
+			T::Assets::transfer(asset_out, &Self::account_id(), &sender, amount_out)?;
+			pool.balances[i] -= amount_out;
+			<WeightedPools<T>>::insert(lpt, pool);
+			Self::deposit_event(RawEvent::ExitedPool(lpt, asset_out, amount_out, pool_amount_in));
+			Ok(())
+		}
+
+		/// Burn `pool_amount_in` of the liquidity provider token of the weighted pool of `lpt` for
+		/// the pro-rata share of every asset of the pool.
+		///
+		/// # <weight>
+		/// - `O(A)` where `A` is the number of assets.
+		/// - `A` transfers, 1 burn and 1 pool write.
+		/// # </weight>
+		#[weight = T::WeightInfo::exit_pool(weighted::MAX_ASSETS as u32)]
+		#[transactional]
+		pub fn exit_pool(origin, lpt: AssetIdOf<T>, pool_amount_in: BalanceOf<T>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut pool = Self::weighted_pool(lpt).ok_or(Error::<T>::InvalidPool)?;
+			let total_supply = T::Assets::total_issuance(lpt);
+			ensure!(!pool_amount_in.is_zero() && pool_amount_in <= total_supply, Error::<T>::InsufficientLiquidityBurned);
+			T::Assets::burn_from(lpt, &sender, pool_amount_in)?;
+			let account = Self::account_id();
+			for i in 0..pool.assets.len() {
+				let amount_out = math::mul_div(pool.balances[i], pool_amount_in, total_supply)
+					.map_err(Error::<T>::from)?;
+				T::Assets::transfer(pool.assets[i], &account, &sender, amount_out)?;
+				pool.balances[i] -= amount_out;
+				Self::deposit_event(RawEvent::ExitedPool(lpt, pool.assets[i], amount_out, pool_amount_in));
+			}
+			<WeightedPools<T>>::insert(lpt, pool);
+			Ok(())
+		}
+
+		/// Swap an exact `amount_in` of `asset_in` for at least `min_amount_out` of `asset_out`
+		/// with the weighted pool of `lpt`.
+		#[weight = T::WeightInfo::swap_weighted()]
+		#[transactional]
+		pub fn swap_weighted(
+			origin,
+			lpt: AssetIdOf<T>,
+			asset_in: AssetIdOf<T>,
+			amount_in: BalanceOf<T>,
+			asset_out: AssetIdOf<T>,
+			min_amount_out: BalanceOf<T>
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(amount_in > Zero::zero(), Error::<T>::InsufficientAmount);
+			ensure!(asset_in != asset_out, Error::<T>::IdenticalIdentifier);
+			let mut pool = Self::weighted_pool(lpt).ok_or(Error::<T>::InvalidPool)?;
+			let i = pool.index_of(&asset_in).ok_or(Error::<T>::InvalidPool)?;
+			let o = pool.index_of(&asset_out).ok_or(Error::<T>::InvalidPool)?;
+			let amount_out = weighted::amount_out(
+				amount_in,
+				pool.balances[i],
+				pool.weights[i],
+				pool.balances[o],
+				pool.weights[o],
+				pool.fee,
+			).ok_or(Error::<T>::InsufficientLiquidity)?;
+			ensure!(amount_out > Zero::zero(), Error::<T>::InsufficientOutputAmount);
+			ensure!(amount_out >= min_amount_out, Error::<T>::InsufficientOutputAmount);
+			let account = Self::account_id();
+			T::Assets::transfer(asset_in, &sender, &account, amount_in)?;
+			T::Assets::transfer(asset_out, &account, &sender, amount_out)?;
+			pool.balances[i] += amount_in;
+			pool.balances[o] -= amount_out;
+			<WeightedPools<T>>::insert(lpt, pool);
+			Self::deposit_event(RawEvent::Swap(asset_in, amount_in, asset_out, amount_out));
+			Ok(())
+		}
+
+		/// Create a concentrated liquidity pool between `token0` and `token1` at the square root
+		/// price `sqrt_price` in Q64.96, charging `fee` basis points of the input amount of every
+		/// swap.
+		///
+		/// The price is of the asset with the lower identifier in units of the other one.
+		/// Positions of the pool may only start and end at multiples of `tick_spacing`.
+		#[weight = T::WeightInfo::create_concentrated_pool()]
+		pub fn create_concentrated_pool(
+			origin,
+			token0: AssetIdOf<T>,
+			token1: AssetIdOf<T>,
+			fee: u32,
+			tick_spacing: u32,
+			sqrt_price: U256
+		) -> dispatch::DispatchResult {
+			ensure_signed(origin)?;
+			ensure!(token0 != token1, Error::<T>::IdenticalIdentifier);
+			ensure!(fee < FEE_DENOMINATOR, Error::<T>::InvalidFee);
+			ensure!(tick_spacing > 0 && tick_spacing <= concentrated::MAX_TICK_SPACING, Error::<T>::InvalidTickSpacing);
+			let tick = concentrated::tick_at_sqrt_ratio(sqrt_price).ok_or(Error::<T>::InvalidPrice)?;
+			let (token0, token1) = if token0 > token1 { (token1, token0) } else { (token0, token1) };
+			let pool_id = Self::next_pool_id();
+			<NextPoolId>::put(pool_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+			<ConcentratedPools<T>>::insert(pool_id, ConcentratedPool {
+				token0,
+				token1,
+				fee,
+				tick_spacing: tick_spacing as i32,
+				sqrt_price,
+				tick,
+				..Default::default()
+			});
+			Self::deposit_event(RawEvent::CreateConcentratedPool(pool_id, token0, token1));
+			Ok(())
+		}
+
+		/// Provide `liquidity` to the price range between `tick_lower` and `tick_upper` of the
+		/// concentrated liquidity pool `pool_id`, depositing at most `amount0_max` of `token0` and
+		/// `amount1_max` of `token1`.
+		///
+		/// The liquidity is recorded as a new position owned by the sender. Fails if it would
+		/// initialize more than `MaxInitializedTicks` ticks of the pool.
+		#[weight = T::WeightInfo::mint_position()]
+		#[transactional]
+		pub fn mint_position(
+			origin,
+			pool_id: PoolId,
+			tick_lower: i32,
+			tick_upper: i32,
+			liquidity: u128,
+			amount0_max: BalanceOf<T>,
+			amount1_max: BalanceOf<T>
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let pool = Self::concentrated_pool(pool_id).ok_or(Error::<T>::InvalidPool)?;
+			ensure!(
+				tick_lower < tick_upper
+					&& tick_lower >= concentrated::MIN_TICK
+					&& tick_upper <= concentrated::MAX_TICK
+					&& tick_lower % pool.tick_spacing == 0
+					&& tick_upper % pool.tick_spacing == 0,
+				Error::<T>::InvalidTick
+			);
+			let delta = i128::try_from(liquidity).map_err(|_| Error::<T>::Overflow)?;
+			ensure!(delta > 0, Error::<T>::InsufficientLiquidityMinted);
+			let mut position = Position {
+				owner: sender.clone(),
+				pool_id,
+				tick_lower,
+				tick_upper,
+				..Default::default()
+			};
+			let (amount0, amount1) = Self::_modify_position(&mut position, delta)?;
+			ensure!(amount0 <= amount0_max && amount1 <= amount1_max, Error::<T>::ExcessiveInputAmount);
+			// Deposit assets from user to the pool account
+			let account = Self::account_id();
+			if !amount0.is_zero() {
+				T::Assets::transfer(pool.token0, &sender, &account, amount0)?;
+			}
+			if !amount1.is_zero() {
+				T::Assets::transfer(pool.token1, &sender, &account, amount1)?;
+			}
+			let position_id = Self::next_position_id();
+			<NextPositionId>::put(position_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+			<Positions<T>>::insert(position_id, position);
+			<PositionsByOwner<T>>::insert(&sender, position_id, ());
+			Self::deposit_event(RawEvent::MintedPosition(position_id, sender, pool_id, amount0, amount1));
+			Ok(())
+		}
+
+		/// Withdraw `liquidity` from the position `position_id`, for at least `amount0_min` of
+		/// `token0` and `amount1_min` of `token1`.
+		///
+		/// The withdrawn assets are owed to the position until they are collected.
+		#[weight = T::WeightInfo::decrease_liquidity()]
+		#[transactional]
+		pub fn decrease_liquidity(
+			origin,
+			position_id: PositionId,
+			liquidity: u128,
+			amount0_min: BalanceOf<T>,
+			amount1_min: BalanceOf<T>
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut position = Self::position(position_id).ok_or(Error::<T>::InvalidPosition)?;
+			ensure!(position.owner == sender, Error::<T>::NotPositionOwner);
+			ensure!(liquidity > 0 && liquidity <= position.liquidity, Error::<T>::InsufficientLiquidityBurned);
+			let delta = i128::try_from(liquidity).map_err(|_| Error::<T>::Overflow)?;
+			let (amount0, amount1) = Self::_modify_position(&mut position, -delta)?;
+			ensure!(amount0 >= amount0_min && amount1 >= amount1_min, Error::<T>::InsufficientOutputAmount);
+			position.tokens_owed0 = position.tokens_owed0.saturating_add(amount0);
+			position.tokens_owed1 = position.tokens_owed1.saturating_add(amount1);
+			<Positions<T>>::insert(position_id, position);
+			Self::deposit_event(RawEvent::DecreasedLiquidity(position_id, liquidity, amount0, amount1));
+			Ok(())
+		}
+
+		/// Collect the fees earned and the liquidity withdrawn by the position `position_id`.
+		#[weight = T::WeightInfo::collect()]
+		#[transactional]
+		pub fn collect(origin, position_id: PositionId) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut position = Self::position(position_id).ok_or(Error::<T>::InvalidPosition)?;
+			ensure!(position.owner == sender, Error::<T>::NotPositionOwner);
+			let pool = Self::concentrated_pool(position.pool_id).ok_or(Error::<T>::InvalidPool)?;
+			if position.liquidity > 0 {
+				// Accrue the fees earned since the last update of the position
+				Self::_modify_position(&mut position, 0)?;
+			}
+			let (amount0, amount1) = (position.tokens_owed0, position.tokens_owed1);
+			let account = Self::account_id();
+			if !amount0.is_zero() {
+				T::Assets::transfer(pool.token0, &account, &sender, amount0)?;
+			}
+			if !amount1.is_zero() {
+				T::Assets::transfer(pool.token1, &account, &sender, amount1)?;
+			}
+			position.tokens_owed0 = Zero::zero();
+			position.tokens_owed1 = Zero::zero();
+			<Positions<T>>::insert(position_id, position);
+			Self::deposit_event(RawEvent::CollectedPosition(position_id, amount0, amount1));
+			Ok(())
+		}
+
+		/// Remove the record of the position `position_id` once all of its liquidity is
+		/// withdrawn and collected.
+		#[weight = T::WeightInfo::burn_position()]
+		pub fn burn_position(origin, position_id: PositionId) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let position = Self::position(position_id).ok_or(Error::<T>::InvalidPosition)?;
+			ensure!(position.owner == sender, Error::<T>::NotPositionOwner);
+			ensure!(
+				position.liquidity == 0 && position.tokens_owed0.is_zero() && position.tokens_owed1.is_zero(),
+				Error::<T>::PositionNotEmpty
+			);
+			<Positions<T>>::remove(position_id);
+			<PositionsByOwner<T>>::remove(&sender, position_id);
+			Self::deposit_event(RawEvent::BurnedPosition(position_id));
+			Ok(())
+		}
+
+		/// Transfer the position `position_id`, with its liquidity and the assets owed to it, to
+		/// `dest`.
+		#[weight = T::WeightInfo::transfer_position()]
+		pub fn transfer_position(
+			origin,
+			position_id: PositionId,
+			dest: <T::Lookup as StaticLookup>::Source
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+			let mut position = Self::position(position_id).ok_or(Error::<T>::InvalidPosition)?;
+			ensure!(position.owner == sender, Error::<T>::NotPositionOwner);
+			position.owner = dest.clone();
+			<Positions<T>>::insert(position_id, position);
+			<PositionsByOwner<T>>::remove(&sender, position_id);
+			<PositionsByOwner<T>>::insert(&dest, position_id, ());
+			Self::deposit_event(RawEvent::TransferredPosition(position_id, sender, dest));
+			Ok(())
+		}
+
+		/// Swap an exact `amount_in` of `asset_in` for at least `min_amount_out` of the other asset
+		/// of the concentrated liquidity pool `pool_id`.
+		///
+		/// The swap stops early if the square root price reaches `sqrt_price_limit`, in which case
+		/// only the input swapped so far is taken. Fails if it would cross more than
+		/// `max_ticks_crossed` initialized ticks, which the weight is charged for.
+		///
+		/// # <weight>
+		/// - `O(T + C)` where `T` is the number of initialized ticks of the pool, at most
+		///   `MaxInitializedTicks`, and `C` is `max_ticks_crossed`.
+		/// - 2 transfers and a tick write for every tick crossed.
+		/// # </weight>
+		#[weight = T::WeightInfo::swap_concentrated(*max_ticks_crossed)]
+		#[transactional]
+		pub fn swap_concentrated(
+			origin,
+			pool_id: PoolId,
+			asset_in: AssetIdOf<T>,
+			amount_in: BalanceOf<T>,
+			min_amount_out: BalanceOf<T>,
+			sqrt_price_limit: Option<U256>,
+			max_ticks_crossed: u32
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(amount_in > Zero::zero(), Error::<T>::InsufficientAmount);
+			let mut pool = Self::concentrated_pool(pool_id).ok_or(Error::<T>::InvalidPool)?;
+			let (zero_for_one, asset_out) = match asset_in {
+				asset if asset == pool.token0 => (true, pool.token1),
+				asset if asset == pool.token1 => (false, pool.token0),
+				_ => Err(Error::<T>::InvalidPool)?,
+			};
+			let (amount_in, amount_out) = Self::_swap_concentrated(
+				pool_id, &mut pool, zero_for_one, amount_in, sqrt_price_limit, max_ticks_crossed,
+			)?;
+			ensure!(amount_out > Zero::zero() && amount_out >= min_amount_out, Error::<T>::InsufficientOutputAmount);
+			let account = Self::account_id();
+			T::Assets::transfer(asset_in, &sender, &account, amount_in)?;
+			T::Assets::transfer(asset_out, &account, &sender, amount_out)?;
+			<ConcentratedPools<T>>::insert(pool_id, pool);
+			Self::deposit_event(RawEvent::Swap(asset_in, amount_in, asset_out, amount_out));
+			Ok(())
+		}
+
+		/// Set the native currency distributed to the stakers of every mining pool per block.
+		///
+		/// The dispatch origin for this call must be `GovernanceOrigin`.
+		///
+		/// # <weight>
+		/// - `O(M)` where `M` is the number of mining pools, which are updated to the current block.
+		/// # </weight>
+		#[weight = T::WeightInfo::set_reward_per_block()]
+		pub fn set_reward_per_block(origin, reward: BalanceOf<T>) -> dispatch::DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			Self::_update_mining_pools()?;
+			<RewardPerBlock<T>>::put(reward);
+			Self::deposit_event(RawEvent::RewardPerBlockChanged(reward));
+			Ok(())
+		}
+
+		/// Create the mining pool of the liquidity provider token `lpt`, or change its share of the
+		/// reward per block to `alloc_point` out of the total allocation points.
+		///
+		/// The dispatch origin for this call must be `GovernanceOrigin`.
+		///
+		/// # <weight>
+		/// - `O(M)` where `M` is the number of mining pools, which are updated to the current block.
+		/// # </weight>
+		#[weight = T::WeightInfo::set_mining_pool()]
+		pub fn set_mining_pool(origin, lpt: AssetIdOf<T>, alloc_point: u32) -> dispatch::DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(
+				<Rewards<T>>::contains_key(lpt) || <WeightedPools<T>>::contains_key(lpt),
+				Error::<T>::InvalidPool
+			);
+			Self::_update_mining_pools()?;
+			let mut pool = Self::mining_pool(lpt).unwrap_or_else(|| MiningPool {
+				last_reward_block: <frame_system::Module<T>>::block_number(),
+				..Default::default()
+			});
+			let total_alloc_point = Self::total_alloc_point()
+				.checked_sub(pool.alloc_point)
+				.and_then(|total| total.checked_add(alloc_point))
+				.ok_or(Error::<T>::Overflow)?;
+			<TotalAllocPoint>::put(total_alloc_point);
+			pool.alloc_point = alloc_point;
+			<MiningPools<T>>::insert(lpt, pool);
+			Self::deposit_event(RawEvent::MiningPoolSet(lpt, alloc_point));
+			Ok(())
+		}
+
+		/// Stake `amount` of the liquidity provider token `lpt` in its mining pool, claiming the
+		/// reward of the tokens already staked.
+		#[weight = T::WeightInfo::stake_lp()]
+		#[transactional]
+		pub fn stake_lp(origin, lpt: AssetIdOf<T>, amount: BalanceOf<T>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(amount > Zero::zero(), Error::<T>::InsufficientAmount);
+			let mut pool = Self::_update_mining_pool(&lpt)?;
+			let mut stake = Self::stake(lpt, &sender);
+			Self::_claim(&sender, &lpt, &pool, &stake)?;
+			T::Assets::transfer(lpt, &sender, &Self::account_id(), amount)?;
+			stake.amount += amount;
+			stake.reward_debt = Self::_accumulated_reward(&pool, stake.amount);
+			pool.total_staked += amount;
+			<Stakes<T>>::insert(lpt, &sender, stake);
+			<MiningPools<T>>::insert(lpt, pool);
+			Self::deposit_event(RawEvent::Staked(sender, lpt, amount));
+			Ok(())
+		}
+
+		/// Unstake `amount` of the liquidity provider token `lpt` from its mining pool, claiming the
+		/// reward of the tokens staked.
+		#[weight = T::WeightInfo::unstake_lp()]
+		#[transactional]
+		pub fn unstake_lp(origin, lpt: AssetIdOf<T>, amount: BalanceOf<T>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut pool = Self::_update_mining_pool(&lpt)?;
+			let mut stake = Self::stake(lpt, &sender);
+			ensure!(amount > Zero::zero() && amount <= stake.amount, Error::<T>::InsufficientStake);
+			Self::_claim(&sender, &lpt, &pool, &stake)?;
+			T::Assets::transfer(lpt, &Self::account_id(), &sender, amount)?;
+			stake.amount -= amount;
+			stake.reward_debt = Self::_accumulated_reward(&pool, stake.amount);
+			pool.total_staked -= amount;
+			if stake.amount.is_zero() {
+				<Stakes<T>>::remove(lpt, &sender);
+			} else {
+				<Stakes<T>>::insert(lpt, &sender, stake);
+			}
+			<MiningPools<T>>::insert(lpt, pool);
+			Self::deposit_event(RawEvent::Unstaked(sender, lpt, amount));
+			Ok(())
+		}
+
+		/// Claim the reward of the liquidity provider token `lpt` staked by the sender.
+		#[weight = T::WeightInfo::claim_rewards()]
+		#[transactional]
+		pub fn claim_rewards(origin, lpt: AssetIdOf<T>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let pool = Self::_update_mining_pool(&lpt)?;
+			let mut stake = Self::stake(lpt, &sender);
+			ensure!(stake.amount > Zero::zero(), Error::<T>::InsufficientStake);
+			Self::_claim(&sender, &lpt, &pool, &stake)?;
+			stake.reward_debt = Self::_accumulated_reward(&pool, stake.amount);
+			<Stakes<T>>::insert(lpt, &sender, stake);
+			<MiningPools<T>>::insert(lpt, pool);
+			Ok(())
+		}
+
+		/// Place an order selling `amount_in` of `asset_in` for at least `min_amount_out` of
+		/// `asset_out` through their pair, at the limit price of `min_amount_out / amount_in`.
+		///
+		/// The input is held by the system until the order is filled, cancelled or expired after
+		/// the block `expiry`. Orders are filled at the beginning of a block, partially if the pair
+		/// only meets the limit price for a part of the remaining input.
+		#[weight = T::WeightInfo::place_limit_order()]
+		#[transactional]
+		pub fn place_limit_order(
+			origin,
+			asset_in: AssetIdOf<T>,
+			amount_in: BalanceOf<T>,
+			asset_out: AssetIdOf<T>,
+			min_amount_out: BalanceOf<T>,
+			expiry: T::BlockNumber
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(asset_in != asset_out, Error::<T>::IdenticalIdentifier);
+			ensure!(Self::pair((asset_in, asset_out)).is_some(), Error::<T>::InvalidPair);
+			ensure!(amount_in > Zero::zero(), Error::<T>::InsufficientAmount);
+			ensure!(min_amount_out > Zero::zero(), Error::<T>::InsufficientOutputAmount);
+			ensure!(expiry >= <frame_system::Module<T>>::block_number(), Error::<T>::Expired);
+			let mut open = Self::open_orders();
+			ensure!((open.len() as u32) < T::MaxOpenOrders::get(), Error::<T>::TooManyOrders);
+			let count = Self::open_order_count(&sender);
+			ensure!(count < T::MaxOrdersPerAccount::get(), Error::<T>::TooManyOrders);
+
+			T::Assets::transfer_to_system(asset_in, &sender, amount_in)?;
+			let id = Self::next_order_id();
+			<NextOrderId>::put(id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+			<Orders<T>>::insert(id, LimitOrder {
+				owner: sender.clone(),
+				asset_in,
+				asset_out,
+				amount_in,
+				min_amount_out,
+				remaining: amount_in,
+				filled_out: Zero::zero(),
+				expiry,
+			});
+			open.push(id);
+			<OpenOrders>::put(open);
+			<OpenOrderCount<T>>::insert(&sender, count + 1);
+			Self::deposit_event(RawEvent::OrderPlaced(id, sender, asset_in, amount_in, asset_out, min_amount_out));
+			Ok(())
+		}
+
+		/// Cancel an open limit order of the sender, returning the input not sold yet.
+		#[weight = T::WeightInfo::cancel_order()]
+		#[transactional]
+		pub fn cancel_order(origin, order_id: OrderId) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let order = Self::order(order_id).ok_or(Error::<T>::InvalidOrder)?;
+			ensure!(order.owner == sender, Error::<T>::NotOrderOwner);
+			Self::_close_order(order_id, &order)?;
+			<OpenOrders>::mutate(|open| open.retain(|id| *id != order_id));
+			Self::deposit_event(RawEvent::OrderCancelled(order_id, order.remaining));
+			Ok(())
+		}
+
+		/// Deposit `token_a` and `token_b` to their pair in the ratio of its reserves, minting
+		/// liquidity provider token to the sender.
+		///
+		/// All of `desired_a` is deposited if `desired_b` covers its value at the current price,
+		/// otherwise all of `desired_b`, and the rest stays with the sender. The pair is created
+		/// with the desired amounts if it does not exist yet, and pairs on other curves than the
+		/// constant product take the desired amounts as they are.
+		///
+		/// Fails if less than `min_a` of `token_a` or `min_b` of `token_b` would be deposited, or
+		/// if the current timestamp is past `deadline`.
+		#[weight = T::WeightInfo::add_liquidity()]
+		#[transactional]
+		pub fn add_liquidity(
+			origin,
+			token_a: AssetIdOf<T>,
+			token_b: AssetIdOf<T>,
+			desired_a: BalanceOf<T>,
+			desired_b: BalanceOf<T>,
+			min_a: BalanceOf<T>,
+			min_b: BalanceOf<T>,
+			deadline: T::Moment
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_deadline(deadline)?;
+			Self::_add_liquidity(&sender, token_a, token_b, desired_a, desired_b, min_a, min_b)?;
+			Ok(())
+		}
+
+		/// Burn `liquidity` of the liquidity provider token of the pair between `token_a` and
+		/// `token_b` for its share of the reserves of the pair.
+		///
+		/// Fails if the sender would receive less than `min_a` of `token_a` or `min_b` of
+		/// `token_b`, or if the current timestamp is past `deadline`.
+		#[weight = T::WeightInfo::remove_liquidity()]
+		#[transactional]
+		pub fn remove_liquidity(
+			origin,
+			token_a: AssetIdOf<T>,
+			token_b: AssetIdOf<T>,
+			liquidity: BalanceOf<T>,
+			min_a: BalanceOf<T>,
+			min_b: BalanceOf<T>,
+			deadline: T::Moment
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_deadline(deadline)?;
+			let lpt = Self::pair((token_a, token_b)).ok_or(Error::<T>::InvalidPair)?;
+			let amounts = Self::_burn_liquidity(&sender, lpt, liquidity)?;
+			let (amount_a, amount_b) = match token_a > token_b {
+				true => (amounts.1, amounts.0),
+				false => amounts,
+			};
+			ensure!(amount_a >= min_a, Error::<T>::InsufficientAmountA);
+			ensure!(amount_b >= min_b, Error::<T>::InsufficientAmountB);
+			Ok(())
+		}
+
+		/// Provide liquidity to the constant product pair of `lpt` with `amount` of a single
+		/// `asset` of the pair.
+		///
+		/// The part of `amount` whose output keeps the rest in the ratio of the reserves is
+		/// swapped to the other asset of the pair, after the swap fee, and both are deposited to
+		/// the pair. Any rounding dust stays with the sender. Fails if less than `min_liquidity`
+		/// of liquidity provider token would be minted.
+		#[weight = T::WeightInfo::zap_in()]
+		#[transactional]
+		pub fn zap_in(
+			origin,
+			lpt: AssetIdOf<T>,
+			asset: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+			min_liquidity: BalanceOf<T>
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(amount > Zero::zero(), Error::<T>::InsufficientAmount);
+			let other = Self::_paired_asset(&lpt, &asset)?;
+			ensure!(Self::curve(lpt) == CurveType::ConstantProduct, Error::<T>::InvalidCurve);
+			let (_, reserve_in, _) = Self::get_reserves(&asset, &other)?;
+			let swap_amount = math::optimal_swap_amount(amount, reserve_in, Self::fee(lpt))
+				.map_err(Error::<T>::from)?;
+
+			let pool = Self::account_id();
+			T::Assets::transfer(asset, &sender, &pool, swap_amount)?;
+			let amount_out = Self::_swap_exact_in(&asset, &other, &swap_amount)?;
+			T::Assets::transfer(other, &pool, &sender, amount_out)?;
+
+			let before = T::Assets::balance(lpt, &sender);
+			let amount_rest = amount - swap_amount;
+			Self::_add_liquidity(&sender, asset, other, amount_rest, amount_out, Zero::zero(), Zero::zero())?;
+			let liquidity = T::Assets::balance(lpt, &sender).saturating_sub(before);
+			ensure!(liquidity >= min_liquidity, Error::<T>::InsufficientLiquidityMinted);
+			Self::deposit_event(RawEvent::ZappedIn(sender, lpt, asset, amount, liquidity));
+			Ok(())
+		}
+
+		/// Burn `liquidity` of the liquidity provider token `lpt` and swap the redeemed other
+		/// asset of the pair to `asset`, so that the sender receives only `asset`.
+		///
+		/// Fails if the sender would receive less than `min_amount_out` of `asset` in total.
+		#[weight = T::WeightInfo::zap_out()]
+		#[transactional]
+		pub fn zap_out(
+			origin,
+			lpt: AssetIdOf<T>,
+			liquidity: BalanceOf<T>,
+			asset: AssetIdOf<T>,
+			min_amount_out: BalanceOf<T>
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let other = Self::_paired_asset(&lpt, &asset)?;
+			let amounts = Self::_burn_liquidity(&sender, lpt, liquidity)?;
+			let (amount, amount_other) = match asset > other {
+				true => (amounts.1, amounts.0),
+				false => amounts,
+			};
+
+			let pool = Self::account_id();
+			T::Assets::transfer(other, &sender, &pool, amount_other)?;
+			let amount_out = Self::_swap_exact_in(&other, &asset, &amount_other)?;
+			T::Assets::transfer(asset, &pool, &sender, amount_out)?;
+
+			let total = amount.checked_add(&amount_out).ok_or(Error::<T>::Overflow)?;
+			ensure!(total >= min_amount_out, Error::<T>::InsufficientOutputAmount);
+			Self::deposit_event(RawEvent::ZappedOut(sender, lpt, liquidity, asset, total));
+			Ok(())
+		}
 	}
 }
 
 decl_event! {
 	pub enum Event<T> where
-		<T as frame_system::Trait>::AccountId,
-		<T as balances::Trait>::Balance,
-		<T as Trait>::AssetId,
+		AccountId = <T as frame_system::Trait>::AccountId,
+		AssetId = AssetIdOf<T>,
+		Balance = BalanceOf<T>,
 	{
-        /// Some assets were issued. \[asset_id, owner, total_supply\]
-        Issued(AssetId, AccountId, Balance),
-        /// Some assets were issued by the system(e.g. lpt, pool tokens) \[asset_id, total_supply]
-        IssuedBySystem(AssetId, Balance),
-        /// Some assets were transferred. \[asset_id, from, to, amount\]
-        Transferred(AssetId, AccountId, AccountId, Balance),
-        TransferredFromSystem(AssetId, Balance),
-        TransferredToSystem(AssetId, Balance),
-        /// Some assets were minted. \[asset_id, owner, balance]
-        Minted(AssetId, AccountId, Balance),
-        /// Some assets were burned. \[asset_id, owner, balance]
-        Burned(AssetId, AccountId, Balance),
-        /// Some assets were destroyed. \[asset_id, owner, balance\]
-		Destroyed(AssetId, AccountId, Balance),
 		/// Pair between two assets is created. \[token0, token1, lptoken]
 		CreatePair(AssetId, AssetId, AssetId),
 		/// An asset is swapped to another asset. \[token0, amount_in, token1, amount_out]
@@ -435,29 +1372,63 @@ decl_event! {
 		MintedLiquidity(AssetId, AssetId, AssetId),
 		/// Liquidity is burned. \[lptoken, token0, token1]
 		BurnedLiquidity(AssetId, AssetId, AssetId),
-		/// Sync oracle. \[price0, price1]
-        SyncOracle(FixedU128, FixedU128),
+		/// Assets are borrowed and repaid by a flash swap. \[who, lptoken, asset_out, amount_out, asset_in, amount_in]
+		FlashSwap(AccountId, AssetId, AssetId, Balance, AssetId, Balance),
+		/// Sync oracle. \[lptoken, price0_cumulative, price1_cumulative]
+		SyncOracle(AssetId, U256, U256),
+		/// The swap fee of a pair is changed. \[lptoken, fee]
+		FeeChanged(AssetId, u32),
+		/// The protocol fee recipient is changed. \[fee_to]
+		FeeToChanged(Option<AccountId>),
+		/// The protocol fee is minted. \[lptoken, fee_to, amount]
+		ProtocolFee(AssetId, AccountId, Balance),
+		/// Weighted pool is created. \[lptoken]
+		CreateWeightedPool(AssetId),
+		/// An asset is deposited to a weighted pool. \[lptoken, asset, amount_in, pool_amount_out]
+		JoinedPool(AssetId, AssetId, Balance, Balance),
+		/// An asset is withdrawn from a weighted pool. \[lptoken, asset, amount_out, pool_amount_in]
+		ExitedPool(AssetId, AssetId, Balance, Balance),
+		/// Concentrated liquidity pool is created. \[pool_id, token0, token1]
+		CreateConcentratedPool(PoolId, AssetId, AssetId),
+		/// A concentrated liquidity position is minted. \[position_id, owner, pool_id, amount0, amount1]
+		MintedPosition(PositionId, AccountId, PoolId, Balance, Balance),
+		/// Liquidity is withdrawn from a position. \[position_id, liquidity, amount0, amount1]
+		DecreasedLiquidity(PositionId, u128, Balance, Balance),
+		/// The assets owed to a position are collected. \[position_id, amount0, amount1]
+		CollectedPosition(PositionId, Balance, Balance),
+		/// An empty position is burned. \[position_id]
+		BurnedPosition(PositionId),
+		/// A position is transferred. \[position_id, from, to]
+		TransferredPosition(PositionId, AccountId, AccountId),
+		/// The native currency distributed to stakers per block is changed. \[reward]
+		RewardPerBlockChanged(Balance),
+		/// A mining pool is created or its allocation points are changed. \[lptoken, alloc_point]
+		MiningPoolSet(AssetId, u32),
+		/// Liquidity provider token is staked. \[who, lptoken, amount]
+		Staked(AccountId, AssetId, Balance),
+		/// Liquidity provider token is unstaked. \[who, lptoken, amount]
+		Unstaked(AccountId, AssetId, Balance),
+		/// Staking reward is paid. \[who, lptoken, reward]
+		RewardPaid(AccountId, AssetId, Balance),
+		/// A limit order is placed. \[order_id, owner, asset_in, amount_in, asset_out, min_amount_out]
+		OrderPlaced(OrderId, AccountId, AssetId, Balance, AssetId, Balance),
+		/// A limit order is filled, partially if some input remains. \[order_id, amount_in, amount_out, remaining]
+		OrderFilled(OrderId, Balance, Balance, Balance),
+		/// A limit order is cancelled and its remaining input returned. \[order_id, remaining]
+		OrderCancelled(OrderId, Balance),
+		/// A limit order is expired and its remaining input returned. \[order_id, remaining]
+		OrderExpired(OrderId, Balance),
+		/// Liquidity is minted from a single asset. \[who, lptoken, asset, amount, liquidity]
+		ZappedIn(AccountId, AssetId, AssetId, Balance, Balance),
+		/// Liquidity is burned for a single asset. \[who, lptoken, liquidity, asset, amount]
+		ZappedOut(AccountId, AssetId, Balance, AssetId, Balance),
 	}
 }
 
 decl_error! {
 	pub enum Error for Module<T: Trait> {
-        /// Transfer amount should be non-zero
-        AmountZero,
-        /// Account balance must be greater than or equal to the transfer amount
-        BalanceLow,
-        /// Balance should be non-zero
-        BalanceZero,
-        /// Not the creator of the asset
-        NotTheCreator,
-        /// Not the approver for the account
-        NotApproved,
-        /// Created by System
-		CreatedBySystem,
 		/// No value
 		NoneValue,
-		/// Insufficient balance
-		InSufficientBalance,
 		/// Pair already exists
 		PairExists,
 		/// Lp token id already exists
@@ -475,229 +1446,1082 @@ decl_error! {
 		/// Insufficient amont for swap
 		InsufficientAmount,
 		/// Insufficiient liquidity for swap
-        InsufficientLiquidity,
-        K,
+		InsufficientLiquidity,
+		K,
+		/// Swap path must contain at least two assets
+		InvalidPath,
+		/// The deadline of the trade has passed
+		Expired,
+		/// Required input amount exceeds the maximum for swap
+		ExcessiveInputAmount,
+		/// Swap fee must be less than 100%
+		InvalidFee,
+		/// Curve parameters are out of range
+		InvalidCurve,
+		/// Pool does not exist or does not hold the asset
+		InvalidPool,
+		/// Weighted pool must have between 2 and 8 assets, each with an amount and a weight
+		InvalidPoolSize,
+		/// Weight of an asset in a weighted pool is out of range
+		InvalidWeight,
+		/// Tick spacing of a concentrated liquidity pool is out of range
+		InvalidTickSpacing,
+		/// Price range of a position is out of range or not aligned to the tick spacing
+		InvalidTick,
+		/// Square root price is out of range
+		InvalidPrice,
+		/// Position does not exist
+		InvalidPosition,
+		/// Sender does not own the position
+		NotPositionOwner,
+		/// Position still has liquidity or assets owed
+		PositionNotEmpty,
+		/// Arithmetic overflow
+		Overflow,
+		/// Mining pool does not exist
+		InvalidMiningPool,
+		/// Unstaked amount exceeds the stake
+		InsufficientStake,
+		/// Pair is locked by a flash swap in progress
+		Locked,
+		/// Limit order does not exist
+		InvalidOrder,
+		/// Sender does not own the limit order
+		NotOrderOwner,
+		/// Too many limit orders are open, in total or of the sender
+		TooManyOrders,
+		/// Invariant of the curve of a pair did not converge
+		NotConverged,
+		/// Amount of the first asset is below its minimum
+		InsufficientAmountA,
+		/// Amount of the second asset is below its minimum
+		InsufficientAmountB,
+		/// Concentrated liquidity pool has too many initialized ticks
+		TooManyTicks,
+		/// Swap crosses more initialized ticks than allowed
+		TooManyTicksCrossed,
+	}
+}
 
+impl<T: Trait> From<MathError> for Error<T> {
+	fn from(error: MathError) -> Self {
+		match error {
+			MathError::Overflow => Error::<T>::Overflow,
+			MathError::DivisionByZero | MathError::InsufficientLiquidity => Error::<T>::InsufficientLiquidity,
+			MathError::InvalidFee => Error::<T>::InvalidFee,
+			MathError::NotConverged => Error::<T>::NotConverged,
+		}
 	}
 }
 
 decl_storage! {
-	trait Store for Module<T: Trait> as Assets {
-		/// The number of units of assets held by any given account.
-		Balances: map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => T::Balance;
-		/// The next asset identifier up for grabs.
-		pub NextAssetId get(fn next_asset_id): T::AssetId;
-		/// The total unit supply of an asset.
-		///
-		/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
-		TotalSupply: map hasher(twox_64_concat) T::AssetId => T::Balance;
-		Creator: map hasher(blake2_128_concat) T::AssetId => T::AccountId;
-
-		/// Market storage
-		/// TODO: decouple this with separate module with defi primitive
-		pub LastBlockTimestamp get(fn last_block_timestamp): T::Moment;
-        // Accumulated price data for each pair. key is lptoken identifier
-        pub LastAccumulativePrice get(fn last_cumulative_price): map hasher(blake2_128_concat) T::AssetId => (FixedU128, FixedU128);
-        pub Rewards get(fn reward): map hasher(blake2_128_concat) T::AssetId => (T::AssetId, T::AssetId);
-        pub Reserves get(fn reserves): map hasher(blake2_128_concat) T::AssetId => (<T as balances::Trait>::Balance, <T as balances::Trait>::Balance);
-        pub Pairs get(fn pair): map hasher(blake2_128_concat) (T::AssetId, T::AssetId) => Option<T::AssetId>;
+	trait Store for Module<T: Trait> as Subswap {
+		// Timestamp of the last reserve update of each pair. key is lptoken identifier
+		pub LastBlockTimestamp get(fn last_block_timestamp): map hasher(blake2_128_concat) AssetIdOf<T> => T::Moment;
+		// Accumulated price data for each pair. key is lptoken identifier
+		pub LastAccumulativePrice get(fn last_cumulative_price): map hasher(blake2_128_concat) AssetIdOf<T> => (U256, U256);
+		// Snapshots of the accumulated price data of each pair, oldest first. key is lptoken identifier
+		pub PriceSnapshots get(fn price_snapshots): map hasher(blake2_128_concat) AssetIdOf<T> => Vec<PriceSnapshot<T::Moment>>;
+		pub Rewards get(fn reward): map hasher(blake2_128_concat) AssetIdOf<T> => (AssetIdOf<T>, AssetIdOf<T>);
+		pub Reserves get(fn reserves): map hasher(blake2_128_concat) AssetIdOf<T> => (BalanceOf<T>, BalanceOf<T>);
+		pub Pairs get(fn pair): map hasher(blake2_128_concat) (AssetIdOf<T>, AssetIdOf<T>) => Option<AssetIdOf<T>>;
+		// Swap fee of each pair in basis points. key is lptoken identifier
+		pub Fees get(fn fee): map hasher(blake2_128_concat) AssetIdOf<T> => u32;
+		// Bonding curve of each pair. key is lptoken identifier
+		pub Curves get(fn curve): map hasher(blake2_128_concat) AssetIdOf<T> => CurveType;
+		// Account receiving the protocol fee, if it is on
+		pub FeeTo get(fn fee_to): Option<T::AccountId>;
+		// Invariant of the reserves of each pair, like their product, as of the last liquidity event while the protocol fee is on. key is lptoken identifier
+		pub KLast get(fn k_last): map hasher(blake2_128_concat) AssetIdOf<T> => U256;
+		// Pairs lending their reserves to a flash swap in progress. key is lptoken identifier
+		pub FlashLocked get(fn flash_locked): map hasher(blake2_128_concat) AssetIdOf<T> => bool;
+		// Assets, weights and balances of each weighted pool. key is lptoken identifier
+		pub WeightedPools get(fn weighted_pool): map hasher(blake2_128_concat) AssetIdOf<T> => Option<WeightedPool<AssetIdOf<T>, BalanceOf<T>>>;
+		// Identifier of the next concentrated liquidity pool
+		pub NextPoolId get(fn next_pool_id): PoolId;
+		pub ConcentratedPools get(fn concentrated_pool): map hasher(blake2_128_concat) PoolId => Option<ConcentratedPool<AssetIdOf<T>>>;
+		// Liquidity referencing each initialized tick of a concentrated liquidity pool
+		pub Ticks get(fn tick): double_map hasher(blake2_128_concat) PoolId, hasher(blake2_128_concat) i32 => TickInfo;
+		// Initialized ticks of each concentrated liquidity pool in ascending order
+		pub InitializedTicks get(fn initialized_ticks): map hasher(blake2_128_concat) PoolId => Vec<i32>;
+		// Identifier of the next concentrated liquidity position
+		pub NextPositionId get(fn next_position_id): PositionId;
+		pub Positions get(fn position): map hasher(blake2_128_concat) PositionId => Option<Position<T::AccountId, PoolId, BalanceOf<T>>>;
+		// Positions owned by each account
+		pub PositionsByOwner get(fn positions_by_owner): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) PositionId => ();
+		// Native currency distributed to the stakers of every mining pool per block
+		pub RewardPerBlock get(fn reward_per_block): BalanceOf<T>;
+		// Sum of the allocation points of every mining pool
+		pub TotalAllocPoint get(fn total_alloc_point): u32;
+		// Mining pool of each liquidity provider token. key is lptoken identifier
+		pub MiningPools get(fn mining_pool): map hasher(blake2_128_concat) AssetIdOf<T> => Option<MiningPool<T::BlockNumber, BalanceOf<T>>>;
+		// Liquidity provider token staked by each account. keys are lptoken identifier and staker
+		pub Stakes get(fn stake): double_map hasher(blake2_128_concat) AssetIdOf<T>, hasher(blake2_128_concat) T::AccountId => StakeInfo<BalanceOf<T>>;
+		// Identifier of the next limit order
+		pub NextOrderId get(fn next_order_id): OrderId;
+		pub Orders get(fn order): map hasher(blake2_128_concat) OrderId => Option<LimitOrder<T::AccountId, AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>>;
+		// Open limit orders in the order they were placed
+		pub OpenOrders get(fn open_orders): Vec<OrderId>;
+		// Position in the open limit orders where the next block starts settling
+		pub OrderCursor get(fn order_cursor): u32;
+		// Number of open limit orders of each account
+		pub OpenOrderCount get(fn open_order_count): map hasher(blake2_128_concat) T::AccountId => u32;
+	}
+	add_extra_genesis {
+		/// The pairs seeded at genesis, as `(token0, token1, providers)` where every provider
+		/// `(who, amount0, amount1)` deposits from its balances and receives liquidity provider
+		/// token. The first provider creates the pair at its price, and the others deposit in the
+		/// ratio of its reserves.
+		config(pairs): Vec<(AssetIdOf<T>, AssetIdOf<T>, Vec<(T::AccountId, BalanceOf<T>, BalanceOf<T>)>)>;
+		build(|config: &GenesisConfig<T>| {
+			for (token0, token1, providers) in &config.pairs {
+				assert!(<Module<T>>::pair((*token0, *token1)).is_none(), "Pair seeded twice at genesis");
+				for (who, amount0, amount1) in providers {
+					<Module<T>>::_add_liquidity(who, *token0, *token1, *amount0, *amount1, Zero::zero(), Zero::zero())
+						.expect("Providers of a genesis pair hold the assets they deposit");
+				}
+			}
+		});
 	}
 }
 
 // The main implementation block for the module.
 impl<T: Trait> Module<T> {
-	// Public immutables
-
-	/// Get the asset `id` balance of `who`.
-	pub fn balance(id: T::AssetId, who: T::AccountId) -> T::Balance {
-		<Balances<T>>::get((id, who))
-	}
-
-	/// Get the total supply of an asset `id`.
-	pub fn total_supply(id: T::AssetId) -> T::Balance {
-		<TotalSupply<T>>::get(id)
-	}
-
-	pub fn mint_from_system(
-        id: &T::AssetId,
-        target: &T::AccountId,
-        amount: &T::Balance,
-    ) -> dispatch::DispatchResult {
-        ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-        Self::deposit_event(RawEvent::Minted(*id, target.clone(), *amount));
-        if *id == Zero::zero() {
-            let new_free = balances::Module::<T>::free_balance(target) + *amount;
-            balances::Module::<T>::mutate_account(target, |account| {
-                account.free = new_free;
-
-                account.free
-            });
-        } else {
-            <Balances<T>>::mutate((*id, target.clone()), |balance| *balance += *amount);
-            <TotalSupply<T>>::mutate(*id, |supply| *supply += *amount);
-        }
-        Ok(())
-    }
-
-    pub fn burn_from_system(
-        id: &T::AssetId,
-        target: &T::AccountId,
-        amount: &T::Balance,
-    ) -> dispatch::DispatchResult {
-        ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-        Self::deposit_event(RawEvent::Burned(*id, target.clone(), *amount));
-        if *id == Zero::zero() {
-            let new_free = balances::Module::<T>::free_balance(target) - *amount;
-            let _free = balances::Module::<T>::mutate_account(target, |account| {
-                account.free = new_free;
-
-                account.free
-            });
-        } else {
-            <Balances<T>>::mutate((*id, target.clone()), |balance| *balance -= *amount);
-            <TotalSupply<T>>::mutate(*id, |supply| *supply -= *amount);
-        }
-        Ok(())
-    }
-
-    pub fn transfer_from_system(
-        id: &T::AssetId,
-        target: &T::AccountId,
-        amount: &T::Balance,
-    ) -> dispatch::DispatchResult {
-        ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-        Self::deposit_event(RawEvent::Minted(*id, target.clone(), *amount));
-        if *id == Zero::zero() {
-            let new_free = balances::Module::<T>::free_balance(target) + *amount;
-            let _free = balances::Module::<T>::mutate_account(target, |account| {
-                account.free = new_free;
-
-                account.free
-            });
-        } else {
-            <Balances<T>>::mutate((*id, target.clone()), |balance| *balance += *amount);
-        }
-        Ok(())
-    }
-
-    pub fn transfer_to_system(
-        id: &T::AssetId,
-        target: &T::AccountId,
-        amount: &T::Balance,
-    ) -> dispatch::DispatchResult {
-        ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-        Self::deposit_event(RawEvent::Burned(*id, target.clone(), *amount));
-        if *id == Zero::zero() {
-            let new_free = balances::Module::<T>::free_balance(target) - *amount;
-            let _free = balances::Module::<T>::mutate_account(target, |account| {
-                account.free = new_free;
-
-                account.free
-            });
-        } else {
-            <Balances<T>>::mutate((*id, target.clone()), |balance| *balance -= *amount);
-        }
-        Ok(())
-    }
-
-    pub fn issue_from_system(total: T::Balance) -> dispatch::DispatchResult {
-        let id = Self::next_asset_id();
-        <NextAssetId<T>>::mutate(|id| {
-            if *id == Zero::zero() {
-                *id += One::one();
-            }
-            *id += One::one();
-        });
-        <TotalSupply<T>>::insert(id, total);
-
-        Self::deposit_event(RawEvent::IssuedBySystem(id, total));
-        Ok(())
-	}
-	
-
-
-	// Market methods
-	// TODO: separate these functions into a new module and share primitives with this
+	/// The account holding the reserves of every pair.
+	pub fn account_id() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	/// The account holding the minimum liquidity of every pair, which never moves.
+	pub fn lock_account_id() -> T::AccountId {
+		T::ModuleId::get().into_sub_account(b"lock")
+	}
+
 	fn _set_reserves(
-        token0: &T::AssetId,
-        token1: &T::AssetId,
-        amount0: &<T as balances::Trait>::Balance,
-        amount1: &<T as balances::Trait>::Balance,
-        lptoken: &T::AssetId,
-    ) {
-        match *token0 > *token1 {
-            true => {
-                <Reserves<T>>::insert(*lptoken, (*amount1, *amount0));
-            }
-            _ => {
-                <Reserves<T>>::insert(*lptoken, (*amount0, *amount1));
-            }
-        }
-    }
-
-    fn _set_pair(token0: &T::AssetId, token1: &T::AssetId, lptoken: &T::AssetId) {
-        <Pairs<T>>::insert((*token0, *token1), *lptoken);
-        <Pairs<T>>::insert((*token1, *token0), *lptoken);
-    }
-    
+		token0: &AssetIdOf<T>,
+		token1: &AssetIdOf<T>,
+		amount0: &BalanceOf<T>,
+		amount1: &BalanceOf<T>,
+		lptoken: &AssetIdOf<T>,
+	) {
+		// Accumulate the price of the reserves being replaced
+		Self::_update(lptoken);
+		match *token0 > *token1 {
+			true => {
+				<Reserves<T>>::insert(*lptoken, (*amount1, *amount0));
+			}
+			_ => {
+				<Reserves<T>>::insert(*lptoken, (*amount0, *amount1));
+			}
+		}
+	}
+
+	fn _set_pair(token0: &AssetIdOf<T>, token1: &AssetIdOf<T>, lptoken: &AssetIdOf<T>) {
+		<Pairs<T>>::insert((*token0, *token1), *lptoken);
+		<Pairs<T>>::insert((*token1, *token0), *lptoken);
+	}
+
 	fn _set_rewards(
-        token0: &T::AssetId, token1: &T::AssetId, lptoken: &T::AssetId
-    ) {
-        match *token0 > *token1 {
-            true => {
-                <Rewards<T>>::insert(*lptoken, (*token1, *token0));
-            }
-            _ => {
-                <Rewards<T>>::insert(*lptoken, (*token0, *token1));
-            }
-        }
-    }
+		token0: &AssetIdOf<T>, token1: &AssetIdOf<T>, lptoken: &AssetIdOf<T>
+	) {
+		match *token0 > *token1 {
+			true => {
+				<Rewards<T>>::insert(*lptoken, (*token1, *token0));
+			}
+			_ => {
+				<Rewards<T>>::insert(*lptoken, (*token0, *token1));
+			}
+		}
+	}
+
+	/// Deposit up to `desired_a` of `token_a` and `desired_b` of `token_b` from `sender` to their
+	/// pair in the ratio of its reserves, no less than `min_a` and `min_b`, minting liquidity
+	/// provider token to `sender`. Returns the amounts deposited.
+	fn _add_liquidity(
+		sender: &T::AccountId,
+		token_a: AssetIdOf<T>,
+		token_b: AssetIdOf<T>,
+		desired_a: BalanceOf<T>,
+		desired_b: BalanceOf<T>,
+		min_a: BalanceOf<T>,
+		min_b: BalanceOf<T>,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), dispatch::DispatchError> {
+		let (amount_a, amount_b) = Self::optimal_amounts(&token_a, &token_b, desired_a, desired_b, min_a, min_b)?;
+		Self::_mint_liquidity(sender, token_a, amount_a, token_b, amount_b)?;
+		Ok((amount_a, amount_b))
+	}
+
+	/// Get the amounts of `token_a` and `token_b` deposited to their pair for up to `desired_a`
+	/// and `desired_b`, in the ratio of the reserves of a constant product pair as in the router
+	/// of Uniswap v2. Fails if either amount is below `min_a` or `min_b`.
+	pub fn optimal_amounts(
+		token_a: &AssetIdOf<T>,
+		token_b: &AssetIdOf<T>,
+		desired_a: BalanceOf<T>,
+		desired_b: BalanceOf<T>,
+		min_a: BalanceOf<T>,
+		min_b: BalanceOf<T>,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), dispatch::DispatchError> {
+		let lpt = match Self::pair((*token_a, *token_b)) {
+			Some(lpt) if T::Assets::total_issuance(lpt) > Zero::zero() => lpt,
+			_ => return Ok((desired_a, desired_b)),
+		};
+		if Self::curve(lpt) != CurveType::ConstantProduct {
+			return Ok((desired_a, desired_b));
+		}
+		let (_, reserve_a, reserve_b) = Self::get_reserves(token_a, token_b)?;
+		let optimal_b = math::quote(desired_a, reserve_a, reserve_b).map_err(Error::<T>::from)?;
+		if optimal_b <= desired_b {
+			ensure!(optimal_b >= min_b, Error::<T>::InsufficientAmountB);
+			return Ok((desired_a, optimal_b));
+		}
+		// Quoting back never exceeds `desired_a` since `desired_b` is worth less than it
+		let optimal_a = math::quote(desired_b, reserve_b, reserve_a).map_err(Error::<T>::from)?;
+		ensure!(optimal_a >= min_a, Error::<T>::InsufficientAmountA);
+		Ok((optimal_a, desired_b))
+	}
+
+	/// Get the other asset of the pair of `lpt` than `asset`. Fails if `lpt` is not the liquidity
+	/// provider token of a pair of `asset`.
+	fn _paired_asset(lpt: &AssetIdOf<T>, asset: &AssetIdOf<T>) -> Result<AssetIdOf<T>, dispatch::DispatchError> {
+		ensure!(<Rewards<T>>::contains_key(lpt), Error::<T>::InvalidPair);
+		match Self::reward(lpt) {
+			(token0, token1) if token0 == *asset => Ok(token1),
+			(token0, token1) if token1 == *asset => Ok(token0),
+			_ => Err(Error::<T>::InvalidPair.into()),
+		}
+	}
+
+	/// Deposit `amount0` of `token0` and `amount1` of `token1` from `sender` to their pair, minting
+	/// liquidity provider token to `sender`, or create the pair if it does not exist yet.
+	fn _mint_liquidity(
+		sender: &T::AccountId,
+		token0: AssetIdOf<T>,
+		amount0: BalanceOf<T>,
+		token1: AssetIdOf<T>,
+		amount1: BalanceOf<T>,
+	) -> dispatch::DispatchResult {
+		ensure!(token0 != token1, Error::<T>::IdenticalIdentifier);
+		match Self::pair((token0, token1)) {
+			// create pair if lpt does not exist
+			None => Self::_create_pair(sender, token0, amount0, token1, amount1, T::DefaultFee::get(), CurveType::ConstantProduct),
+			// when lpt exists and total supply is superset of 0
+			Some(lpt) if T::Assets::total_issuance(lpt) > Zero::zero() => {
+				ensure!(!Self::flash_locked(lpt), Error::<T>::Locked);
+				// Deposit assets from user to the pool account
+				let pool = Self::account_id();
+				T::Assets::transfer(token0, sender, &pool, amount0)?;
+				T::Assets::transfer(token1, sender, &pool, amount1)?;
+				let fee_on = Self::_mint_fee(&lpt)?;
+				let total_supply = T::Assets::total_issuance(lpt);
+				let reserves = Self::reserves(lpt);
+				let tokens = Self::reward(lpt);
+				// Order the deposits as the reserves
+				let amounts = match token0 > token1 {
+					true => (amount1, amount0),
+					false => (amount0, amount1),
+				};
+				let lptoken_amount = Self::curve(lpt)
+					.liquidity_minted(amounts, reserves, total_supply, Self::fee(lpt))
+					.map_err(Error::<T>::from)?;
+				ensure!(lptoken_amount > Zero::zero(), Error::<T>::InsufficientLiquidityMinted);
+				let reserve0 = reserves.0.checked_add(&amounts.0).ok_or(Error::<T>::Overflow)?;
+				let reserve1 = reserves.1.checked_add(&amounts.1).ok_or(Error::<T>::Overflow)?;
+				// Deposit assets to the reserve
+				Self::_set_reserves(&tokens.0, &tokens.1, &reserve0, &reserve1, &lpt);
+				// Mint LPtoken to the sender
+				T::Assets::mint_into(lpt, sender, lptoken_amount)?;
+				Self::_update_k_last(&lpt, fee_on)?;
+				Self::deposit_event(RawEvent::MintedLiquidity(token0, token1, lpt));
+				Ok(())
+			},
+			Some(_) => Err(Error::<T>::NoneValue)?,
+		}
+	}
+
+	/// Issue the liquidity provider token of a new pair, deposit the initial liquidity of
+	/// `sender` and mint liquidity provider token in return.
+	fn _create_pair(
+		sender: &T::AccountId,
+		token0: AssetIdOf<T>,
+		amount0: BalanceOf<T>,
+		token1: AssetIdOf<T>,
+		amount1: BalanceOf<T>,
+		fee: u32,
+		curve: CurveType,
+	) -> dispatch::DispatchResult {
+		ensure!(fee < FEE_DENOMINATOR, Error::<T>::InvalidFee);
+		ensure!(curve.is_valid(), Error::<T>::InvalidCurve);
+		let minimum_liquidity = BalanceOf::<T>::from(MINIMUM_LIQUIDITY);
+		// Deposit assets from user to the pool account
+		let pool = Self::account_id();
+		T::Assets::transfer(token0, sender, &pool, amount0)?;
+		T::Assets::transfer(token1, sender, &pool, amount1)?;
+		let lptoken_amount = curve
+			.liquidity_minted((amount0, amount1), (Zero::zero(), Zero::zero()), Zero::zero(), fee)
+			.map_err(Error::<T>::from)?
+			.checked_sub(&minimum_liquidity)
+			.ok_or(Error::<T>::InsufficientLiquidityMinted)?;
+		// Issue LPtoken
+		let lptoken_id = T::Assets::issue_from_system(Zero::zero())?;
+		Self::_set_lp_metadata(lptoken_id, &[token0, token1])?;
+		// Deposit assets to the reserve
+		Self::_set_reserves(&token0, &token1, &amount0, &amount1, &lptoken_id);
+		// Set pairs for swap lookup
+		Self::_set_pair(&token0, &token1, &lptoken_id);
+		Self::_set_rewards(&token0, &token1, &lptoken_id);
+		<Fees<T>>::insert(lptoken_id, fee);
+		<Curves<T>>::insert(lptoken_id, curve);
+		// Lock the minimum liquidity and mint LPtoken to the sender
+		T::Assets::mint_into(lptoken_id, &Self::lock_account_id(), minimum_liquidity)?;
+		T::Assets::mint_into(lptoken_id, sender, lptoken_amount)?;
+		Self::_update_k_last(&lptoken_id, Self::fee_to().is_some())?;
+		Self::deposit_event(RawEvent::CreatePair(token0, token1, lptoken_id));
+		Ok(())
+	}
+
+	/// Burn `amount` of the liquidity provider token `lpt` of `sender` for the pro-rata share of
+	/// the reserves of its pair. Returns the amounts redeemed, ordered by identifier.
+	fn _burn_liquidity(
+		sender: &T::AccountId,
+		lpt: AssetIdOf<T>,
+		amount: BalanceOf<T>,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), dispatch::DispatchError> {
+		ensure!(!Self::flash_locked(lpt), Error::<T>::Locked);
+		let mut reserves = Self::reserves(lpt);
+		let tokens = Self::reward(lpt);
+		let fee_on = Self::_mint_fee(&lpt)?;
 
+		// Calculate rewards for providing liquidity with pro-rata distribution
+		let (reward0, reward1) = Self::lp_share_value(&lpt, &amount)?;
+
+		// Ensure rewards exist
+		ensure!(reward0 > Zero::zero() && reward1 > Zero::zero(), Error::<T>::InsufficientLiquidityBurned);
+
+		// Distribute reward to the sender
+		let pool = Self::account_id();
+		T::Assets::burn_from(lpt, sender, amount)?;
+		T::Assets::transfer(tokens.0, &pool, sender, reward0)?;
+		T::Assets::transfer(tokens.1, &pool, sender, reward1)?;
+
+		// Update reserve when the balance is set
+		reserves.0 = reserves.0.checked_sub(&reward0).ok_or(Error::<T>::InsufficientLiquidity)?;
+		reserves.1 = reserves.1.checked_sub(&reward1).ok_or(Error::<T>::InsufficientLiquidity)?;
+		Self::_set_reserves(&tokens.0, &tokens.1, &reserves.0, &reserves.1, &lpt);
+		Self::_update_k_last(&lpt, fee_on)?;
+		// Deposit event that the liquidity is burned successfully
+		Self::deposit_event(RawEvent::BurnedLiquidity(lpt, tokens.0, tokens.1));
+		Ok((reward0, reward1))
+	}
+
+	/// Name the liquidity provider token `lpt` after the symbols of its `assets` ordered by
+	/// identifier, such as `SUB-USDT LP`, with the decimals of the native currency. The token is
+	/// left without metadata if an asset has no symbol.
+	///
+	/// Liquidity is a mean of the reserves weighted by the curve of the pool rather than an amount
+	/// of any one asset, so every liquidity provider token shares the same decimals.
+	fn _set_lp_metadata(lpt: AssetIdOf<T>, assets: &[AssetIdOf<T>]) -> dispatch::DispatchResult {
+		let mut assets = assets.to_vec();
+		assets.sort();
+		let mut symbol = Vec::new();
+		for (i, asset) in assets.iter().enumerate() {
+			let asset_symbol = T::Assets::symbol(*asset);
+			if asset_symbol.is_empty() {
+				return Ok(());
+			}
+			if i > 0 {
+				symbol.push(b'-');
+			}
+			symbol.extend_from_slice(&asset_symbol);
+		}
+		let mut name = symbol.clone();
+		name.extend_from_slice(b" LP");
+		symbol.extend_from_slice(b"-LP");
+		T::Assets::set_system_metadata(lpt, name, symbol, T::Assets::decimals(Zero::zero()))
+	}
+
+	/// The invariant of the reserves of the pair of `lpt` under its bonding curve.
+	fn _k(lpt: &AssetIdOf<T>) -> math::MathResult<U256> {
+		Self::curve(lpt).invariant(Self::reserves(lpt))
+	}
+
+	/// Mint the protocol fee accrued by the pair of `lpt` since the last liquidity event, if the
+	/// protocol fee is on. Returns whether the protocol fee is on.
+	fn _mint_fee(lpt: &AssetIdOf<T>) -> Result<bool, dispatch::DispatchError> {
+		let k_last = Self::k_last(lpt);
+		match Self::fee_to() {
+			Some(fee_to) => {
+				if !k_last.is_zero() {
+					let root_k = math::sqrt_u256(Self::_k(lpt).map_err(Error::<T>::from)?);
+					let root_k_last = math::sqrt_u256(k_last);
+					if root_k > root_k_last {
+						// Mint one sixth of the growth of sqrt(k) as in Uniswap v2
+						let total_supply = U256::from(T::Assets::total_issuance(*lpt).saturated_into::<u128>());
+						let numerator = total_supply * (root_k - root_k_last);
+						let denominator = root_k * U256::from(5u32) + root_k_last;
+						let liquidity = (numerator / denominator).as_u128().saturated_into::<BalanceOf<T>>();
+						if liquidity > Zero::zero() {
+							T::Assets::mint_into(*lpt, &fee_to, liquidity)?;
+							Self::deposit_event(RawEvent::ProtocolFee(*lpt, fee_to, liquidity));
+						}
+					}
+				}
+				Ok(true)
+			}
+			None => {
+				if !k_last.is_zero() {
+					<KLast<T>>::remove(lpt);
+				}
+				Ok(false)
+			}
+		}
+	}
+
+	fn _update_k_last(lpt: &AssetIdOf<T>, fee_on: bool) -> dispatch::DispatchResult {
+		if fee_on {
+			<KLast<T>>::insert(lpt, Self::_k(lpt).map_err(Error::<T>::from)?);
+		}
+		Ok(())
+	}
+
+	/// Change the liquidity of `position` by `delta`, updating its ticks, the in-range liquidity
+	/// of its pool and the fees owed to it. Returns the amounts of `token0` and `token1` of the
+	/// change, rounded up when liquidity is added and down when it is removed.
+	fn _modify_position(
+		position: &mut Position<T::AccountId, PoolId, BalanceOf<T>>,
+		delta: i128,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), dispatch::DispatchError> {
+		let pool_id = position.pool_id;
+		let mut pool = Self::concentrated_pool(pool_id).ok_or(Error::<T>::InvalidPool)?;
+		let (tick_lower, tick_upper) = (position.tick_lower, position.tick_upper);
+		if delta != 0 {
+			Self::_update_tick(pool_id, &pool, tick_lower, delta, false)?;
+			Self::_update_tick(pool_id, &pool, tick_upper, delta, true)?;
+		}
+		// Accrue the fees earned inside the range by the liquidity before the change
+		let lower = Self::tick(pool_id, tick_lower);
+		let upper = Self::tick(pool_id, tick_upper);
+		let (inside0, inside1) = concentrated::fee_growth_inside(
+			&lower, tick_lower, &upper, tick_upper, pool.tick, pool.fee_growth_global0, pool.fee_growth_global1,
+		);
+		let earned0 = concentrated::fees_earned(inside0, position.fee_growth_inside0_last, position.liquidity)
+			.ok_or(Error::<T>::Overflow)?;
+		let earned1 = concentrated::fees_earned(inside1, position.fee_growth_inside1_last, position.liquidity)
+			.ok_or(Error::<T>::Overflow)?;
+		position.tokens_owed0 = position.tokens_owed0.saturating_add(Self::_u256_to_balance(earned0)?);
+		position.tokens_owed1 = position.tokens_owed1.saturating_add(Self::_u256_to_balance(earned1)?);
+		position.fee_growth_inside0_last = inside0;
+		position.fee_growth_inside1_last = inside1;
+		position.liquidity = concentrated::add_delta(position.liquidity, delta).ok_or(Error::<T>::InsufficientLiquidity)?;
+
+		let liquidity = if delta < 0 { (delta as u128).wrapping_neg() } else { delta as u128 };
+		let (amount0, amount1) = concentrated::amounts_for_liquidity(
+			pool.sqrt_price, pool.tick, tick_lower, tick_upper, liquidity, delta > 0,
+		).ok_or(Error::<T>::Overflow)?;
+		if pool.tick >= tick_lower && pool.tick < tick_upper && delta != 0 {
+			pool.liquidity = concentrated::add_delta(pool.liquidity, delta).ok_or(Error::<T>::InsufficientLiquidity)?;
+			<ConcentratedPools<T>>::insert(pool_id, pool);
+		}
+		// Clear the ticks no longer referenced by any position
+		if delta < 0 {
+			for tick in &[tick_lower, tick_upper] {
+				if Self::tick(pool_id, tick).liquidity_gross == 0 {
+					<Ticks>::remove(pool_id, tick);
+					<InitializedTicks>::mutate(pool_id, |ticks| ticks.retain(|t| t != tick));
+				}
+			}
+		}
+		Ok((Self::_u256_to_balance(amount0)?, Self::_u256_to_balance(amount1)?))
+	}
+
+	/// Add `delta` to the liquidity referencing `tick` of the pool `pool_id`, initializing the
+	/// tick if it was not referenced yet.
+	fn _update_tick(
+		pool_id: PoolId,
+		pool: &ConcentratedPool<AssetIdOf<T>>,
+		tick: i32,
+		delta: i128,
+		upper: bool,
+	) -> dispatch::DispatchResult {
+		let mut info = Self::tick(pool_id, tick);
+		let liquidity_gross_before = info.liquidity_gross;
+		info.liquidity_gross = concentrated::add_delta(liquidity_gross_before, delta)
+			.ok_or(Error::<T>::InsufficientLiquidity)?;
+		if liquidity_gross_before == 0 {
+			// By convention, all the fees so far were earned below the tick
+			if tick <= pool.tick {
+				info.fee_growth_outside0 = pool.fee_growth_global0;
+				info.fee_growth_outside1 = pool.fee_growth_global1;
+			}
+			<InitializedTicks>::try_mutate(pool_id, |ticks| -> dispatch::DispatchResult {
+				if let Err(i) = ticks.binary_search(&tick) {
+					ensure!((ticks.len() as u32) < T::MaxInitializedTicks::get(), Error::<T>::TooManyTicks);
+					ticks.insert(i, tick);
+				}
+				Ok(())
+			})?;
+		}
+		info.liquidity_net = match upper {
+			true => info.liquidity_net.checked_sub(delta),
+			false => info.liquidity_net.checked_add(delta),
+		}.ok_or(Error::<T>::Overflow)?;
+		<Ticks>::insert(pool_id, tick, info);
+		Ok(())
+	}
+
+	/// Swap `amount_in` with the concentrated liquidity pool `pool_id`, crossing up to
+	/// `max_ticks_crossed` of its initialized ticks until the input is spent or the square root
+	/// price reaches `sqrt_price_limit`. Returns the input swapped and the output amount.
+	fn _swap_concentrated(
+		pool_id: PoolId,
+		pool: &mut ConcentratedPool<AssetIdOf<T>>,
+		zero_for_one: bool,
+		amount_in: BalanceOf<T>,
+		sqrt_price_limit: Option<U256>,
+		max_ticks_crossed: u32,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), dispatch::DispatchError> {
+		let limit = match (sqrt_price_limit, zero_for_one) {
+			(Some(limit), _) => limit,
+			(None, true) => concentrated::MIN_SQRT_RATIO + U256::one(),
+			(None, false) => concentrated::MAX_SQRT_RATIO - U256::one(),
+		};
+		ensure!(
+			match zero_for_one {
+				true => limit < pool.sqrt_price && limit > concentrated::MIN_SQRT_RATIO,
+				false => limit > pool.sqrt_price && limit < concentrated::MAX_SQRT_RATIO,
+			},
+			Error::<T>::InvalidPrice
+		);
+		let ticks = Self::initialized_ticks(pool_id);
+		let mut amount_remaining = U256::from(amount_in.saturated_into::<u128>());
+		let mut amount_out = U256::zero();
+		let mut ticks_crossed = 0u32;
+		while !amount_remaining.is_zero() && pool.sqrt_price != limit {
+			let (tick_next, initialized) = concentrated::next_initialized_tick(&ticks, pool.tick, zero_for_one);
+			let sqrt_price_next = concentrated::sqrt_ratio_at_tick(tick_next).ok_or(Error::<T>::InvalidTick)?;
+			let sqrt_price_target = match zero_for_one {
+				true => sqrt_price_next.max(limit),
+				false => sqrt_price_next.min(limit),
+			};
+			let step = concentrated::compute_swap_step(
+				pool.sqrt_price, sqrt_price_target, pool.liquidity, amount_remaining, pool.fee,
+			).ok_or(Error::<T>::InsufficientLiquidity)?;
+			pool.sqrt_price = step.sqrt_price_next;
+			amount_remaining = step.amount_in.checked_add(step.fee_amount)
+				.and_then(|spent| amount_remaining.checked_sub(spent))
+				.ok_or(Error::<T>::Overflow)?;
+			amount_out = amount_out.checked_add(step.amount_out).ok_or(Error::<T>::Overflow)?;
+			// Share the fee between the liquidity in range
+			if pool.liquidity > 0 {
+				let growth = concentrated::mul_div(step.fee_amount, concentrated::q128(), U256::from(pool.liquidity))
+					.ok_or(Error::<T>::Overflow)?;
+				match zero_for_one {
+					true => pool.fee_growth_global0 = pool.fee_growth_global0.overflowing_add(growth).0,
+					false => pool.fee_growth_global1 = pool.fee_growth_global1.overflowing_add(growth).0,
+				}
+			}
+			if pool.sqrt_price == sqrt_price_next {
+				if initialized {
+					ensure!(ticks_crossed < max_ticks_crossed, Error::<T>::TooManyTicksCrossed);
+					ticks_crossed += 1;
+					// Cross the tick, flipping the side of its fee growth and moving its liquidity
+					// in or out of range
+					let mut info = Self::tick(pool_id, tick_next);
+					info.fee_growth_outside0 = pool.fee_growth_global0.overflowing_sub(info.fee_growth_outside0).0;
+					info.fee_growth_outside1 = pool.fee_growth_global1.overflowing_sub(info.fee_growth_outside1).0;
+					let liquidity_net = match zero_for_one {
+						true => info.liquidity_net.checked_neg().ok_or(Error::<T>::Overflow)?,
+						false => info.liquidity_net,
+					};
+					pool.liquidity = concentrated::add_delta(pool.liquidity, liquidity_net)
+						.ok_or(Error::<T>::InsufficientLiquidity)?;
+					<Ticks>::insert(pool_id, tick_next, info);
+				}
+				pool.tick = if zero_for_one { tick_next - 1 } else { tick_next };
+			} else {
+				pool.tick = concentrated::tick_at_sqrt_ratio(pool.sqrt_price).ok_or(Error::<T>::InvalidPrice)?;
+			}
+		}
+		let amount_remaining = Self::_u256_to_balance(amount_remaining)?;
+		Ok((amount_in - amount_remaining, Self::_u256_to_balance(amount_out)?))
+	}
+
+	/// Accumulate the reward of every mining pool up to the current block, before the reward
+	/// per block or the allocation points change.
+	fn _update_mining_pools() -> dispatch::DispatchResult {
+		let pools: Vec<AssetIdOf<T>> = <MiningPools<T>>::iter().map(|(lpt, _)| lpt).collect();
+		for lpt in pools {
+			let pool = Self::_update_mining_pool(&lpt)?;
+			<MiningPools<T>>::insert(lpt, pool);
+		}
+		Ok(())
+	}
+
+	/// Get the mining pool of `lpt` with its reward accumulated up to the current block.
+	///
+	/// The reward of the blocks since the last update is spread over the staked tokens at once,
+	/// so the cost does not depend on the number of stakers.
+	fn _update_mining_pool(
+		lpt: &AssetIdOf<T>,
+	) -> Result<MiningPool<T::BlockNumber, BalanceOf<T>>, dispatch::DispatchError> {
+		let mut pool = Self::mining_pool(lpt).ok_or(Error::<T>::InvalidMiningPool)?;
+		let now = <frame_system::Module<T>>::block_number();
+		if now <= pool.last_reward_block {
+			return Ok(pool);
+		}
+		let total_alloc_point = Self::total_alloc_point();
+		if !pool.total_staked.is_zero() && total_alloc_point > 0 {
+			let blocks = U256::from((now - pool.last_reward_block).saturated_into::<u128>());
+			let reward = blocks
+				.saturating_mul(U256::from(Self::reward_per_block().saturated_into::<u128>()))
+				.saturating_mul(U256::from(pool.alloc_point))
+				/ U256::from(total_alloc_point);
+			let total_staked = U256::from(pool.total_staked.saturated_into::<u128>());
+			pool.acc_reward_per_share = pool.acc_reward_per_share
+				.saturating_add(reward.saturating_mul(U256::from(ACC_REWARD_PRECISION)) / total_staked);
+		}
+		pool.last_reward_block = now;
+		Ok(pool)
+	}
+
+	/// The reward accumulated by `amount` of staked token since the creation of `pool`, scaled by
+	/// `ACC_REWARD_PRECISION`.
+	fn _accumulated_reward(pool: &MiningPool<T::BlockNumber, BalanceOf<T>>, amount: BalanceOf<T>) -> U256 {
+		U256::from(amount.saturated_into::<u128>()).saturating_mul(pool.acc_reward_per_share)
+	}
+
+	/// Pay the reward of `stake` accumulated since it was last claimed, minting the native
+	/// currency to `who`.
+	fn _claim(
+		who: &T::AccountId,
+		lpt: &AssetIdOf<T>,
+		pool: &MiningPool<T::BlockNumber, BalanceOf<T>>,
+		stake: &StakeInfo<BalanceOf<T>>,
+	) -> dispatch::DispatchResult {
+		let pending = Self::_accumulated_reward(pool, stake.amount).saturating_sub(stake.reward_debt)
+			/ U256::from(ACC_REWARD_PRECISION);
+		let reward = Self::_u256_to_balance(pending)?;
+		if !reward.is_zero() {
+			// Asset id 0 is the native currency
+			T::Assets::mint_into(Zero::zero(), who, reward)?;
+			Self::deposit_event(RawEvent::RewardPaid(who.clone(), *lpt, reward));
+		}
+		Ok(())
+	}
+
+	/// Get the reward of the liquidity provider token `lpt` staked by `who` which is not claimed
+	/// yet, as of the current block.
+	pub fn pending_rewards(lpt: &AssetIdOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+		let stake = Self::stake(lpt, who);
+		Self::_update_mining_pool(lpt)
+			.map(|pool| {
+				let pending = Self::_accumulated_reward(&pool, stake.amount).saturating_sub(stake.reward_debt)
+					/ U256::from(ACC_REWARD_PRECISION);
+				pending.min(U256::from(u128::max_value())).as_u128().saturated_into()
+			})
+			.unwrap_or_else(|_| Zero::zero())
+	}
+
+	/// Settle up to `MaxOrdersPerBlock` open limit orders, starting where the previous block
+	/// stopped so that every open order is visited in turn. Returns the weight consumed.
+	fn _settle_orders(now: T::BlockNumber) -> Weight {
+		let mut open = Self::open_orders();
+		if open.is_empty() {
+			return T::DbWeight::get().reads(1);
+		}
+		let count = open.len().min(T::MaxOrdersPerBlock::get() as usize);
+		let start = Self::order_cursor() as usize % open.len();
+		let closed: Vec<OrderId> = (0..count)
+			.map(|i| open[(start + i) % open.len()])
+			.filter(|id| !Self::_settle_order(*id, now))
+			.collect();
+
+		// Resume from the first order after the ones visited, counting only the orders kept
+		let next = (start + count) % open.len();
+		let mut cursor = 0u32;
+		let mut index = 0usize;
+		open.retain(|id| {
+			let keep = !closed.contains(id);
+			if keep && index < next {
+				cursor += 1;
+			}
+			index += 1;
+			keep
+		});
+		<OpenOrders>::put(open);
+		<OrderCursor>::put(cursor);
+		T::WeightInfo::settle_orders(count as u32)
+	}
+
+	/// Fill the limit order `id` as far as its pair meets the limit price, or refund it if it is
+	/// expired. Returns whether the order remains open.
+	fn _settle_order(id: OrderId, now: T::BlockNumber) -> bool {
+		let mut order = match Self::order(id) {
+			Some(order) => order,
+			None => return false,
+		};
+		if now > order.expiry {
+			// An order whose input cannot be released yet, such as a frozen asset, stays open to
+			// be refunded on a later visit
+			let closed = with_transaction(|| match Self::_close_order(id, &order) {
+				Ok(()) => TransactionOutcome::Commit(true),
+				Err(_) => TransactionOutcome::Rollback(false),
+			});
+			if closed {
+				Self::deposit_event(RawEvent::OrderExpired(id, order.remaining));
+			}
+			return !closed;
+		}
+		let amount_in = Self::_fillable_amount(&order);
+		if amount_in.is_zero() {
+			return true;
+		}
+		// A failed fill leaves the order and the pair untouched
+		let filled = with_transaction(|| match Self::_fill_order(&order, amount_in) {
+			Ok(amount_out) => TransactionOutcome::Commit(Some(amount_out)),
+			Err(_) => TransactionOutcome::Rollback(None),
+		});
+		if let Some(amount_out) = filled {
+			order.remaining -= amount_in;
+			order.filled_out = order.filled_out.saturating_add(amount_out);
+			Self::deposit_event(RawEvent::OrderFilled(id, amount_in, amount_out, order.remaining));
+			if order.remaining.is_zero() {
+				Self::_remove_order(id, &order.owner);
+				return false;
+			}
+			<Orders<T>>::insert(id, order);
+		}
+		true
+	}
+
+	/// Get the largest part of the remaining input of `order` which its pair swaps at no less
+	/// than the limit price of the order.
+	fn _fillable_amount(order: &LimitOrder<T::AccountId, AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>) -> BalanceOf<T> {
+		let (lpt, reserve_in, reserve_out) = match Self::get_reserves(&order.asset_in, &order.asset_out) {
+			Ok(reserves) => reserves,
+			Err(_) => return Zero::zero(),
+		};
+		let curve = Self::curve(lpt);
+		let fee = Self::fee(lpt);
+		let amount_in = U256::from(order.amount_in.saturated_into::<u128>());
+		let min_amount_out = U256::from(order.min_amount_out.saturated_into::<u128>());
+		// Selling `x` meets the limit if it pays at least `x * min_amount_out / amount_in`
+		let meets_limit = |x: u128| match Self::_get_amount_out(&curve, &x.saturated_into(), &reserve_in, &reserve_out, fee) {
+			Ok(out) => U256::from(out.saturated_into::<u128>()) * amount_in >= U256::from(x) * min_amount_out,
+			Err(_) => false,
+		};
+		let remaining = order.remaining.saturated_into::<u128>();
+		if meets_limit(remaining) {
+			return order.remaining;
+		}
+		// The average price only falls with the amount sold, so search the largest amount meeting
+		// the limit
+		let (mut low, mut high) = (0u128, remaining);
+		while high - low > 1 {
+			let mid = low + (high - low) / 2;
+			if meets_limit(mid) {
+				low = mid;
+			} else {
+				high = mid;
+			}
+		}
+		low.saturated_into()
+	}
+
+	/// Swap `amount_in` of the input of `order` held by the system through its pair, paying the
+	/// output to the owner of the order. Returns the amount paid.
+	fn _fill_order(
+		order: &LimitOrder<T::AccountId, AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>,
+		amount_in: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, dispatch::DispatchError> {
+		let pool = Self::account_id();
+		T::Assets::transfer_from_system(order.asset_in, &pool, amount_in)?;
+		let amount_out = Self::_swap_exact_in(&order.asset_in, &order.asset_out, &amount_in)?;
+		T::Assets::transfer(order.asset_out, &pool, &order.owner, amount_out)?;
+		Ok(amount_out)
+	}
+
+	/// Return the input of the limit order `id` not sold yet to its owner and remove the order.
+	fn _close_order(
+		id: OrderId,
+		order: &LimitOrder<T::AccountId, AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>,
+	) -> dispatch::DispatchResult {
+		if !order.remaining.is_zero() {
+			T::Assets::transfer_from_system(order.asset_in, &order.owner, order.remaining)?;
+		}
+		Self::_remove_order(id, &order.owner);
+		Ok(())
+	}
+
+	/// Remove the limit order `id` of `owner`, freeing one of the open order slots of `owner`.
+	fn _remove_order(id: OrderId, owner: &T::AccountId) {
+		<Orders<T>>::remove(id);
+		<OpenOrderCount<T>>::mutate_exists(owner, |count| {
+			*count = count.and_then(|count| count.checked_sub(1)).filter(|count| *count > 0);
+		});
+	}
+
+	fn _u256_to_balance(value: U256) -> Result<BalanceOf<T>, dispatch::DispatchError> {
+		ensure!(value <= U256::from(u128::max_value()), Error::<T>::Overflow);
+		let balance = BalanceOf::<T>::try_from(value.as_u128()).map_err(|_| Error::<T>::Overflow)?;
+		Ok(balance)
+	}
+
+	/// Get the liquidity provider token and the reserves of the pair between `from` and `to`,
+	/// ordered as `(lptoken, reserve_in, reserve_out)` for a swap from `from` to `to`. Fails if
+	/// either asset is frozen, so no swap routes through a frozen asset.
+	pub fn get_reserves(
+		from: &AssetIdOf<T>,
+		to: &AssetIdOf<T>,
+	) -> Result<(AssetIdOf<T>, BalanceOf<T>, BalanceOf<T>), dispatch::DispatchError> {
+		let lpt = Self::pair((*from, *to)).ok_or(Error::<T>::InvalidPair)?;
+		ensure!(!Self::flash_locked(lpt), Error::<T>::Locked);
+		T::Assets::ensure_transferable(*from)?;
+		T::Assets::ensure_transferable(*to)?;
+		let reserves = Self::reserves(lpt);
+		ensure!(reserves.0 > Zero::zero() && reserves.1 > Zero::zero(), Error::<T>::InsufficientLiquidity);
+		match *from > *to {
+			true => Ok((lpt, reserves.1, reserves.0)),
+			false => Ok((lpt, reserves.0, reserves.1)),
+		}
+	}
+
+	/// Get the liquidity provider token and the assets, ordered by identifier, of every pair.
+	pub fn pairs() -> Vec<(AssetIdOf<T>, (AssetIdOf<T>, AssetIdOf<T>))> {
+		<Rewards<T>>::iter().collect()
+	}
+
+	/// Get the amounts of each asset of a pair, ordered by identifier, redeemed by burning `amount`
+	/// of its liquidity provider token.
+	pub fn lp_share_value(
+		lpt: &AssetIdOf<T>,
+		amount: &BalanceOf<T>,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>), dispatch::DispatchError> {
+		ensure!(<Rewards<T>>::contains_key(lpt), Error::<T>::InvalidPair);
+		let reserves = Self::reserves(lpt);
+		let total_supply = T::Assets::total_issuance(*lpt);
+		ensure!(!total_supply.is_zero() && *amount <= total_supply, Error::<T>::InsufficientLiquidity);
+		let shares = Self::curve(lpt)
+			.liquidity_value(*amount, reserves, total_supply)
+			.map_err(Error::<T>::from)?;
+		Ok(shares)
+	}
+
+	/// Get the amount received at every step of swapping `amount_in` along `path`, starting with
+	/// `amount_in` itself.
+	pub fn get_amounts_out(
+		amount_in: &BalanceOf<T>,
+		path: &[AssetIdOf<T>],
+	) -> Result<Vec<BalanceOf<T>>, dispatch::DispatchError> {
+		ensure!(path.len() >= 2, Error::<T>::InvalidPath);
+		let mut amounts = Vec::with_capacity(path.len());
+		amounts.push(*amount_in);
+		for hop in path.windows(2) {
+			let (lpt, reserve_in, reserve_out) = Self::get_reserves(&hop[0], &hop[1])?;
+			let amount_out = Self::_get_amount_out(&Self::curve(lpt), &amounts[amounts.len() - 1], &reserve_in, &reserve_out, Self::fee(lpt))?;
+			amounts.push(amount_out);
+		}
+		Ok(amounts)
+	}
+
+	fn ensure_deadline(deadline: T::Moment) -> dispatch::DispatchResult {
+		ensure!(<timestamp::Module<T>>::get() <= deadline, Error::<T>::Expired);
+		Ok(())
+	}
+
+	/// Get the amount required at every step of swapping along `path` to receive `amount_out`,
+	/// ending with `amount_out` itself.
+	pub fn get_amounts_in(
+		amount_out: &BalanceOf<T>,
+		path: &[AssetIdOf<T>],
+	) -> Result<Vec<BalanceOf<T>>, dispatch::DispatchError> {
+		ensure!(path.len() >= 2, Error::<T>::InvalidPath);
+		let mut amounts = vec![Zero::zero(); path.len()];
+		amounts[path.len() - 1] = *amount_out;
+		for i in (1..path.len()).rev() {
+			let (lpt, reserve_in, reserve_out) = Self::get_reserves(&path[i - 1], &path[i])?;
+			amounts[i - 1] = Self::_get_amount_in(&Self::curve(lpt), &amounts[i], &reserve_in, &reserve_out, Self::fee(lpt))?;
+		}
+		Ok(amounts)
+	}
+
+	/// Swap as little of `from` of `who` as possible for exactly `amount_out` of `to`, which is
+	/// left in the pool account for the caller to take out, such as a native transaction fee
+	/// withdrawn from the pool account. Fails if the required input exceeds `max_amount_in`.
+	/// Returns the input swapped.
+	#[transactional]
+	pub fn swap_into_pool(
+		who: &T::AccountId,
+		from: AssetIdOf<T>,
+		to: AssetIdOf<T>,
+		amount_out: BalanceOf<T>,
+		max_amount_in: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, dispatch::DispatchError> {
+		let amounts = Self::get_amounts_in(&amount_out, &[from, to])?;
+		ensure!(amounts[0] <= max_amount_in, Error::<T>::ExcessiveInputAmount);
+		T::Assets::transfer(from, who, &Self::account_id(), amounts[0])?;
+		Self::_swap(&from, &to, &amounts[0], &amount_out)?;
+		Ok(amounts[0])
+	}
+
+	/// Swap `amount_in` of `from`, put into the pool account by the caller, such as a refund of
+	/// a native transaction fee, for as much of `to` as the pair gives, paid to `who`. Returns the
+	/// output paid.
+	#[transactional]
+	pub fn swap_out_of_pool(
+		who: &T::AccountId,
+		from: AssetIdOf<T>,
+		to: AssetIdOf<T>,
+		amount_in: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, dispatch::DispatchError> {
+		let amount_out = Self::_swap_exact_in(&from, &to, &amount_in)?;
+		T::Assets::transfer(to, &Self::account_id(), who, amount_out)?;
+		Ok(amount_out)
+	}
+
+	/// Swap `amount_in` of `from`, already deposited to the pool account, to as much of `to` as
+	/// the pair gives. Returns the amount of `to` which the pool account owes to the trader.
+	fn _swap_exact_in(
+		from: &AssetIdOf<T>,
+		to: &AssetIdOf<T>,
+		amount_in: &BalanceOf<T>,
+	) -> Result<BalanceOf<T>, dispatch::DispatchError> {
+		let (lpt, reserve_in, reserve_out) = Self::get_reserves(from, to)?;
+		// get amount out
+		let amount_out = Self::_get_amount_out(&Self::curve(lpt), amount_in, &reserve_in, &reserve_out, Self::fee(lpt))?;
+		Self::_swap(from, to, amount_in, &amount_out)?;
+		Ok(amount_out)
+	}
+
+	/// Swap `amount_in` of `from`, already deposited to the pool account, to `amount_out` of `to`
+	/// and update the reserves of the pair. Fails if the pair gives less than `amount_out`.
+	fn _swap(
+		from: &AssetIdOf<T>,
+		to: &AssetIdOf<T>,
+		amount_in: &BalanceOf<T>,
+		amount_out: &BalanceOf<T>,
+	) -> dispatch::DispatchResult {
+		let (lpt, reserve_in, reserve_out) = Self::get_reserves(from, to)?;
+		ensure!(*amount_out > Zero::zero(), Error::<T>::InsufficientOutputAmount);
+		let amount_out_max = Self::_get_amount_out(&Self::curve(lpt), amount_in, &reserve_in, &reserve_out, Self::fee(lpt))?;
+		ensure!(*amount_out <= amount_out_max, Error::<T>::K);
+		let reserve_in = reserve_in.checked_add(amount_in).ok_or(Error::<T>::Overflow)?;
+		// update reserves
+		Self::_set_reserves(from, to, &reserve_in, &(reserve_out - *amount_out), &lpt);
+		Self::deposit_event(RawEvent::Swap(*from, *amount_in, *to, *amount_out));
+		Ok(())
+	}
+
+	/// Get the output amount of swapping `amount_in` with a pair on `curve`.
 	pub fn _get_amount_out(
-        amount_in: &<T as balances::Trait>::Balance,
-        reserve_in: &<T as balances::Trait>::Balance,
-        reserve_out: &<T as balances::Trait>::Balance,
-    ) -> <T as balances::Trait>::Balance {
-        let amount_in_with_fee = amount_in
-            .checked_mul(&T::Balance::from(997))
-            .expect("Multiplication overflow");
-        let numerator = amount_in_with_fee
-            .checked_mul(reserve_out)
-            .expect("Multiplication overflow");
-        let denominator = reserve_in
-            .checked_mul(&T::Balance::from(1000))
-            .expect("Multiplication overflow")
-            .checked_add(&amount_in_with_fee)
-            .expect("Overflow");
-        numerator.checked_div(&denominator).expect("divided by zero")
-    }
-	
-
-	// TODO: Reimplement TWAP so that checked calculation does not lose values
-	fn _update(pair: &T::AssetId) -> dispatch::DispatchResult {
-        let block_timestamp = <timestamp::Module<T>>::get() % T::Moment::from(2u32.pow(32));
-        let time_elapsed = block_timestamp - Self::last_block_timestamp();
-        let reserves = Self::reserves(pair);
-        if time_elapsed > Zero::zero() && reserves.0 != Zero::zero() && reserves.1 != Zero::zero() {
-            let reserve0 = FixedU128::saturating_from_integer(reserves.0.saturated_into());
-            let reserve1 = FixedU128::saturating_from_integer(reserves.1.saturated_into());
-            let price0_cumulative_last = reserve1.checked_div(&reserve0).unwrap()
-                * FixedU128::saturating_from_integer(time_elapsed.saturated_into());
-            let price1_cumulative_last = reserve0.checked_div(&reserve1).unwrap()
-                * FixedU128::saturating_from_integer(time_elapsed.saturated_into());
-            <LastAccumulativePrice<T>>::insert(
-                &pair,
-                (price0_cumulative_last.clone(), price1_cumulative_last.clone()),
-            );
-            <LastBlockTimestamp<T>>::put(block_timestamp);
-            Self::deposit_event(RawEvent::SyncOracle(
-                price0_cumulative_last,
-                price1_cumulative_last,
-            ));
-        }
-        Ok(())
-    }
-}
+		curve: &CurveType,
+		amount_in: &BalanceOf<T>,
+		reserve_in: &BalanceOf<T>,
+		reserve_out: &BalanceOf<T>,
+		fee: u32,
+	) -> Result<BalanceOf<T>, dispatch::DispatchError> {
+		let amount_out = curve
+			.amount_out(*amount_in, *reserve_in, *reserve_out, fee)
+			.map_err(Error::<T>::from)?;
+		Ok(amount_out)
+	}
 
+	/// Get the input amount required to swap for `amount_out` with a pair on `curve`.
+	pub fn _get_amount_in(
+		curve: &CurveType,
+		amount_out: &BalanceOf<T>,
+		reserve_in: &BalanceOf<T>,
+		reserve_out: &BalanceOf<T>,
+		fee: u32,
+	) -> Result<BalanceOf<T>, dispatch::DispatchError> {
+		let amount_in = curve
+			.amount_in(*amount_out, *reserve_in, *reserve_out, fee)
+			.map_err(Error::<T>::from)?;
+		Ok(amount_in)
+	}
 
+	/// Get the spot prices of a pair as `(price0, price1)`, where `price0` is the price of
+	/// `token0` in units of `token1` scaled by `FixedU128::accuracy()`.
+	fn spot_prices(reserves: &(BalanceOf<T>, BalanceOf<T>)) -> (U256, U256) {
+		let reserve0 = U256::from(reserves.0.saturated_into::<u128>());
+		let reserve1 = U256::from(reserves.1.saturated_into::<u128>());
+		let accuracy = U256::from(FixedU128::accuracy());
+		(reserve1 * accuracy / reserve0, reserve0 * accuracy / reserve1)
+	}
 
+	/// Get the accumulated prices of a pair as of the current timestamp, counting the time
+	/// elapsed since the last reserve update at the current spot prices.
+	pub fn current_cumulative_prices(lpt: &AssetIdOf<T>) -> (U256, U256) {
+		let (price0_cumulative, price1_cumulative) = Self::last_cumulative_price(lpt);
+		let now = <timestamp::Module<T>>::get();
+		let last = Self::last_block_timestamp(lpt);
+		let reserves = Self::reserves(lpt);
+		if now <= last || reserves.0.is_zero() || reserves.1.is_zero() {
+			return (price0_cumulative, price1_cumulative);
+		}
+		let time_elapsed = U256::from((now - last).saturated_into::<u128>());
+		let (price0, price1) = Self::spot_prices(&reserves);
+		(
+			price0_cumulative.overflowing_add(price0.saturating_mul(time_elapsed)).0,
+			price1_cumulative.overflowing_add(price1.saturating_mul(time_elapsed)).0,
+		)
+	}
+
+	/// Get the time weighted average prices of a pair over at least `window`, as
+	/// `(price0, price1)` where `price0` is the price of `token0` in units of `token1`.
+	///
+	/// The average is taken from the latest snapshot older than `window` until now. Returns
+	/// `None` if there is no such snapshot yet, as for a `window` longer than the timestamp range.
+	pub fn consult(lpt: &AssetIdOf<T>, window: T::Moment) -> Option<(FixedU128, FixedU128)> {
+		let now = <timestamp::Module<T>>::get();
+		let snapshot = Self::price_snapshots(lpt)
+			.into_iter()
+			.rev()
+			.find(|snapshot| snapshot.timestamp.checked_add(&window).map_or(false, |end| end <= now))?;
+		let time_elapsed = now - snapshot.timestamp;
+		if time_elapsed.is_zero() {
+			return None;
+		}
+		let time_elapsed = U256::from(time_elapsed.saturated_into::<u128>());
+		let (price0_cumulative, price1_cumulative) = Self::current_cumulative_prices(lpt);
+		// Accumulators wrap on overflow, so only their difference is meaningful
+		let average = |cumulative: U256, last: U256| {
+			let inner = cumulative.overflowing_sub(last).0 / time_elapsed;
+			FixedU128::from_inner(inner.min(U256::from(u128::max_value())).as_u128())
+		};
+		Some((
+			average(price0_cumulative, snapshot.price0_cumulative),
+			average(price1_cumulative, snapshot.price1_cumulative),
+		))
+	}
+
+	/// Accumulate the prices of a pair for the time elapsed since its last update and take a
+	/// snapshot once `OracleSnapshotPeriod` has passed since the previous one.
+	///
+	/// Must be called before the reserves of the pair change.
+	fn _update(lpt: &AssetIdOf<T>) {
+		let now = <timestamp::Module<T>>::get();
+		let reserves = Self::reserves(lpt);
+		if now > Self::last_block_timestamp(lpt) && !reserves.0.is_zero() && !reserves.1.is_zero() {
+			let (price0_cumulative, price1_cumulative) = Self::current_cumulative_prices(lpt);
+			<LastAccumulativePrice<T>>::insert(lpt, (price0_cumulative, price1_cumulative));
+			Self::deposit_event(RawEvent::SyncOracle(*lpt, price0_cumulative, price1_cumulative));
+		}
+		<LastBlockTimestamp<T>>::insert(lpt, now);
+
+		<PriceSnapshots<T>>::mutate(lpt, |snapshots| {
+			let period = T::OracleSnapshotPeriod::get();
+			let due = snapshots.last()
+				.map_or(true, |last| last.timestamp.checked_add(&period).map_or(false, |next| next <= now));
+			if due {
+				let (price0_cumulative, price1_cumulative) = Self::last_cumulative_price(lpt);
+				snapshots.push(PriceSnapshot { timestamp: now, price0_cumulative, price1_cumulative });
+				let max = T::OracleSnapshotCount::get() as usize;
+				if snapshots.len() > max {
+					let excess = snapshots.len() - max;
+					snapshots.drain(..excess);
+				}
+			}
+		});
+	}
+}
@@ -1,44 +1,302 @@
-use crate::Trait;
-use crate::balances;
-pub fn sqrt<T: Trait>(y: <T as balances::Trait>::Balance) -> <T as balances::Trait>::Balance {
-    if y > <T as balances::Trait>::Balance::from(3) {
-        let mut z = y;
-        let mut x: <T as balances::Trait>::Balance = y / <T as balances::Trait>::Balance::from(2);
-        x += <T as balances::Trait>::Balance::from(1);
-        while x < z {
-            z = x;
-            x = (y / x + x) / <T as balances::Trait>::Balance::from(2);
-        }
-        z
-    } else if y != <T as balances::Trait>::Balance::from(0) {
-        let z = <T as balances::Trait>::Balance::from(1);
-        z
-    } else {
-        y
-    }
-}
-
-pub fn min<T: Trait>(
-    x: <T as balances::Trait>::Balance,
-    y: <T as balances::Trait>::Balance,
-) -> <T as balances::Trait>::Balance {
-    let z = match x < y {
-        true => x,
-        _ => y,
-    };
-    z
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Arithmetic of the constant product pairs.
+//!
+//! Products of balances are computed in 256 bits, so they never overflow for any pair of
+//! `u128` balances, and only results which do not fit in a balance fail. No function panics.
+
+use sp_core::{U256, U512};
+use sp_runtime::RuntimeDebug;
+use sp_runtime::traits::AtLeast32BitUnsigned;
+use sp_std::convert::{TryFrom, TryInto};
+use crate::FEE_DENOMINATOR;
+
+/// An error of the arithmetic of a pair.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum MathError {
+	/// The result does not fit in a balance
+	Overflow,
+	/// A divisor is zero, such as a reserve of an empty pair
+	DivisionByZero,
+	/// The pair cannot give the requested output
+	InsufficientLiquidity,
+	/// The fee is not below `FEE_DENOMINATOR`
+	InvalidFee,
+	/// An iterative solution did not converge
+	NotConverged,
+}
+
+/// The result of the arithmetic of a pair.
+pub type MathResult<T> = Result<T, MathError>;
+
+/// Widen a balance to 256 bits.
+pub fn to_u256<B: TryInto<u128>>(balance: B) -> MathResult<U256> {
+	balance.try_into().map(U256::from).map_err(|_| MathError::Overflow)
+}
+
+/// Narrow a 256 bit value to a balance.
+pub fn from_u256<B: TryFrom<u128>>(value: U256) -> MathResult<B> {
+	if value > U256::from(u128::max_value()) {
+		return Err(MathError::Overflow);
+	}
+	B::try_from(value.low_u128()).map_err(|_| MathError::Overflow)
+}
+
+/// `a * b / c` rounded down.
+pub fn mul_div<B: AtLeast32BitUnsigned + Copy>(a: B, b: B, c: B) -> MathResult<B> {
+	let c = non_zero(to_u256(c)?)?;
+	from_u256(to_u256(a)?.full_mul(to_u256(b)?).checked_div(c.into()).ok_or(MathError::DivisionByZero)?
+		.try_into().map_err(|_| MathError::Overflow)?)
+}
+
+/// `a * b / c` rounded up.
+pub fn mul_div_up<B: AtLeast32BitUnsigned + Copy>(a: B, b: B, c: B) -> MathResult<B> {
+	let c = non_zero(to_u256(c)?)?;
+	let product = to_u256(a)?.full_mul(to_u256(b)?);
+	let (quotient, remainder) = product.div_mod(c.into());
+	let quotient: U256 = quotient.try_into().map_err(|_| MathError::Overflow)?;
+	match remainder.is_zero() {
+		true => from_u256(quotient),
+		false => from_u256(quotient.checked_add(U256::one()).ok_or(MathError::Overflow)?),
+	}
+}
+
+/// The square root of `a * b` rounded down, which always fits in a balance.
+pub fn geometric_mean<B: AtLeast32BitUnsigned + Copy>(a: B, b: B) -> MathResult<B> {
+	// The product of two `u128` fits in 256 bits
+	from_u256(sqrt_u256(to_u256(a)?.saturating_mul(to_u256(b)?)))
+}
+
+/// The output amount of swapping `amount_in` with a pair of `reserve_in` and `reserve_out`,
+/// charging `fee` basis points of the input, rounded down. Fails if either reserve is empty.
+pub fn get_amount_out<B: AtLeast32BitUnsigned + Copy>(
+	amount_in: B,
+	reserve_in: B,
+	reserve_out: B,
+	fee: u32,
+) -> MathResult<B> {
+	if reserve_in.is_zero() || reserve_out.is_zero() {
+		return Err(MathError::InsufficientLiquidity);
+	}
+	let fee_complement = FEE_DENOMINATOR.checked_sub(fee).ok_or(MathError::InvalidFee)?;
+	// Every term is below 2^128 * 2^14, so the sums and products below fit in 256 bits
+	let amount_in_with_fee = to_u256(amount_in)? * U256::from(fee_complement);
+	let numerator = amount_in_with_fee.full_mul(to_u256(reserve_out)?);
+	let denominator = non_zero(to_u256(reserve_in)? * U256::from(FEE_DENOMINATOR) + amount_in_with_fee)?;
+	from_u256((numerator / denominator).try_into().map_err(|_| MathError::Overflow)?)
+}
+
+/// The input amount required to swap for `amount_out` with a pair of `reserve_in` and
+/// `reserve_out`, charging `fee` basis points of the input, rounded up so that the pair never
+/// receives less than required.
+pub fn get_amount_in<B: AtLeast32BitUnsigned + Copy>(
+	amount_out: B,
+	reserve_in: B,
+	reserve_out: B,
+	fee: u32,
+) -> MathResult<B> {
+	if reserve_in.is_zero() || amount_out >= reserve_out {
+		return Err(MathError::InsufficientLiquidity);
+	}
+	let fee_complement = FEE_DENOMINATOR.checked_sub(fee).ok_or(MathError::InvalidFee)?;
+	let numerator = (to_u256(reserve_in)? * U256::from(FEE_DENOMINATOR)).full_mul(to_u256(amount_out)?);
+	let denominator = non_zero(to_u256(reserve_out - amount_out)? * U256::from(fee_complement))?;
+	let amount_in: U256 = (numerator / denominator).try_into().map_err(|_| MathError::Overflow)?;
+	from_u256(amount_in.checked_add(U256::one()).ok_or(MathError::Overflow)?)
+}
+
+/// The amount of the other asset of a pair of `reserve_a` and `reserve_b` worth `amount_a`,
+/// rounded down.
+pub fn quote<B: AtLeast32BitUnsigned + Copy>(amount_a: B, reserve_a: B, reserve_b: B) -> MathResult<B> {
+	mul_div(amount_a, reserve_b, reserve_a)
+}
+
+/// The liquidity provider token minted for depositing `amounts` to a pair of `reserves` with
+/// `total_supply` of liquidity provider token, rounded down. A `total_supply` of zero creates
+/// the pair, minting the geometric mean of the deposits.
+pub fn liquidity_minted<B: AtLeast32BitUnsigned + Copy>(
+	amounts: (B, B),
+	reserves: (B, B),
+	total_supply: B,
+) -> MathResult<B> {
+	if total_supply.is_zero() {
+		return geometric_mean(amounts.0, amounts.1);
+	}
+	let left = mul_div(amounts.0, total_supply, reserves.0)?;
+	let right = mul_div(amounts.1, total_supply, reserves.1)?;
+	Ok(min(left, right))
+}
+
+/// The amounts redeemed by burning `amount` of the `total_supply` of liquidity provider token of
+/// a pair of `reserves`, pro-rata and rounded down.
+pub fn liquidity_value<B: AtLeast32BitUnsigned + Copy>(
+	amount: B,
+	reserves: (B, B),
+	total_supply: B,
+) -> MathResult<(B, B)> {
+	if amount > total_supply {
+		return Err(MathError::InsufficientLiquidity);
+	}
+	Ok((mul_div(amount, reserves.0, total_supply)?, mul_div(amount, reserves.1, total_supply)?))
+}
+
+/// The part of `amount_in` to swap with a pair of `reserve_in` so that the rest and the output
+/// of the swap are in the ratio of the reserves after the swap, charging `fee` basis points of
+/// the input, rounded down. With `f` the fee as a fraction, `a` the amount and `r` the reserve,
+/// this is the positive root `(sqrt(((2 - f) * r)^2 + 4 * (1 - f) * a * r) - (2 - f) * r) / (2 * (1 - f))`.
+pub fn optimal_swap_amount<B: AtLeast32BitUnsigned + Copy>(amount_in: B, reserve_in: B, fee: u32) -> MathResult<B> {
+	if reserve_in.is_zero() {
+		return Err(MathError::InsufficientLiquidity);
+	}
+	let fee_complement = match FEE_DENOMINATOR.checked_sub(fee) {
+		Some(complement) if complement > 0 => complement,
+		_ => return Err(MathError::InvalidFee),
+	};
+	let (amount_in, reserve_in) = (to_u256(amount_in)?, to_u256(reserve_in)?);
+	// Scaled by `FEE_DENOMINATOR`, `b` is below 2^143 and `c` below 2^157, so the discriminant
+	// fits in 512 bits and its root in 256 bits
+	let b = reserve_in * U256::from(2 * FEE_DENOMINATOR - fee);
+	let c = amount_in * U256::from(4 * FEE_DENOMINATOR as u64 * fee_complement as u64);
+	let discriminant = b.full_mul(b) + c.full_mul(reserve_in);
+	let root: U256 = sqrt_u512(discriminant).try_into().map_err(|_| MathError::Overflow)?;
+	from_u256((root - b) / U256::from(2 * fee_complement))
+}
+
+fn non_zero(value: U256) -> MathResult<U256> {
+	match value.is_zero() {
+		true => Err(MathError::DivisionByZero),
+		false => Ok(value),
+	}
+}
+
+pub fn sqrt<B: AtLeast32BitUnsigned + Copy>(y: B) -> B {
+	if y > B::from(3u32) {
+		let mut z = y;
+		let mut x: B = y / B::from(2u32);
+		x += B::from(1u32);
+		while x < z {
+			z = x;
+			x = (y / x + x) / B::from(2u32);
+		}
+		z
+	} else if y != B::from(0u32) {
+		let z = B::from(1u32);
+		z
+	} else {
+		y
+	}
+}
+
+pub fn sqrt_u256(y: U256) -> U256 {
+	if y > U256::from(3u32) {
+		let mut z = y;
+		// Halve first so that `y / 2 + 1` cannot overflow
+		let mut x = y / U256::from(2u32) + U256::from(1u32);
+		while x < z {
+			z = x;
+			x = (y / x + x) / U256::from(2u32);
+		}
+		z
+	} else if !y.is_zero() {
+		U256::from(1u32)
+	} else {
+		y
+	}
+}
+
+pub fn sqrt_u512(y: U512) -> U512 {
+	if y.is_zero() {
+		return y;
+	}
+	// Start from a power of two above the root, from which Newton's method only decreases
+	let mut z = U512::one() << ((y.bits() + 1) / 2);
+	loop {
+		let x = (y / z + z) >> 1;
+		if x >= z {
+			return z;
+		}
+		z = x;
+	}
+}
+
+pub fn min<B: AtLeast32BitUnsigned + Copy>(x: B, y: B) -> B {
+	let z = match x < y {
+		true => x,
+		_ => y,
+	};
+	z
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-    #[test]
-    fn sqrt_works() {
-        assert_eq!(2, sqrt(4));
-    }
-
-    #[test]
-    fn min_works() {
-        assert_eq!(1, min(1, 3));
-    }
+	use super::*;
+	#[test]
+	fn sqrt_works() {
+		assert_eq!(2, sqrt(4u128));
+	}
+
+	#[test]
+	fn sqrt_u256_works() {
+		assert_eq!(U256::from(2u32), sqrt_u256(U256::from(4u32)));
+		assert_eq!(U256::from(u128::max_value()), sqrt_u256(U256::MAX));
+	}
+
+	#[test]
+	fn sqrt_u512_works() {
+		assert_eq!(U512::from(2u32), sqrt_u512(U512::from(4u32)));
+		assert_eq!(U512::from(3u32), sqrt_u512(U512::from(15u32)));
+		assert_eq!(U512::from(U256::MAX), sqrt_u512(U512::MAX));
+	}
+
+	#[test]
+	fn optimal_swap_amount_deposits_rest_in_ratio() {
+		assert_eq!(optimal_swap_amount(10_000u128, 1_000_000, 30), Ok(4_995));
+		assert_eq!(get_amount_out(4_995u128, 1_000_000, 4_000_000, 30), Ok(19_821));
+		// The rest 5_005 and 19_821 are in the ratio of the reserves 1_004_995 and 3_980_179
+		assert_eq!(quote(5_005u128, 1_004_995, 3_980_179), Ok(19_821));
+		assert_eq!(optimal_swap_amount(u128::max_value(), u128::max_value(), 30).map(|_| ()), Ok(()));
+		assert_eq!(optimal_swap_amount(1u128, 0, 30), Err(MathError::InsufficientLiquidity));
+		assert_eq!(optimal_swap_amount(1u128, 1, FEE_DENOMINATOR), Err(MathError::InvalidFee));
+	}
+
+	#[test]
+	fn min_works() {
+		assert_eq!(1, min(1u128, 3));
+	}
+
+	#[test]
+	fn mul_div_uses_wide_products() {
+		let max = u128::max_value();
+		assert_eq!(mul_div(max, max, max), Ok(max));
+		assert_eq!(mul_div_up(7u128, 3, 2), Ok(11));
+		assert_eq!(mul_div(max, 2, 1), Err(MathError::Overflow));
+		assert_eq!(mul_div(1u128, 1, 0), Err(MathError::DivisionByZero));
+	}
+
+	#[test]
+	fn amounts_of_eighteen_decimal_reserves_do_not_overflow() {
+		let unit = 1_000_000_000_000_000_000u128;
+		let (reserve_in, reserve_out) = (1_000_000_000 * unit, 4_000_000_000 * unit);
+		let amount_out = get_amount_out(1_000_000 * unit, reserve_in, reserve_out, 30).unwrap();
+		assert_eq!(amount_out, 3_984_027_924_159_612_865_972_625);
+		assert!(get_amount_in(amount_out, reserve_in, reserve_out, 30).unwrap() <= 1_000_000 * unit);
+		assert_eq!(geometric_mean(reserve_in, reserve_out), Ok(2_000_000_000 * unit));
+		assert_eq!(get_amount_in(reserve_out, reserve_in, reserve_out, 30), Err(MathError::InsufficientLiquidity));
+		assert_eq!(get_amount_out(1u128, 1, 1, FEE_DENOMINATOR + 1), Err(MathError::InvalidFee));
+	}
 }
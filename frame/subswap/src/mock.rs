@@ -1,13 +1,23 @@
 use crate::{Module, Trait};
+use codec::Encode;
 use sp_core::H256;
-use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use frame_support::{impl_outer_origin, impl_outer_dispatch, parameter_types, weights::Weight, storage::unhashed};
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill, ModuleId, DispatchError,
 };
 use frame_system as system;
+use crate as subswap;
+use subswap_asset::MultiAsset;
 
 impl_outer_origin! {
-	pub enum Origin for Test {}
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		frame_system::System,
+		subswap::Subswap,
+	}
 }
 
 // Configure a mock runtime to test the pallet.
@@ -24,7 +34,7 @@ parameter_types! {
 impl system::Trait for Test {
 	type BaseCallFilter = ();
 	type Origin = Origin;
-	type Call = ();
+	type Call = Call;
 	type Index = u64;
 	type BlockNumber = u64;
 	type Hash = H256;
@@ -49,13 +59,227 @@ impl system::Trait for Test {
 	type SystemWeightInfo = ();
 }
 
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+/// A minimal asset ledger kept in unhashed storage, so the market is tested on its own.
+pub struct MockAssets;
+
+impl MockAssets {
+	fn balance_key(id: u32, who: &u64) -> Vec<u8> {
+		(&b"balance"[..], id, who).encode()
+	}
+
+	fn reserved_key(id: u32, who: &u64) -> Vec<u8> {
+		(&b"reserved"[..], id, who).encode()
+	}
+
+	fn issuance_key(id: u32) -> Vec<u8> {
+		(&b"issuance"[..], id).encode()
+	}
+
+	fn metadata_key(id: u32) -> Vec<u8> {
+		(&b"metadata"[..], id).encode()
+	}
+
+	fn frozen_key(id: u32) -> Vec<u8> {
+		(&b"frozen"[..], id).encode()
+	}
+
+	fn set_balance(id: u32, who: &u64, amount: u128) {
+		unhashed::put(&Self::balance_key(id, who), &amount);
+	}
+
+	/// Freeze asset `id` as a whole.
+	pub fn freeze_asset(id: u32) {
+		unhashed::put(&Self::frozen_key(id), &true);
+	}
+
+	/// Thaw asset `id` frozen as a whole.
+	pub fn thaw_asset(id: u32) {
+		unhashed::kill(&Self::frozen_key(id));
+	}
+
+	/// The name, symbol and decimals of asset `id`.
+	pub fn metadata(id: u32) -> (Vec<u8>, Vec<u8>, u8) {
+		unhashed::get_or_default(&Self::metadata_key(id))
+	}
+}
+
+impl MultiAsset<u64> for MockAssets {
+	type AssetId = u32;
+	type Balance = u128;
+
+	fn balance(id: u32, who: &u64) -> u128 {
+		unhashed::get_or_default(&Self::balance_key(id, who))
+	}
+
+	fn reserved_balance(id: u32, who: &u64) -> u128 {
+		unhashed::get_or_default(&Self::reserved_key(id, who))
+	}
+
+	fn total_issuance(id: u32) -> u128 {
+		unhashed::get_or_default(&Self::issuance_key(id))
+	}
+
+	fn issue_from_system(total: u128) -> Result<u32, DispatchError> {
+		let id = unhashed::get_or(b"next_asset_id", 1u32);
+		unhashed::put(b"next_asset_id", &(id + 1));
+		unhashed::put(&Self::issuance_key(id), &total);
+		Ok(id)
+	}
+
+	fn transfer(id: u32, from: &u64, to: &u64, amount: u128) -> Result<(), DispatchError> {
+		Self::ensure_transferable(id)?;
+		let from_balance = Self::balance(id, from);
+		if amount == 0 || from_balance < amount {
+			return Err("BalanceLow".into());
+		}
+		Self::set_balance(id, from, from_balance - amount);
+		Self::set_balance(id, to, Self::balance(id, to) + amount);
+		Ok(())
+	}
+
+	fn mint_into(id: u32, who: &u64, amount: u128) -> Result<(), DispatchError> {
+		Self::set_balance(id, who, Self::balance(id, who) + amount);
+		unhashed::put(&Self::issuance_key(id), &(Self::total_issuance(id) + amount));
+		Ok(())
+	}
+
+	fn burn_from(id: u32, who: &u64, amount: u128) -> Result<(), DispatchError> {
+		let balance = Self::balance(id, who);
+		if balance < amount {
+			return Err("BalanceLow".into());
+		}
+		Self::set_balance(id, who, balance - amount);
+		unhashed::put(&Self::issuance_key(id), &(Self::total_issuance(id) - amount));
+		Ok(())
+	}
+
+	fn transfer_to_system(id: u32, who: &u64, amount: u128) -> Result<(), DispatchError> {
+		let balance = Self::balance(id, who);
+		if amount == 0 || balance < amount {
+			return Err("BalanceLow".into());
+		}
+		Self::set_balance(id, who, balance - amount);
+		Ok(())
+	}
+
+	fn transfer_from_system(id: u32, who: &u64, amount: u128) -> Result<(), DispatchError> {
+		Self::ensure_transferable(id)?;
+		Self::set_balance(id, who, Self::balance(id, who) + amount);
+		Ok(())
+	}
+
+	fn reserve(id: u32, who: &u64, amount: u128) -> Result<(), DispatchError> {
+		let balance = Self::balance(id, who);
+		if balance < amount {
+			return Err("BalanceLow".into());
+		}
+		Self::set_balance(id, who, balance - amount);
+		unhashed::put(&Self::reserved_key(id, who), &(Self::reserved_balance(id, who) + amount));
+		Ok(())
+	}
+
+	fn unreserve(id: u32, who: &u64, amount: u128) -> u128 {
+		let reserved = Self::reserved_balance(id, who);
+		let actual = amount.min(reserved);
+		unhashed::put(&Self::reserved_key(id, who), &(reserved - actual));
+		Self::set_balance(id, who, Self::balance(id, who) + actual);
+		amount - actual
+	}
+
+	fn repatriate_reserved(id: u32, source: &u64, dest: &u64, amount: u128) -> Result<u128, DispatchError> {
+		let reserved = Self::reserved_balance(id, source);
+		let actual = amount.min(reserved);
+		unhashed::put(&Self::reserved_key(id, source), &(reserved - actual));
+		Self::set_balance(id, dest, Self::balance(id, dest) + actual);
+		Ok(amount - actual)
+	}
+
+	fn ensure_transferable(id: u32) -> Result<(), DispatchError> {
+		if unhashed::get_or_default(&Self::frozen_key(id)) {
+			return Err("AssetFrozen".into());
+		}
+		Ok(())
+	}
+
+	fn symbol(id: u32) -> Vec<u8> {
+		Self::metadata(id).1
+	}
+
+	fn decimals(id: u32) -> u8 {
+		Self::metadata(id).2
+	}
+
+	fn set_system_metadata(id: u32, name: Vec<u8>, symbol: Vec<u8>, decimals: u8) -> Result<(), DispatchError> {
+		unhashed::put(&Self::metadata_key(id), &(name, symbol, decimals));
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub const SubswapModuleId: ModuleId = ModuleId(*b"py/subsw");
+	pub const OracleSnapshotPeriod: u64 = 500;
+	pub const OracleSnapshotCount: u32 = 3;
+	pub const DefaultFee: u32 = 30;
+	pub const MaxOpenOrders: u32 = 3;
+	pub const MaxOrdersPerAccount: u32 = 2;
+	pub const MaxOrdersPerBlock: u32 = 2;
+	pub const MaxInitializedTicks: u32 = 4;
+}
+
 impl Trait for Test {
 	type Event = ();
+	type Assets = MockAssets;
+	type ModuleId = SubswapModuleId;
+	type OracleSnapshotPeriod = OracleSnapshotPeriod;
+	type OracleSnapshotCount = OracleSnapshotCount;
+	type DefaultFee = DefaultFee;
+	type GovernanceOrigin = frame_system::EnsureRoot<u64>;
+	type Call = Call;
+	type MaxOpenOrders = MaxOpenOrders;
+	type MaxOrdersPerAccount = MaxOrdersPerAccount;
+	type MaxOrdersPerBlock = MaxOrdersPerBlock;
+	type MaxInitializedTicks = MaxInitializedTicks;
+	type WeightInfo = ();
 }
 
-pub type TemplateModule = Module<Test>;
+pub type Subswap = Module<Test>;
+pub type System = system::Module<Test>;
+pub type Timestamp = pallet_timestamp::Module<Test>;
+pub type Assets = MockAssets;
+
+pub const NATIVE: u32 = 0;
+pub const USDT: u32 = 1;
+pub const DOT: u32 = 2;
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut ext: sp_io::TestExternalities = system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into();
+	ext.execute_with(|| {
+		// Issue two assets next to the native currency and fund the test accounts.
+		assert_eq!(Assets::issue_from_system(0), Ok(USDT));
+		assert_eq!(Assets::issue_from_system(0), Ok(DOT));
+		Assets::set_system_metadata(NATIVE, b"Subswap".to_vec(), b"SUB".to_vec(), 12).unwrap();
+		Assets::set_system_metadata(USDT, b"Tether".to_vec(), b"USDT".to_vec(), 6).unwrap();
+		Assets::set_system_metadata(DOT, b"Polkadot".to_vec(), b"DOT".to_vec(), 10).unwrap();
+		for who in &[1u64, 2, 3] {
+			for id in &[NATIVE, USDT, DOT] {
+				Assets::mint_into(*id, who, 1_000_000_000).unwrap();
+			}
+		}
+	});
+	ext
 }
@@ -1,168 +1,1067 @@
-use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
-
-#[test]
-fn it_works_for_default_value() {
-	new_test_ext().execute_with(|| {
-		// Dispatch a signed extrinsic.
-		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
-		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
-	});
-}
-
-#[test]
-fn correct_error_for_none_value() {
-	new_test_ext().execute_with(|| {
-		// Ensure the expected error is thrown when no value is present.
-		assert_noop!(
-			TemplateModule::cause_error(Origin::signed(1)),
-			Error::<Test>::NoneValue
-		);
-	});
-}
-
-#[cfg(test)]
-mod tests {
-	use super::*;
-
-	use frame_support::{impl_outer_origin, assert_ok, assert_noop, parameter_types, weights::Weight};
-	use sp_core::H256;
-	use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
-
-	impl_outer_origin! {
-		pub enum Origin for Test where system = frame_system {}
-	}
-
-	#[derive(Clone, Eq, PartialEq)]
-	pub struct Test;
-	parameter_types! {
-		pub const BlockHashCount: u64 = 250;
-		pub const MaximumBlockWeight: Weight = 1024;
-		pub const MaximumBlockLength: u32 = 2 * 1024;
-		pub const AvailableBlockRatio: Perbill = Perbill::one();
-	}
-	impl frame_system::Trait for Test {
-		type BaseCallFilter = ();
-		type Origin = Origin;
-		type Index = u64;
-		type Call = ();
-		type BlockNumber = u64;
-		type Hash = H256;
-		type Hashing = BlakeTwo256;
-		type AccountId = u64;
-		type Lookup = IdentityLookup<Self::AccountId>;
-		type Header = Header;
-		type Event = ();
-		type BlockHashCount = BlockHashCount;
-		type MaximumBlockWeight = MaximumBlockWeight;
-		type DbWeight = ();
-		type BlockExecutionWeight = ();
-		type ExtrinsicBaseWeight = ();
-		type MaximumExtrinsicWeight = MaximumBlockWeight;
-		type AvailableBlockRatio = AvailableBlockRatio;
-		type MaximumBlockLength = MaximumBlockLength;
-		type Version = ();
-		type PalletInfo = ();
-		type AccountData = ();
-		type OnNewAccount = ();
-		type OnKilledAccount = ();
-		type SystemWeightInfo = ();
-	}
-	impl Trait for Test {
-		type Event = ();
-		type Balance = u64;
-		type AssetId = u32;
-	}
-	type Assets = Module<Test>;
-
-	fn new_test_ext() -> sp_io::TestExternalities {
-		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
-	}
-
-	#[test]
-	fn issuing_asset_units_to_issuer_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-		});
-	}
-
-	#[test]
-	fn querying_total_supply_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 50);
-			assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 31));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 19);
-			assert_eq!(Assets::balance(0, 3), 31);
-			assert_ok!(Assets::destroy(Origin::signed(3), 0));
-			assert_eq!(Assets::total_supply(0), 69);
-		});
-	}
-
-	#[test]
-	fn transferring_amount_above_available_balance_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 50);
-		});
-	}
-
-	#[test]
-	fn transferring_amount_more_than_available_balance_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 50);
-			assert_ok!(Assets::destroy(Origin::signed(1), 0));
-			assert_eq!(Assets::balance(0, 1), 0);
-			assert_noop!(Assets::transfer(Origin::signed(1), 0, 1, 50), Error::<Test>::BalanceLow);
-		});
-	}
-
-	#[test]
-	fn transferring_less_than_one_unit_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 0), Error::<Test>::AmountZero);
-		});
-	}
-
-	#[test]
-	fn transferring_more_units_than_total_supply_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 101), Error::<Test>::BalanceLow);
-		});
-	}
-
-	#[test]
-	fn destroying_asset_balance_with_positive_balance_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::destroy(Origin::signed(1), 0));
-		});
-	}
-
-	#[test]
-	fn destroying_asset_balance_with_zero_balance_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 2), 0);
-			assert_noop!(Assets::destroy(Origin::signed(2), 0), Error::<Test>::BalanceZero);
-		});
-	}
-}
\ No newline at end of file
+use crate::{Error, CurveType, GenesisConfig, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::OnInitialize, BasicExternalities};
+use sp_runtime::DispatchError;
+use sp_core::U256;
+use sp_runtime::{FixedU128, FixedPointNumber};
+use subswap_asset::MultiAsset;
+
+const LPT: u32 = 3;
+
+fn create_usdt_dot_pair() {
+	assert_ok!(Subswap::mint_liquidity(Origin::signed(1), USDT, 1_000_000, DOT, 4_000_000));
+}
+
+#[test]
+fn mint_liquidity_creates_pair() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+
+		assert_eq!(Subswap::pair((USDT, DOT)), Some(LPT));
+		assert_eq!(Subswap::pair((DOT, USDT)), Some(LPT));
+		assert_eq!(Subswap::reserves(LPT), (1_000_000, 4_000_000));
+		assert_eq!(Assets::balance(LPT, &1), 1_999_999);
+		assert_eq!(Assets::balance(LPT, &Subswap::lock_account_id()), 1);
+		assert_eq!(Assets::total_issuance(LPT), 2_000_000);
+		assert_eq!(Assets::balance(USDT, &Subswap::account_id()), 1_000_000);
+		assert_eq!(Assets::balance(DOT, &Subswap::account_id()), 4_000_000);
+	});
+}
+
+#[test]
+fn created_pair_names_its_lp_token() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Subswap::mint_liquidity(Origin::signed(1), USDT, 1_000_000, NATIVE, 4_000_000));
+
+		assert_eq!(Assets::metadata(LPT), (b"SUB-USDT LP".to_vec(), b"SUB-USDT-LP".to_vec(), 12));
+	});
+}
+
+#[test]
+fn mint_liquidity_keeps_reserves_ordered() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_ok!(Subswap::mint_liquidity(Origin::signed(2), DOT, 400, USDT, 100));
+
+		assert_eq!(Subswap::reserves(LPT), (1_000_100, 4_000_400));
+	});
+}
+
+#[test]
+fn mint_liquidity_keeps_excess_of_deposit() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_ok!(Subswap::mint_liquidity(Origin::signed(2), USDT, 100, DOT, 1_000));
+
+		assert_eq!(Subswap::reserves(LPT), (1_000_100, 4_000_400));
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 - 400);
+		assert_eq!(Assets::balance(LPT, &2), 200);
+	});
+}
+
+#[test]
+fn add_liquidity_deposits_in_ratio_of_reserves() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_eq!(Subswap::optimal_amounts(&DOT, &USDT, 4_000, 5_000, 0, 0), Ok((4_000, 1_000)));
+		assert_eq!(Subswap::optimal_amounts(&DOT, &USDT, 4_000, 500, 0, 0), Ok((2_000, 500)));
+
+		assert_ok!(Subswap::add_liquidity(Origin::signed(2), DOT, USDT, 4_000, 5_000, 3_000, 900, 0));
+
+		assert_eq!(Subswap::reserves(LPT), (1_001_000, 4_004_000));
+		assert_eq!(Assets::balance(USDT, &2), 1_000_000_000 - 1_000);
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 - 4_000);
+		assert_eq!(Assets::balance(LPT, &2), 2_000);
+	});
+}
+
+#[test]
+fn add_liquidity_below_minimum_should_not_work() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_noop!(
+			Subswap::add_liquidity(Origin::signed(2), DOT, USDT, 4_000, 5_000, 0, 1_001, 0),
+			Error::<Test>::InsufficientAmountB
+		);
+		assert_noop!(
+			Subswap::add_liquidity(Origin::signed(2), DOT, USDT, 4_000, 500, 2_001, 0, 0),
+			Error::<Test>::InsufficientAmountA
+		);
+		Timestamp::set_timestamp(10);
+		assert_noop!(
+			Subswap::add_liquidity(Origin::signed(2), DOT, USDT, 4_000, 1_000, 0, 0, 9),
+			Error::<Test>::Expired
+		);
+	});
+}
+
+#[test]
+fn remove_liquidity_pays_out_no_less_than_minimum() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_noop!(
+			Subswap::remove_liquidity(Origin::signed(1), DOT, USDT, 999_999, 2_000_000, 0, 0),
+			Error::<Test>::InsufficientAmountA
+		);
+		assert_noop!(
+			Subswap::remove_liquidity(Origin::signed(1), DOT, USDT, 999_999, 0, 500_000, 0),
+			Error::<Test>::InsufficientAmountB
+		);
+
+		assert_ok!(Subswap::remove_liquidity(Origin::signed(1), DOT, USDT, 999_999, 1_999_998, 499_999, 0));
+
+		assert_eq!(Assets::balance(USDT, &1), 1_000_000_000 - 1_000_000 + 499_999);
+		assert_eq!(Assets::balance(DOT, &1), 1_000_000_000 - 4_000_000 + 1_999_998);
+	});
+}
+
+#[test]
+fn zap_in_swaps_optimal_part_and_mints_liquidity() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_noop!(
+			Subswap::zap_in(Origin::signed(2), LPT, USDT, 10_000, 9_960),
+			Error::<Test>::InsufficientLiquidityMinted
+		);
+		assert_noop!(Subswap::zap_in(Origin::signed(2), LPT, NATIVE, 10_000, 0), Error::<Test>::InvalidPair);
+
+		// 4_995 USDT is swapped for 19_821 DOT and the rest deposited along with it
+		assert_ok!(Subswap::zap_in(Origin::signed(2), LPT, USDT, 10_000, 9_959));
+
+		assert_eq!(Subswap::reserves(LPT), (1_010_000, 4_000_000));
+		assert_eq!(Assets::balance(USDT, &2), 1_000_000_000 - 10_000);
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000);
+		assert_eq!(Assets::balance(LPT, &2), 9_959);
+	});
+}
+
+#[test]
+fn zap_out_swaps_share_into_one_asset() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_noop!(
+			Subswap::zap_out(Origin::signed(1), LPT, 999_999, USDT, 749_624),
+			Error::<Test>::InsufficientOutputAmount
+		);
+
+		// 1_999_998 DOT redeemed is swapped for 249_624 USDT
+		assert_ok!(Subswap::zap_out(Origin::signed(1), LPT, 999_999, USDT, 749_623));
+
+		assert_eq!(Subswap::reserves(LPT), (250_377, 4_000_000));
+		assert_eq!(Assets::balance(USDT, &1), 1_000_000_000 - 1_000_000 + 749_623);
+		assert_eq!(Assets::balance(DOT, &1), 1_000_000_000 - 4_000_000);
+	});
+}
+
+#[test]
+fn mint_liquidity_with_identical_assets_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Subswap::mint_liquidity(Origin::signed(1), USDT, 1_000, USDT, 1_000),
+			Error::<Test>::IdenticalIdentifier
+		);
+	});
+}
+
+#[test]
+fn swap_pays_out_of_pool_reserves() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_ok!(Subswap::swap(Origin::signed(2), USDT, 1_000, DOT));
+
+		assert_eq!(Assets::balance(USDT, &2), 1_000_000_000 - 1_000);
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 + 3_984);
+		assert_eq!(Subswap::reserves(LPT), (1_001_000, 4_000_000 - 3_984));
+	});
+}
+
+#[test]
+fn pairs_of_eighteen_decimal_assets_do_not_overflow() {
+	new_test_ext().execute_with(|| {
+		let unit = 1_000_000_000_000_000_000u128;
+		assert_ok!(Assets::mint_into(USDT, &3, 2_000_000_000 * unit));
+		assert_ok!(Assets::mint_into(DOT, &3, 8_000_000_000 * unit));
+		// The product of the deposits is far above `u128::max_value()`
+		assert_ok!(Subswap::mint_liquidity(Origin::signed(3), USDT, 1_000_000_000 * unit, DOT, 4_000_000_000 * unit));
+		assert_eq!(Assets::balance(LPT, &3), 2_000_000_000 * unit - 1);
+
+		assert_ok!(Subswap::swap(Origin::signed(3), USDT, 1_000_000 * unit, DOT));
+		let amount_out = 3_984_027_924_159_612_865_972_625;
+		assert_eq!(Subswap::reserves(LPT), (1_001_000_000 * unit, 4_000_000_000 * unit - amount_out));
+
+		assert_ok!(Subswap::mint_liquidity(Origin::signed(3), USDT, 1_001_000 * unit, DOT, 4_000_000 * unit));
+		assert_ok!(Subswap::burn_liquidity(Origin::signed(3), LPT, 1_000_000_000 * unit));
+	});
+}
+
+#[test]
+fn swap_without_pair_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Subswap::swap(Origin::signed(2), USDT, 1_000, NATIVE), Error::<Test>::InvalidPair);
+	});
+}
+
+#[test]
+fn burn_liquidity_returns_pro_rata_reserves() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_ok!(Subswap::burn_liquidity(Origin::signed(1), LPT, 999_999));
+
+		assert_eq!(Assets::balance(LPT, &1), 1_000_000);
+		assert_eq!(Assets::total_issuance(LPT), 1_000_001);
+		assert_eq!(Assets::balance(USDT, &1), 1_000_000_000 - 1_000_000 + 499_999);
+		assert_eq!(Assets::balance(DOT, &1), 1_000_000_000 - 4_000_000 + 1_999_998);
+		assert_eq!(Subswap::reserves(LPT), (1_000_000 - 499_999, 4_000_000 - 1_999_998));
+	});
+}
+
+fn create_dot_native_pair() {
+	assert_ok!(Subswap::mint_liquidity(Origin::signed(1), DOT, 2_000_000, NATIVE, 2_000_000));
+}
+
+#[test]
+fn swap_along_path_hops_through_every_pair() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		create_dot_native_pair();
+		assert_eq!(Subswap::get_amounts_out(&10_000, &[USDT, DOT, NATIVE]), Ok(vec![10_000, 39_486, 38_607]));
+
+		assert_ok!(Subswap::swap_exact_in_along_path(Origin::signed(2), vec![USDT, DOT, NATIVE], 10_000, 38_607, 0));
+
+		assert_eq!(Assets::balance(USDT, &2), 1_000_000_000 - 10_000);
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000);
+		assert_eq!(Assets::balance(NATIVE, &2), 1_000_000_000 + 38_607);
+		assert_eq!(Subswap::reserves(LPT), (1_010_000, 4_000_000 - 39_486));
+		assert_eq!(Subswap::reserves(LPT + 1), (2_000_000 - 38_607, 2_000_000 + 39_486));
+	});
+}
+
+#[test]
+fn swaps_through_frozen_asset_should_not_work() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		create_dot_native_pair();
+		Assets::freeze_asset(DOT);
+
+		// DOT never leaves the pool account on this route, yet it may not move
+		assert_noop!(
+			Subswap::swap_exact_in_along_path(Origin::signed(2), vec![USDT, DOT, NATIVE], 10_000, 0, 0),
+			DispatchError::Other("AssetFrozen")
+		);
+		// Nor may the other asset of its pair be lent
+		assert_noop!(
+			Subswap::flash_swap(Origin::signed(2), LPT, USDT, 1_000, USDT, 1_010, remark()),
+			DispatchError::Other("AssetFrozen")
+		);
+	});
+}
+
+#[test]
+fn swap_along_path_below_minimum_output_should_not_work() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		create_dot_native_pair();
+		assert_noop!(
+			Subswap::swap_exact_in_along_path(Origin::signed(2), vec![USDT, DOT, NATIVE], 10_000, 38_608, 0),
+			Error::<Test>::InsufficientOutputAmount
+		);
+	});
+}
+
+#[test]
+fn swap_along_path_after_deadline_should_not_work() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		Timestamp::set_timestamp(100);
+		assert_noop!(
+			Subswap::swap_exact_in_along_path(Origin::signed(2), vec![USDT, DOT], 10_000, 0, 99),
+			Error::<Test>::Expired
+		);
+		assert_ok!(Subswap::swap_exact_in_along_path(Origin::signed(2), vec![USDT, DOT], 10_000, 0, 100));
+	});
+}
+
+#[test]
+fn swap_along_invalid_path_should_not_work() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_noop!(
+			Subswap::swap_exact_in_along_path(Origin::signed(2), vec![USDT], 10_000, 0, 0),
+			Error::<Test>::InvalidPath
+		);
+		assert_noop!(
+			Subswap::swap_exact_in_along_path(Origin::signed(2), vec![USDT, DOT, NATIVE], 10_000, 0, 0),
+			Error::<Test>::InvalidPair
+		);
+	});
+}
+
+#[test]
+fn swap_exact_out_charges_rounded_up_input() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_eq!(Subswap::_get_amount_in(&CurveType::ConstantProduct, &3_984, &1_000_000, &4_000_000, 30), Ok(1_000));
+
+		assert_ok!(Subswap::swap_exact_out(Origin::signed(2), vec![USDT, DOT], 3_984, 1_000, 0));
+
+		assert_eq!(Assets::balance(USDT, &2), 1_000_000_000 - 1_000);
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 + 3_984);
+		assert_eq!(Subswap::reserves(LPT), (1_001_000, 4_000_000 - 3_984));
+	});
+}
+
+#[test]
+fn swap_exact_out_along_path_computes_input_backwards() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		create_dot_native_pair();
+		assert_eq!(Subswap::get_amounts_in(&38_607, &[USDT, DOT, NATIVE]), Ok(vec![10_000, 39_486, 38_607]));
+
+		assert_ok!(Subswap::swap_exact_out(Origin::signed(2), vec![USDT, DOT, NATIVE], 38_607, 10_000, 0));
+
+		assert_eq!(Assets::balance(USDT, &2), 1_000_000_000 - 10_000);
+		assert_eq!(Assets::balance(NATIVE, &2), 1_000_000_000 + 38_607);
+		assert_eq!(Subswap::reserves(LPT + 1), (2_000_000 - 38_607, 2_000_000 + 39_486));
+	});
+}
+
+#[test]
+fn swap_exact_out_above_maximum_input_should_not_work() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_noop!(
+			Subswap::swap_exact_out(Origin::signed(2), vec![USDT, DOT], 3_984, 999, 0),
+			Error::<Test>::ExcessiveInputAmount
+		);
+		assert_noop!(
+			Subswap::swap_exact_out(Origin::signed(2), vec![USDT, DOT], 4_000_000, u128::max_value(), 0),
+			Error::<Test>::InsufficientLiquidity
+		);
+	});
+}
+
+#[test]
+fn oracle_accumulates_prices_of_replaced_reserves() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_eq!(Subswap::last_cumulative_price(LPT), (U256::zero(), U256::zero()));
+
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(Subswap::swap(Origin::signed(2), USDT, 1_000, DOT));
+
+		// 1 USDT was worth 4 DOT for 1000 milliseconds
+		assert_eq!(
+			Subswap::last_cumulative_price(LPT),
+			(U256::from(4_000_000_000_000_000_000_000u128), U256::from(250_000_000_000_000_000_000u128))
+		);
+		assert_eq!(Subswap::last_block_timestamp(LPT), 1_000);
+	});
+}
+
+#[test]
+fn oracle_consult_averages_over_window() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		Timestamp::set_timestamp(1_000);
+		assert_eq!(
+			Subswap::consult(&LPT, 1_000),
+			Some((FixedU128::saturating_from_integer(4), FixedU128::saturating_from_rational(1, 4)))
+		);
+
+		assert_ok!(Subswap::swap(Origin::signed(2), USDT, 1_000, DOT));
+		Timestamp::set_timestamp(2_000);
+
+		assert_eq!(
+			Subswap::consult(&LPT, 2_000),
+			Some((FixedU128::from_inner(3_996_011_988_011_988_011), FixedU128::from_inner(250_249_748_749_754_755)))
+		);
+		assert_eq!(Subswap::consult(&LPT, 2_001), None);
+		assert_eq!(Subswap::consult(&LPT, u64::max_value()), None);
+	});
+}
+
+#[test]
+fn oracle_keeps_bounded_snapshots() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		for now in 1..=5 {
+			Timestamp::set_timestamp(now * 500);
+			assert_ok!(Subswap::swap(Origin::signed(2), USDT, 1_000, DOT));
+		}
+
+		let snapshots = Subswap::price_snapshots(LPT);
+		assert_eq!(snapshots.len(), 3);
+		assert_eq!(snapshots.iter().map(|s| s.timestamp).collect::<Vec<_>>(), vec![1_500, 2_000, 2_500]);
+	});
+}
+
+#[test]
+fn pairs_lists_every_pair_once() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		create_dot_native_pair();
+
+		let mut pairs = Subswap::pairs();
+		pairs.sort();
+		assert_eq!(pairs, vec![(LPT, (USDT, DOT)), (LPT + 1, (NATIVE, DOT))]);
+	});
+}
+
+#[test]
+fn lp_share_value_is_pro_rata() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_eq!(Subswap::lp_share_value(&LPT, &999_999), Ok((499_999, 1_999_998)));
+		assert_eq!(Subswap::lp_share_value(&(LPT + 1), &1), Err(Error::<Test>::InvalidPair.into()));
+		assert_eq!(Subswap::lp_share_value(&LPT, &2_000_001), Err(Error::<Test>::InsufficientLiquidity.into()));
+	});
+}
+
+#[test]
+fn create_pair_charges_its_own_fee() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Subswap::create_pair(Origin::signed(1), USDT, 1_000_000, DOT, 4_000_000, 100, CurveType::ConstantProduct));
+		assert_eq!(Subswap::fee(LPT), 100);
+		assert_ok!(Subswap::swap(Origin::signed(2), USDT, 1_000, DOT));
+
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 + 3_956);
+		assert_noop!(
+			Subswap::create_pair(Origin::signed(1), USDT, 1_000, DOT, 1_000, 30, CurveType::ConstantProduct),
+			Error::<Test>::PairExists
+		);
+	});
+}
+
+#[test]
+fn create_pair_with_whole_input_as_fee_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Subswap::create_pair(Origin::signed(1), USDT, 1_000_000, DOT, 4_000_000, 10_000, CurveType::ConstantProduct),
+			Error::<Test>::InvalidFee
+		);
+	});
+}
+
+#[test]
+fn governance_can_change_fee() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_eq!(Subswap::fee(LPT), 30);
+		assert_noop!(Subswap::set_fee(Origin::signed(1), LPT, 100), DispatchError::BadOrigin);
+		assert_noop!(Subswap::set_fee(Origin::root(), LPT + 1, 100), Error::<Test>::InvalidPair);
+
+		assert_ok!(Subswap::set_fee(Origin::root(), LPT, 100));
+		assert_ok!(Subswap::swap(Origin::signed(2), USDT, 1_000, DOT));
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 + 3_956);
+	});
+}
+
+#[test]
+fn protocol_fee_mints_share_of_fee_growth() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Subswap::set_fee_to(Origin::signed(1), Some(9)), DispatchError::BadOrigin);
+		assert_ok!(Subswap::set_fee_to(Origin::root(), Some(9)));
+		create_usdt_dot_pair();
+		assert_eq!(Subswap::k_last(LPT), U256::from(4_000_000_000_000u64));
+
+		assert_ok!(Subswap::swap(Origin::signed(2), USDT, 100_000, DOT));
+		assert_ok!(Subswap::burn_liquidity(Origin::signed(1), LPT, 999_999));
+
+		// One sixth of the growth of sqrt(k) from 2_000_000 to 2_000_272
+		assert_eq!(Assets::balance(LPT, &9), 45);
+		assert_eq!(Subswap::reserves(LPT), (1_100_000 - 549_987, 4_000_000 - 362_644 - 1_818_635));
+		assert_eq!(Subswap::k_last(LPT), U256::from(1_000_320_193_373u64));
+	});
+}
+
+#[test]
+fn protocol_fee_off_mints_nothing() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_ok!(Subswap::swap(Origin::signed(2), USDT, 100_000, DOT));
+		assert_ok!(Subswap::burn_liquidity(Origin::signed(1), LPT, 999_999));
+
+		assert_eq!(Assets::total_issuance(LPT), 1_000_001);
+		assert_eq!(Subswap::k_last(LPT), U256::zero());
+	});
+}
+
+fn create_usdt_dot_stable_pair() {
+	assert_ok!(Subswap::create_pair(Origin::signed(1), USDT, 1_000_000, DOT, 1_000_000, 4, CurveType::StableSwap(100)));
+}
+
+#[test]
+fn stable_pair_mints_its_invariant() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_stable_pair();
+
+		assert_eq!(Subswap::curve(LPT), CurveType::StableSwap(100));
+		assert_eq!(Assets::balance(LPT, &1), 1_999_999);
+		assert_noop!(
+			Subswap::create_pair(Origin::signed(1), USDT, 1_000, NATIVE, 1_000, 4, CurveType::StableSwap(0)),
+			Error::<Test>::InvalidCurve
+		);
+	});
+}
+
+#[test]
+fn stable_pair_swaps_close_to_peg() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_stable_pair();
+		assert_eq!(Subswap::get_amounts_out(&10_000, &[USDT, DOT]), Ok(vec![10_000, 9_995]));
+		assert_eq!(Subswap::get_amounts_in(&9_995, &[USDT, DOT]), Ok(vec![10_001, 9_995]));
+
+		assert_ok!(Subswap::swap(Origin::signed(2), USDT, 10_000, DOT));
+
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 + 9_995);
+		assert_eq!(Subswap::reserves(LPT), (1_010_000, 1_000_000 - 9_995));
+	});
+}
+
+#[test]
+fn stable_pair_mints_liquidity_by_invariant_growth() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_stable_pair();
+		assert_ok!(Subswap::mint_liquidity(Origin::signed(2), USDT, 1_500, DOT, 500));
+
+		// A constant product pair would only mint for the smaller side of the deposit
+		assert_eq!(Assets::balance(LPT, &2), 1_999);
+		assert_eq!(Subswap::reserves(LPT), (1_001_500, 1_000_500));
+	});
+}
+
+fn create_usdt_dot_weighted_pool() {
+	assert_ok!(Subswap::create_weighted_pool(Origin::signed(1), vec![USDT, DOT], vec![800_000, 200_000], vec![80, 20], 30));
+}
+
+#[test]
+fn create_weighted_pool_mints_initial_supply() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_weighted_pool();
+
+		let pool = Subswap::weighted_pool(LPT).unwrap();
+		assert_eq!(pool.assets, vec![USDT, DOT]);
+		assert_eq!(pool.balances, vec![800_000, 200_000]);
+		assert_eq!(pool.total_weight(), 100);
+		assert_eq!(Assets::balance(LPT, &1), crate::weighted::INITIAL_POOL_SUPPLY);
+		assert_eq!(Assets::balance(USDT, &Subswap::account_id()), 800_000);
+		assert_eq!(Assets::balance(DOT, &Subswap::account_id()), 200_000);
+	});
+}
+
+#[test]
+fn create_weighted_pool_with_invalid_assets_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Subswap::create_weighted_pool(Origin::signed(1), vec![USDT], vec![1_000], vec![100], 30),
+			Error::<Test>::InvalidPoolSize
+		);
+		assert_noop!(
+			Subswap::create_weighted_pool(Origin::signed(1), vec![USDT, DOT], vec![1_000], vec![50, 50], 30),
+			Error::<Test>::InvalidPoolSize
+		);
+		assert_noop!(
+			Subswap::create_weighted_pool(Origin::signed(1), vec![USDT, USDT], vec![1_000, 1_000], vec![50, 50], 30),
+			Error::<Test>::IdenticalIdentifier
+		);
+		assert_noop!(
+			Subswap::create_weighted_pool(Origin::signed(1), vec![USDT, DOT], vec![1_000, 1_000], vec![99, 1], 30),
+			Error::<Test>::InvalidWeight
+		);
+	});
+}
+
+#[test]
+fn swap_weighted_prices_by_weights() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_weighted_pool();
+		assert_ok!(Subswap::swap_weighted(Origin::signed(2), LPT, USDT, 10_000, DOT, 9_000));
+
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 + 9_666);
+		assert_eq!(Subswap::weighted_pool(LPT).unwrap().balances, vec![810_000, 200_000 - 9_666]);
+		assert_noop!(
+			Subswap::swap_weighted(Origin::signed(2), LPT, USDT, 10_000, DOT, 10_000),
+			Error::<Test>::InsufficientOutputAmount
+		);
+		assert_noop!(
+			Subswap::swap_weighted(Origin::signed(2), LPT, USDT, 10_000, NATIVE, 0),
+			Error::<Test>::InvalidPool
+		);
+	});
+}
+
+#[test]
+fn join_and_exit_weighted_pool_with_single_asset() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_weighted_pool();
+		assert_ok!(Subswap::join_pool_single(Origin::signed(2), LPT, DOT, 40_000, 0));
+
+		assert_eq!(Assets::balance(LPT, &2), 3_705_430_506_959);
+		assert_eq!(Subswap::weighted_pool(LPT).unwrap().balances, vec![800_000, 240_000]);
+
+		// Both the join and the exit pay the fee on the part swapped to USDT
+		assert_ok!(Subswap::exit_pool_single(Origin::signed(2), LPT, 3_705_430_506_959, DOT, 0));
+		assert_eq!(Assets::balance(LPT, &2), 0);
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 - 40_000 + 39_823);
+	});
+}
+
+#[test]
+fn exit_weighted_pool_with_single_asset_charges_swapped_part() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_weighted_pool();
+		let pool_amount_in = crate::weighted::INITIAL_POOL_SUPPLY / 10;
+		assert_ok!(Subswap::exit_pool_single(Origin::signed(1), LPT, pool_amount_in, USDT, 98_000));
+
+		assert_eq!(Assets::balance(USDT, &1), 1_000_000_000 - 800_000 + 98_657);
+		assert_eq!(Subswap::weighted_pool(LPT).unwrap().balances, vec![800_000 - 98_657, 200_000]);
+	});
+}
+
+#[test]
+fn exit_weighted_pool_is_pro_rata() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_weighted_pool();
+		assert_ok!(Subswap::exit_pool(Origin::signed(1), LPT, crate::weighted::INITIAL_POOL_SUPPLY / 4));
+
+		assert_eq!(Assets::balance(USDT, &1), 1_000_000_000 - 600_000);
+		assert_eq!(Assets::balance(DOT, &1), 1_000_000_000 - 150_000);
+		assert_eq!(Subswap::weighted_pool(LPT).unwrap().balances, vec![600_000, 150_000]);
+	});
+}
+
+#[test]
+fn exit_weighted_pool_of_large_balances_does_not_overflow() {
+	new_test_ext().execute_with(|| {
+		let balance = 1_000_000_000_000_000_000_000_000_000u128;
+		assert_ok!(Assets::mint_into(USDT, &3, balance));
+		assert_ok!(Assets::mint_into(DOT, &3, balance));
+		assert_ok!(Subswap::create_weighted_pool(Origin::signed(3), vec![USDT, DOT], vec![balance, balance], vec![50, 50], 30));
+		// The balances times the supply burned are far above `u128::max_value()`
+		assert_ok!(Subswap::exit_pool(Origin::signed(3), LPT, crate::weighted::INITIAL_POOL_SUPPLY / 4));
+
+		assert_eq!(Subswap::weighted_pool(LPT).unwrap().balances, vec![balance / 4 * 3, balance / 4 * 3]);
+	});
+}
+
+fn create_usdt_dot_concentrated_pool() {
+	// A price of 1 DOT per USDT
+	let sqrt_price = U256::one() << 96;
+	assert_ok!(Subswap::create_concentrated_pool(Origin::signed(1), DOT, USDT, 30, 60, sqrt_price));
+}
+
+#[test]
+fn create_concentrated_pool_orders_assets() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_concentrated_pool();
+
+		let pool = Subswap::concentrated_pool(0).unwrap();
+		assert_eq!((pool.token0, pool.token1), (USDT, DOT));
+		assert_eq!(pool.tick, 0);
+		assert_eq!(pool.liquidity, 0);
+		assert_eq!(Subswap::next_pool_id(), 1);
+		assert_noop!(
+			Subswap::create_concentrated_pool(Origin::signed(1), USDT, DOT, 30, 0, U256::one() << 96),
+			Error::<Test>::InvalidTickSpacing
+		);
+		assert_noop!(
+			Subswap::create_concentrated_pool(Origin::signed(1), USDT, DOT, 30, 60, U256::one()),
+			Error::<Test>::InvalidPrice
+		);
+	});
+}
+
+#[test]
+fn mint_position_deposits_assets_of_its_range() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_concentrated_pool();
+		assert_ok!(Subswap::mint_position(Origin::signed(1), 0, -600, 600, 10_000_000, 300_000, 300_000));
+		// Ranges above or below the current price hold a single asset
+		assert_ok!(Subswap::mint_position(Origin::signed(2), 0, 600, 1_200, 1_000_000, 30_000, 0));
+		assert_ok!(Subswap::mint_position(Origin::signed(2), 0, -1_200, -600, 1_000_000, 0, 30_000));
+
+		assert_eq!(Assets::balance(USDT, &Subswap::account_id()), 295_531 + 28_680);
+		assert_eq!(Assets::balance(DOT, &Subswap::account_id()), 295_531 + 28_680);
+		assert_eq!(Subswap::concentrated_pool(0).unwrap().liquidity, 10_000_000);
+		assert_eq!(Subswap::initialized_ticks(0), vec![-1_200, -600, 600, 1_200]);
+		let position = Subswap::position(0).unwrap();
+		assert_eq!((position.owner, position.liquidity), (1, 10_000_000));
+		assert!(crate::PositionsByOwner::<Test>::contains_key(2, 2));
+		assert_noop!(
+			Subswap::mint_position(Origin::signed(1), 0, -600, 600, 10_000_000, 295_530, 300_000),
+			Error::<Test>::ExcessiveInputAmount
+		);
+		assert_noop!(
+			Subswap::mint_position(Origin::signed(1), 0, -590, 600, 10_000_000, 300_000, 300_000),
+			Error::<Test>::InvalidTick
+		);
+		// Every tick a pool may initialize is taken
+		assert_noop!(
+			Subswap::mint_position(Origin::signed(1), 0, -1_800, -1_200, 1_000_000, 0, 30_000),
+			Error::<Test>::TooManyTicks
+		);
+		assert_ok!(Subswap::mint_position(Origin::signed(1), 0, -1_200, 600, 1_000_000, 30_000, 60_000));
+	});
+}
+
+#[test]
+fn position_accrues_fees_of_swaps_in_range() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_concentrated_pool();
+		assert_ok!(Subswap::mint_position(Origin::signed(1), 0, -600, 600, 10_000_000, 300_000, 300_000));
+		assert_ok!(Subswap::swap_concentrated(Origin::signed(2), 0, USDT, 10_000, 9_900, None, 0));
+
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 + 9_960);
+		assert_eq!(Subswap::concentrated_pool(0).unwrap().tick, -20);
+
+		assert_ok!(Subswap::collect(Origin::signed(1), 0));
+		assert_eq!(Assets::balance(USDT, &1), 1_000_000_000 - 295_531 + 29);
+
+		assert_ok!(Subswap::decrease_liquidity(Origin::signed(1), 0, 10_000_000, 305_000, 285_000));
+		assert_eq!(Subswap::concentrated_pool(0).unwrap().liquidity, 0);
+		assert_eq!(Subswap::initialized_ticks(0), Vec::<i32>::new());
+		assert_noop!(Subswap::burn_position(Origin::signed(1), 0), Error::<Test>::PositionNotEmpty);
+		assert_ok!(Subswap::collect(Origin::signed(1), 0));
+		assert_eq!(Assets::balance(USDT, &1), 1_000_000_000 - 295_531 + 29 + 305_500);
+		assert_eq!(Assets::balance(DOT, &1), 1_000_000_000 - 295_531 + 285_570);
+		assert_ok!(Subswap::burn_position(Origin::signed(1), 0));
+		assert_eq!(Subswap::position(0), None);
+	});
+}
+
+#[test]
+fn swap_concentrated_crosses_initialized_ticks() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_concentrated_pool();
+		assert_ok!(Subswap::mint_position(Origin::signed(1), 0, -600, 600, 10_000_000, 300_000, 300_000));
+		assert_ok!(Subswap::mint_position(Origin::signed(1), 0, -60, 60, 10_000_000, 30_000, 30_000));
+		assert_eq!(Subswap::concentrated_pool(0).unwrap().liquidity, 20_000_000);
+
+		assert_noop!(
+			Subswap::swap_concentrated(Origin::signed(2), 0, USDT, 100_000, 0, None, 0),
+			Error::<Test>::TooManyTicksCrossed
+		);
+		assert_ok!(Subswap::swap_concentrated(Origin::signed(2), 0, USDT, 100_000, 0, None, 1));
+
+		let pool = Subswap::concentrated_pool(0).unwrap();
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 + 99_126);
+		assert_eq!(pool.tick, -139);
+		assert_eq!(pool.liquidity, 10_000_000);
+	});
+}
+
+#[test]
+fn position_can_be_transferred() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_concentrated_pool();
+		assert_ok!(Subswap::mint_position(Origin::signed(1), 0, -600, 600, 10_000_000, 300_000, 300_000));
+		assert_noop!(Subswap::transfer_position(Origin::signed(2), 0, 3), Error::<Test>::NotPositionOwner);
+		assert_ok!(Subswap::transfer_position(Origin::signed(1), 0, 3));
+
+		assert_eq!(Subswap::position(0).unwrap().owner, 3);
+		assert!(!crate::PositionsByOwner::<Test>::contains_key(1, 0));
+		assert!(crate::PositionsByOwner::<Test>::contains_key(3, 0));
+		assert_noop!(
+			Subswap::decrease_liquidity(Origin::signed(1), 0, 1, 0, 0),
+			Error::<Test>::NotPositionOwner
+		);
+		assert_ok!(Subswap::decrease_liquidity(Origin::signed(3), 0, 10_000_000, 0, 0));
+	});
+}
+
+fn create_usdt_dot_mining_pool() {
+	create_usdt_dot_pair();
+	System::set_block_number(1);
+	assert_ok!(Subswap::set_reward_per_block(Origin::root(), 1_000));
+	assert_ok!(Subswap::set_mining_pool(Origin::root(), LPT, 100));
+	assert_ok!(Assets::transfer(LPT, &1, &2, 1_000_000));
+}
+
+#[test]
+fn stakers_share_reward_per_block_by_stake() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_mining_pool();
+		assert_ok!(Subswap::stake_lp(Origin::signed(1), LPT, 1_000));
+		System::set_block_number(11);
+		assert_ok!(Subswap::stake_lp(Origin::signed(2), LPT, 3_000));
+		System::set_block_number(21);
+
+		// 10 blocks of 1_000 to the first staker alone, then 10 blocks shared 1:3
+		assert_eq!(Subswap::pending_rewards(&LPT, &1), 12_500);
+		assert_eq!(Subswap::pending_rewards(&LPT, &2), 7_500);
+		assert_ok!(Subswap::claim_rewards(Origin::signed(1), LPT));
+		assert_ok!(Subswap::claim_rewards(Origin::signed(2), LPT));
+		assert_eq!(Assets::balance(NATIVE, &1), 1_000_000_000 + 12_500);
+		assert_eq!(Assets::balance(NATIVE, &2), 1_000_000_000 + 7_500);
+		assert_eq!(Subswap::pending_rewards(&LPT, &1), 0);
+		assert_eq!(Subswap::mining_pool(LPT).unwrap().total_staked, 4_000);
+	});
+}
+
+#[test]
+fn unstake_lp_returns_stake_with_reward() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_mining_pool();
+		assert_ok!(Subswap::stake_lp(Origin::signed(2), LPT, 1_000));
+		System::set_block_number(5);
+		assert_noop!(Subswap::unstake_lp(Origin::signed(2), LPT, 1_001), Error::<Test>::InsufficientStake);
+		assert_ok!(Subswap::unstake_lp(Origin::signed(2), LPT, 1_000));
+
+		assert_eq!(Assets::balance(LPT, &2), 1_000_000);
+		assert_eq!(Assets::balance(NATIVE, &2), 1_000_000_000 + 4_000);
+		assert_eq!(Subswap::stake(LPT, 2).amount, 0);
+		assert_noop!(Subswap::claim_rewards(Origin::signed(2), LPT), Error::<Test>::InsufficientStake);
+	});
+}
+
+#[test]
+fn allocation_points_split_reward_between_pools() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_mining_pool();
+		assert_ok!(Subswap::mint_liquidity(Origin::signed(1), NATIVE, 1_000_000, USDT, 1_000_000));
+		assert_ok!(Subswap::set_mining_pool(Origin::root(), LPT + 1, 300));
+		assert_eq!(Subswap::total_alloc_point(), 400);
+		assert_ok!(Subswap::stake_lp(Origin::signed(1), LPT, 1_000));
+		assert_ok!(Subswap::stake_lp(Origin::signed(1), LPT + 1, 1_000));
+		System::set_block_number(9);
+
+		assert_eq!(Subswap::pending_rewards(&LPT, &1), 2_000);
+		assert_eq!(Subswap::pending_rewards(&(LPT + 1), &1), 6_000);
+	});
+}
+
+#[test]
+fn mining_pools_require_governance_and_lp_tokens() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_noop!(Subswap::set_reward_per_block(Origin::signed(1), 1_000), DispatchError::BadOrigin);
+		assert_noop!(Subswap::set_mining_pool(Origin::signed(1), LPT, 100), DispatchError::BadOrigin);
+		assert_noop!(Subswap::set_mining_pool(Origin::root(), DOT, 100), Error::<Test>::InvalidPool);
+		assert_noop!(Subswap::stake_lp(Origin::signed(1), LPT, 1_000), Error::<Test>::InvalidMiningPool);
+	});
+}
+
+fn remark() -> Box<Call> {
+	Box::new(Call::System(frame_system::Call::remark(vec![])))
+}
+
+#[test]
+fn flash_loan_repaid_with_fee_works() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_noop!(
+			Subswap::flash_swap(Origin::signed(2), LPT, DOT, 10_000, DOT, 10_030, remark()),
+			Error::<Test>::K
+		);
+		assert_ok!(Subswap::flash_swap(Origin::signed(2), LPT, DOT, 10_000, DOT, 10_031, remark()));
+
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 - 31);
+		assert_eq!(Subswap::reserves(LPT), (1_000_000, 4_000_031));
+		assert!(!Subswap::flash_locked(LPT));
+	});
+}
+
+#[test]
+fn flash_swap_repaid_in_other_asset_works() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_noop!(
+			Subswap::flash_swap(Origin::signed(2), LPT, DOT, 3_985, USDT, 1_000, remark()),
+			Error::<Test>::K
+		);
+		assert_ok!(Subswap::flash_swap(Origin::signed(2), LPT, DOT, 3_984, USDT, 1_000, remark()));
+
+		assert_eq!(Assets::balance(DOT, &2), 1_000_000_000 + 3_984);
+		assert_eq!(Subswap::reserves(LPT), (1_001_000, 4_000_000 - 3_984));
+	});
+}
+
+#[test]
+fn flash_swap_draining_stable_pair_should_not_work() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_stable_pair();
+		// The invariant of the reserves left cannot be solved, which must not pass for keeping it
+		assert_noop!(
+			Subswap::flash_swap(Origin::signed(2), LPT, DOT, 999_999, DOT, 0, remark()),
+			Error::<Test>::K
+		);
+		assert_eq!(Subswap::reserves(LPT), (1_000_000, 1_000_000));
+	});
+}
+
+#[test]
+fn flash_swap_callback_can_trade_other_pairs_only() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		assert_ok!(Subswap::mint_liquidity(Origin::signed(1), NATIVE, 1_000_000, DOT, 1_000_000));
+
+		// Borrow DOT, sell it on the other pair and repay with the proceeds
+		let sell = Box::new(Call::Subswap(crate::Call::swap(DOT, 3_000, NATIVE)));
+		assert_ok!(Subswap::flash_swap(Origin::signed(2), LPT, DOT, 3_000, DOT, 3_010, sell));
+		assert_eq!(Assets::balance(NATIVE, &2), 1_000_000_000 + 2_982);
+
+		let reenter = Box::new(Call::Subswap(crate::Call::swap(USDT, 1_000, DOT)));
+		assert_noop!(
+			Subswap::flash_swap(Origin::signed(2), LPT, DOT, 3_000, DOT, 3_010, reenter),
+			Error::<Test>::Locked
+		);
+	});
+}
+
+fn settle_orders(block: u64) {
+	System::set_block_number(block);
+	Subswap::on_initialize(block);
+}
+
+#[test]
+fn limit_order_holds_input_until_cancelled() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		System::set_block_number(1);
+		assert_ok!(Subswap::place_limit_order(Origin::signed(2), USDT, 10_000, DOT, 50_000, 10));
+		assert_eq!(Assets::balance(USDT, &2), 999_990_000);
+		assert_eq!(Subswap::open_orders(), vec![0]);
+
+		// The pair pays less than 5 DOT per USDT
+		settle_orders(2);
+		assert_eq!(Subswap::order(0).unwrap().remaining, 10_000);
+
+		assert_noop!(Subswap::cancel_order(Origin::signed(3), 0), Error::<Test>::NotOrderOwner);
+		assert_ok!(Subswap::cancel_order(Origin::signed(2), 0));
+		assert_eq!(Assets::balance(USDT, &2), 1_000_000_000);
+		assert_eq!(Subswap::order(0), None);
+		assert!(Subswap::open_orders().is_empty());
+		assert_noop!(Subswap::cancel_order(Origin::signed(2), 0), Error::<Test>::InvalidOrder);
+	});
+}
+
+#[test]
+fn limit_order_fills_when_price_is_met() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		System::set_block_number(1);
+		assert_ok!(Subswap::place_limit_order(Origin::signed(2), USDT, 10_000, DOT, 39_000, 10));
+
+		settle_orders(2);
+		assert_eq!(Assets::balance(DOT, &2), 1_000_039_486);
+		assert_eq!(Subswap::reserves(LPT), (1_010_000, 3_960_514));
+		assert_eq!(Subswap::order(0), None);
+		assert!(Subswap::open_orders().is_empty());
+	});
+}
+
+#[test]
+fn limit_order_fills_partially_and_expires() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		System::set_block_number(1);
+		assert_ok!(Subswap::place_limit_order(Origin::signed(2), USDT, 100_000, DOT, 380_000, 3));
+
+		// Only part of the order keeps the price above 3.8 DOT per USDT
+		settle_orders(2);
+		let order = Subswap::order(0).unwrap();
+		assert_eq!((order.remaining, order.filled_out), (50_379, 188_560));
+		assert_eq!(Assets::balance(DOT, &2), 1_000_188_560);
+
+		settle_orders(3);
+		assert_eq!(Subswap::order(0).unwrap().remaining, 50_379);
+
+		settle_orders(4);
+		assert_eq!(Subswap::order(0), None);
+		assert!(Subswap::open_orders().is_empty());
+		assert_eq!(Assets::balance(USDT, &2), 999_950_379);
+	});
+}
+
+#[test]
+fn limit_orders_are_settled_in_turn() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		System::set_block_number(1);
+		for _ in 0..2 {
+			assert_ok!(Subswap::place_limit_order(Origin::signed(2), USDT, 1_000, DOT, 3_000, 10));
+		}
+		// Two orders at most are open for each account, and three in total
+		assert_noop!(
+			Subswap::place_limit_order(Origin::signed(2), USDT, 1_000, DOT, 3_000, 10),
+			Error::<Test>::TooManyOrders
+		);
+		assert_ok!(Subswap::place_limit_order(Origin::signed(3), USDT, 1_000, DOT, 3_000, 10));
+		assert_noop!(
+			Subswap::place_limit_order(Origin::signed(1), USDT, 1_000, DOT, 3_000, 10),
+			Error::<Test>::TooManyOrders
+		);
+		assert_eq!(Subswap::open_order_count(2), 2);
+
+		// Two orders are settled per block
+		settle_orders(2);
+		assert_eq!(Subswap::open_orders(), vec![2]);
+		settle_orders(3);
+		assert!(Subswap::open_orders().is_empty());
+	});
+}
+
+#[test]
+fn expired_limit_order_stays_open_until_refunded() {
+	new_test_ext().execute_with(|| {
+		create_usdt_dot_pair();
+		System::set_block_number(1);
+		assert_ok!(Subswap::place_limit_order(Origin::signed(2), USDT, 10_000, DOT, 50_000, 2));
+		Assets::freeze_asset(USDT);
+
+		settle_orders(3);
+		assert_eq!(Subswap::order(0).unwrap().remaining, 10_000);
+		assert_eq!(Subswap::open_orders(), vec![0]);
+		assert_eq!(Assets::balance(USDT, &2), 999_990_000);
+
+		Assets::thaw_asset(USDT);
+		settle_orders(4);
+		assert_eq!(Subswap::order(0), None);
+		assert!(Subswap::open_orders().is_empty());
+		assert_eq!(Subswap::open_order_count(2), 0);
+		assert_eq!(Assets::balance(USDT, &2), 1_000_000_000);
+	});
+}
+
+#[test]
+fn limit_order_requires_pair_and_future_expiry() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		assert_noop!(
+			Subswap::place_limit_order(Origin::signed(2), USDT, 1_000, DOT, 3_000, 10),
+			Error::<Test>::InvalidPair
+		);
+		create_usdt_dot_pair();
+		assert_noop!(
+			Subswap::place_limit_order(Origin::signed(2), USDT, 1_000, DOT, 3_000, 4),
+			Error::<Test>::Expired
+		);
+		assert_noop!(
+			Subswap::place_limit_order(Origin::signed(2), USDT, 100_000_000_000, DOT, 3_000, 10),
+			DispatchError::Other("BalanceLow")
+		);
+	});
+}
+
+#[test]
+fn genesis_seeds_pairs_from_providers() {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	BasicExternalities::execute_with_storage(&mut t, || {
+		assert_eq!(Assets::issue_from_system(0), Ok(USDT));
+		for who in &[1u64, 2] {
+			Assets::mint_into(NATIVE, who, 1_000_000).unwrap();
+			Assets::mint_into(USDT, who, 1_000_000).unwrap();
+		}
+	});
+	GenesisConfig::<Test> {
+		pairs: vec![(NATIVE, USDT, vec![(1, 100_000, 200_000), (2, 50_000, 100_000)])],
+	}.assimilate_storage(&mut t).unwrap();
+
+	sp_io::TestExternalities::from(t).execute_with(|| {
+		let lpt = Subswap::pair((NATIVE, USDT)).unwrap();
+		assert_eq!(Subswap::reserves(lpt), (150_000, 300_000));
+		assert_eq!(Assets::balance(lpt, &1), 141_420);
+		assert_eq!(Assets::balance(lpt, &2), 70_710);
+		assert_eq!(Assets::balance(USDT, &2), 900_000);
+		assert_eq!(Assets::balance(USDT, &Subswap::account_id()), 300_000);
+	});
+}
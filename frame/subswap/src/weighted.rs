@@ -0,0 +1,361 @@
+// This file is part of Substrate.
+
+// Copyright (C) Hyungsuk Kang
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weighted pools of several assets keeping the weighted product invariant of Balancer,
+//! `Π balance_i ^ weight_i = k`, where the weights are normalized by their sum.
+//!
+//! The math follows the Balancer whitepaper on `FixedU128`. Amounts are converted to `u128` and
+//! every function returns `None` on overflow or when the pool cannot satisfy the request.
+
+use codec::{Encode, Decode};
+use sp_runtime::{RuntimeDebug, FixedU128, FixedPointNumber};
+use sp_runtime::traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedSub, CheckedMul, CheckedDiv, One, Zero};
+use sp_std::convert::{TryFrom, TryInto};
+use sp_std::prelude::*;
+use crate::FEE_DENOMINATOR;
+
+/// The smallest number of assets in a weighted pool.
+pub const MIN_ASSETS: usize = 2;
+
+/// The largest number of assets in a weighted pool.
+pub const MAX_ASSETS: usize = 8;
+
+/// The largest weight of an asset, before normalization.
+pub const MAX_WEIGHT: u32 = 1_000_000;
+
+/// Every asset must weigh at least `1 / MIN_WEIGHT_FRACTION` of the pool, which bounds the
+/// exponents of the swap math.
+pub const MIN_WEIGHT_FRACTION: u32 = 50;
+
+/// The liquidity provider token minted for the initial deposit of a pool.
+pub const INITIAL_POOL_SUPPLY: u128 = 100_000_000_000_000;
+
+/// The deposit of a swap or a single asset join may be at most `1 / MAX_IN_RATIO` of the
+/// balance of the asset in the pool.
+pub const MAX_IN_RATIO: u32 = 2;
+
+/// The withdrawal of a swap or a single asset exit may be at most `1 / MAX_OUT_RATIO` of the
+/// balance of the asset in the pool.
+pub const MAX_OUT_RATIO: u32 = 3;
+
+/// The error under which the series approximating a fractional power stops.
+const POW_PRECISION: u128 = 100_000_000;
+
+/// The number of terms after which the series approximating a fractional power gives up.
+const MAX_ITERATIONS: u32 = 255;
+
+/// A pool of several assets sharing one liquidity provider token.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, Default)]
+pub struct WeightedPool<AssetId, Balance> {
+	/// The assets of the pool.
+	pub assets: Vec<AssetId>,
+	/// The weight of each asset, normalized by their sum.
+	pub weights: Vec<u32>,
+	/// The balance of each asset held by the pool.
+	pub balances: Vec<Balance>,
+	/// The swap fee of the pool in basis points.
+	pub fee: u32,
+}
+
+impl<AssetId: PartialEq, Balance> WeightedPool<AssetId, Balance> {
+	/// The position of `asset` in the pool.
+	pub fn index_of(&self, asset: &AssetId) -> Option<usize> {
+		self.assets.iter().position(|a| a == asset)
+	}
+
+	/// The sum of the weights of the assets.
+	pub fn total_weight(&self) -> u32 {
+		self.weights.iter().sum()
+	}
+}
+
+/// Whether every weight is within `MAX_WEIGHT` and weighs at least `1 / MIN_WEIGHT_FRACTION`
+/// of their sum.
+pub fn valid_weights(weights: &[u32]) -> bool {
+	if weights.iter().any(|weight| *weight == 0 || *weight > MAX_WEIGHT) {
+		return false;
+	}
+	let total: u64 = weights.iter().map(|weight| u64::from(*weight)).sum();
+	weights.iter().all(|weight| u64::from(*weight) * u64::from(MIN_WEIGHT_FRACTION) >= total)
+}
+
+/// The output amount of swapping `amount_in`, rounded down.
+///
+/// `amount_out = balance_out * (1 - (balance_in / (balance_in + amount_in * (1 - fee))) ^ (weight_in / weight_out))`
+pub fn amount_out<Balance: AtLeast32BitUnsigned + Copy>(
+	amount_in: Balance,
+	balance_in: Balance,
+	weight_in: u32,
+	balance_out: Balance,
+	weight_out: u32,
+	fee: u32,
+) -> Option<Balance> {
+	let (amount_in, balance_in, balance_out) = (to_u128(amount_in)?, to_u128(balance_in)?, to_u128(balance_out)?);
+	if amount_in > balance_in / u128::from(MAX_IN_RATIO) {
+		return None;
+	}
+	let weight_ratio = FixedU128::checked_from_rational(weight_in, weight_out)?;
+	let adjusted_in = fee_complement(fee)?.checked_mul_int(amount_in)?;
+	let base = FixedU128::checked_from_rational(balance_in, balance_in.checked_add(adjusted_in)?)?;
+	let ratio = FixedU128::one().checked_sub(&pow(base, weight_ratio)?)?;
+	let amount_out = ratio.checked_mul_int(balance_out)?;
+	if amount_out > balance_out / u128::from(MAX_OUT_RATIO) {
+		return None;
+	}
+	from_u128(amount_out)
+}
+
+/// The input amount required to swap for `amount_out`, rounded up.
+///
+/// `amount_in = balance_in * ((balance_out / (balance_out - amount_out)) ^ (weight_out / weight_in) - 1) / (1 - fee)`
+pub fn amount_in<Balance: AtLeast32BitUnsigned + Copy>(
+	amount_out: Balance,
+	balance_in: Balance,
+	weight_in: u32,
+	balance_out: Balance,
+	weight_out: u32,
+	fee: u32,
+) -> Option<Balance> {
+	let (amount_out, balance_in, balance_out) = (to_u128(amount_out)?, to_u128(balance_in)?, to_u128(balance_out)?);
+	if amount_out > balance_out / u128::from(MAX_OUT_RATIO) {
+		return None;
+	}
+	let weight_ratio = FixedU128::checked_from_rational(weight_out, weight_in)?;
+	let base = FixedU128::checked_from_rational(balance_out, balance_out.checked_sub(amount_out)?)?;
+	let ratio = pow(base, weight_ratio)?.checked_sub(&FixedU128::one())?;
+	let amount_in = ratio.checked_mul_int(balance_in)?
+		.checked_mul(u128::from(FEE_DENOMINATOR))?
+		.checked_div(u128::from(FEE_DENOMINATOR.checked_sub(fee)?))?
+		.checked_add(1)?;
+	if amount_in > balance_in / u128::from(MAX_IN_RATIO) {
+		return None;
+	}
+	from_u128(amount_in)
+}
+
+/// The liquidity provider token minted for depositing `amount_in` of a single asset, rounded down.
+///
+/// The part of the deposit which the pool would swap to the other assets to keep their weights
+/// pays the swap fee.
+pub fn pool_out_given_single_in<Balance: AtLeast32BitUnsigned + Copy>(
+	amount_in: Balance,
+	balance_in: Balance,
+	weight_in: u32,
+	total_weight: u32,
+	pool_supply: Balance,
+	fee: u32,
+) -> Option<Balance> {
+	let (amount_in, balance_in, pool_supply) = (to_u128(amount_in)?, to_u128(balance_in)?, to_u128(pool_supply)?);
+	if amount_in > balance_in / u128::from(MAX_IN_RATIO) {
+		return None;
+	}
+	let normalized_weight = FixedU128::checked_from_rational(weight_in, total_weight)?;
+	let adjusted_in = swapped_fee_complement(normalized_weight, fee)?.checked_mul_int(amount_in)?;
+	let balance_ratio = FixedU128::checked_from_rational(balance_in.checked_add(adjusted_in)?, balance_in)?;
+	let pool_ratio = pow(balance_ratio, normalized_weight)?;
+	let new_supply = pool_ratio.checked_mul_int(pool_supply)?;
+	from_u128(new_supply.checked_sub(pool_supply)?)
+}
+
+/// The amount of a single asset redeemed by burning `pool_amount_in` of liquidity provider token,
+/// rounded down.
+///
+/// The part of the withdrawal which the pool would swap from the other assets to keep their
+/// weights pays the swap fee.
+pub fn single_out_given_pool_in<Balance: AtLeast32BitUnsigned + Copy>(
+	pool_amount_in: Balance,
+	balance_out: Balance,
+	weight_out: u32,
+	total_weight: u32,
+	pool_supply: Balance,
+	fee: u32,
+) -> Option<Balance> {
+	let (pool_amount_in, balance_out, pool_supply) = (to_u128(pool_amount_in)?, to_u128(balance_out)?, to_u128(pool_supply)?);
+	let normalized_weight = FixedU128::checked_from_rational(weight_out, total_weight)?;
+	let pool_ratio = FixedU128::checked_from_rational(pool_supply.checked_sub(pool_amount_in)?, pool_supply)?;
+	let balance_ratio = pow(pool_ratio, normalized_weight.reciprocal()?)?;
+	// Round the remaining balance up so that the withdrawal rounds down
+	let new_balance_out = balance_ratio.checked_mul_int(balance_out)?.checked_add(1)?;
+	let amount_out_before_fee = balance_out.saturating_sub(new_balance_out);
+	let amount_out = swapped_fee_complement(normalized_weight, fee)?.checked_mul_int(amount_out_before_fee)?;
+	if amount_out > balance_out / u128::from(MAX_OUT_RATIO) {
+		return None;
+	}
+	from_u128(amount_out)
+}
+
+/// `base ^ exp` for `0 < base < 2`.
+///
+/// The integer part of `exp` is raised exactly, and the fractional part is approximated with
+/// the binomial series of `(1 + x) ^ a`.
+pub fn pow(base: FixedU128, exp: FixedU128) -> Option<FixedU128> {
+	let two = FixedU128::saturating_from_integer(2);
+	if base.is_zero() || base >= two {
+		return None;
+	}
+	let whole = exp.trunc();
+	let remain = exp.checked_sub(&whole)?;
+	let whole_pow = pow_int(base, whole.into_inner() / FixedU128::accuracy())?;
+	if remain.is_zero() {
+		return Some(whole_pow);
+	}
+	whole_pow.checked_mul(&pow_approx(base, remain)?)
+}
+
+/// `base ^ exp` by repeated squaring, failing on overflow.
+fn pow_int(base: FixedU128, exp: u128) -> Option<FixedU128> {
+	let mut result = FixedU128::one();
+	let mut square = base;
+	let mut exp = exp;
+	while exp > 0 {
+		if exp & 1 == 1 {
+			result = result.checked_mul(&square)?;
+		}
+		exp >>= 1;
+		if exp > 0 {
+			square = square.checked_mul(&square)?;
+		}
+	}
+	Some(result)
+}
+
+/// `base ^ exp` for `0 <= exp < 1` with the binomial series
+/// `(1 + x) ^ a = 1 + a x + a (a - 1) x² / 2! + ...`, where `x = base - 1`.
+///
+/// Every term is kept as a magnitude and a sign, since `FixedU128` is unsigned.
+fn pow_approx(base: FixedU128, exp: FixedU128) -> Option<FixedU128> {
+	let one = FixedU128::one();
+	let (x, x_negative) = if base >= one {
+		(base.checked_sub(&one)?, false)
+	} else {
+		(one.checked_sub(&base)?, true)
+	};
+	let precision = FixedU128::from_inner(POW_PRECISION);
+	let mut term = one;
+	let mut sum = one;
+	let mut negative = false;
+	for i in 1..=MAX_ITERATIONS {
+		let k = FixedU128::saturating_from_integer(i);
+		let k_minus_one = k.checked_sub(&one)?;
+		// The coefficient `a - (k - 1)` of the `k`th term
+		let (c, c_negative) = if exp >= k_minus_one {
+			(exp.checked_sub(&k_minus_one)?, false)
+		} else {
+			(k_minus_one.checked_sub(&exp)?, true)
+		};
+		term = term.checked_mul(&c.checked_mul(&x)?)?.checked_div(&k)?;
+		if term.is_zero() {
+			break;
+		}
+		if x_negative {
+			negative = !negative;
+		}
+		if c_negative {
+			negative = !negative;
+		}
+		sum = if negative { sum.checked_sub(&term)? } else { sum.checked_add(&term)? };
+		if term < precision {
+			break;
+		}
+	}
+	Some(sum)
+}
+
+/// `1 - fee`.
+fn fee_complement(fee: u32) -> Option<FixedU128> {
+	FixedU128::checked_from_rational(FEE_DENOMINATOR.checked_sub(fee)?, FEE_DENOMINATOR)
+}
+
+/// `1 - (1 - normalized_weight) * fee`, the share of a single asset deposit or withdrawal left
+/// after the fee on the part swapped to or from the other assets.
+fn swapped_fee_complement(normalized_weight: FixedU128, fee: u32) -> Option<FixedU128> {
+	let swapped = FixedU128::one().checked_sub(&normalized_weight)?;
+	let charged = swapped.checked_mul(&FixedU128::checked_from_rational(fee, FEE_DENOMINATOR)?)?;
+	FixedU128::one().checked_sub(&charged)
+}
+
+fn to_u128<Balance: TryInto<u128>>(balance: Balance) -> Option<u128> {
+	balance.try_into().ok()
+}
+
+fn from_u128<Balance: TryFrom<u128>>(value: u128) -> Option<Balance> {
+	Balance::try_from(value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_close(actual: FixedU128, expected: FixedU128) {
+		let error = if actual > expected { actual - expected } else { expected - actual };
+		assert!(error <= FixedU128::from_inner(1_000_000_000), "{:?} != {:?}", actual, expected);
+	}
+
+	#[test]
+	fn pow_raises_integer_exponents_exactly() {
+		let base = FixedU128::saturating_from_rational(3, 2);
+		assert_eq!(pow(base, FixedU128::saturating_from_integer(3)), Some(FixedU128::saturating_from_rational(27, 8)));
+		assert_eq!(pow(base, FixedU128::zero()), Some(FixedU128::one()));
+	}
+
+	#[test]
+	fn pow_approximates_fractional_exponents() {
+		// 0.25 ^ 0.5 = 0.5
+		let root = pow(FixedU128::saturating_from_rational(1, 4), FixedU128::saturating_from_rational(1, 2)).unwrap();
+		assert_close(root, FixedU128::saturating_from_rational(1, 2));
+		// 1.44 ^ 1.5 = 1.728
+		let power = pow(FixedU128::saturating_from_rational(144, 100), FixedU128::saturating_from_rational(3, 2)).unwrap();
+		assert_close(power, FixedU128::saturating_from_rational(1_728, 1_000));
+	}
+
+	#[test]
+	fn pow_rejects_bases_out_of_range() {
+		assert_eq!(pow(FixedU128::zero(), FixedU128::one()), None);
+		assert_eq!(pow(FixedU128::saturating_from_integer(2), FixedU128::one()), None);
+	}
+
+	#[test]
+	fn equal_weights_swap_like_constant_product() {
+		// Without fee, 1_000 in against 1_000_000 / 4_000_000 gives 4_000_000 * 1_000 / 1_001_000
+		assert_eq!(amount_out(1_000u128, 1_000_000, 50, 4_000_000, 50, 0), Some(3_996));
+		assert_eq!(amount_in(3_996u128, 1_000_000, 50, 4_000_000, 50, 0), Some(1_000));
+	}
+
+	#[test]
+	fn swaps_are_bounded_by_ratios() {
+		assert_eq!(amount_out(500_001u128, 1_000_000, 50, 1_000_000, 50, 0), None);
+		assert_eq!(amount_in(333_334u128, 1_000_000, 50, 1_000_000, 50, 0), None);
+	}
+
+	#[test]
+	fn weights_are_validated() {
+		assert!(valid_weights(&[80, 20]));
+		assert!(valid_weights(&[1, 49]));
+		assert!(!valid_weights(&[1, 50]));
+		assert!(!valid_weights(&[0, 50]));
+		assert!(!valid_weights(&[MAX_WEIGHT + 1, MAX_WEIGHT]));
+	}
+
+	#[test]
+	fn single_asset_join_and_exit_round_trip_below_deposit() {
+		let supply = INITIAL_POOL_SUPPLY;
+		let minted = pool_out_given_single_in(100_000u128, 1_000_000, 80, 100, supply, 30).unwrap();
+		let redeemed = single_out_given_pool_in(minted, 1_100_000, 80, 100, supply + minted, 30).unwrap();
+		assert!(redeemed < 100_000);
+		assert!(redeemed > 99_000);
+	}
+}